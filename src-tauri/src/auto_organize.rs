@@ -0,0 +1,721 @@
+//! Auto-Organize Module
+//!
+//! Background organization of new documents (per doc 08).
+//! The watcher keeps the index current; this service periodically picks up
+//! newly discovered files in the watch folders, classifies them and builds
+//! an organization plan for them:
+//! - Conservative: the plan is saved as suggestions and waits for approval
+//! - Automatic: files at or above the confidence threshold are moved right
+//!   away, the rest go to Review
+//! - Scheduled: same as Automatic, but once a day at the configured time
+//!
+//! Every move runs through `execute_plan_items`, so each run is an activity
+//! log session that the existing undo commands can roll back.
+
+use crate::ai::{AIClient, AIConfig, FileForClassification};
+use crate::commands::{self, ExecutionResult, OrganizationStyle};
+use crate::path_identity::PathIdentity;
+use chrono::{NaiveDateTime, NaiveTime};
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// How often the service looks for new files (doc 08 default processing delay)
+pub const CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Maximum files classified per AI request
+const CLASSIFY_BATCH_SIZE: usize = 20;
+
+/// organization_style prefix for plans created by the service
+pub const AUTO_PLAN_STYLE_PREFIX: &str = "auto_";
+
+/// Activity log session mode for moves made by the service
+pub const AUTO_SESSION_MODE: &str = "auto_organize";
+
+/// Auto-organize mode (per doc 08 "Mode Comparison")
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AutoOrganizeMode {
+    #[default]
+    Conservative,
+    Automatic,
+    Scheduled,
+}
+
+impl AutoOrganizeMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AutoOrganizeMode::Conservative => "conservative",
+            AutoOrganizeMode::Automatic => "automatic",
+            AutoOrganizeMode::Scheduled => "scheduled",
+        }
+    }
+}
+
+/// Auto-organize settings (subset of the doc 08 configuration schema)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AutoOrganizeConfig {
+    pub enabled: bool,
+    pub mode: AutoOrganizeMode,
+    pub watch_folders: Vec<String>,
+    pub file_types: Vec<String>,
+    /// Defaults to Documents/Organized Files
+    pub destination_root: Option<String>,
+    pub confidence_threshold: f64,
+    /// Local time ("HH:MM") at which Scheduled mode runs its daily batch
+    pub schedule_time: String,
+}
+
+impl Default for AutoOrganizeConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            mode: AutoOrganizeMode::Conservative,
+            watch_folders: [dirs::download_dir(), dirs::desktop_dir()]
+                .into_iter()
+                .flatten()
+                .map(|p| p.to_string_lossy().to_string())
+                .collect(),
+            file_types: ["pdf", "doc", "docx", "txt"].iter().map(|s| s.to_string()).collect(),
+            destination_root: None,
+            confidence_threshold: 0.75,
+            schedule_time: "18:00".to_string(),
+        }
+    }
+}
+
+/// Service status reported to the UI
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AutoOrganizeStatus {
+    pub running: bool,
+    pub mode: AutoOrganizeMode,
+    pub last_run_at: Option<String>,
+    pub last_session_id: Option<String>,
+    pub files_moved: usize,
+    pub files_suggested: usize,
+    pub last_error: Option<String>,
+}
+
+/// Outcome of one auto-organize run
+#[derive(Debug, Clone, Default)]
+pub struct CycleResult {
+    pub plan_id: Option<String>,
+    pub files_suggested: usize,
+    pub execution: Option<ExecutionResult>,
+}
+
+/// Service timestamps saved in auto_organize_state, so a restarted service
+/// picks up files that arrived while it was stopped
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct AutoOrganizeProgress {
+    /// Last run that built a plan (RFC 3339)
+    pub last_run_at: Option<String>,
+    /// Files discovered before this point have been handled (files.discovered_at format)
+    pub last_processed_at: Option<String>,
+}
+
+/// A pending suggestion from Conservative mode
+#[derive(Debug, Clone, Serialize)]
+pub struct AutoSuggestion {
+    pub plan_id: String,
+    pub file_id: i64,
+    pub source_path: String,
+    pub destination_path: String,
+    pub confidence: f64,
+    pub reason: String,
+}
+
+/// Tauri-managed handle to the running service (None when stopped)
+#[derive(Default)]
+pub struct AutoOrganizeState(pub Mutex<Option<AutoOrganizeService>>);
+
+/// Whether Scheduled mode should run now: the configured time has passed today
+/// and today's batch has not run yet
+pub fn is_schedule_due(schedule_time: &str, last_run: Option<NaiveDateTime>, now: NaiveDateTime) -> bool {
+    let Ok(time) = NaiveTime::parse_from_str(schedule_time, "%H:%M") else {
+        return false;
+    };
+    let due_at = now.date().and_time(time);
+    now >= due_at && last_run.is_none_or(|last| last < due_at)
+}
+
+/// Whether `path` is inside one of the watch folders
+fn in_watch_folders(path: &str, watch_folders: &[String], identity: &PathIdentity) -> bool {
+    watch_folders.iter().any(|folder| identity.is_at_or_under(path, folder))
+}
+
+/// Newly discovered files the service has not handled yet: inside a watch folder,
+/// of a configured type, not already organized and not part of an earlier auto plan
+pub fn find_candidates(conn: &Connection, config: &AutoOrganizeConfig, since: &str) -> Result<Vec<i64>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT f.id, f.path, f.extension FROM files f
+             WHERE f.discovered_at >= ?1
+               AND NOT EXISTS (
+                   SELECT 1 FROM plan_items pi
+                   JOIN organization_plans p ON p.id = pi.plan_id
+                   WHERE pi.file_id = f.id AND p.organization_style LIKE ?2
+               )",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let rows = stmt
+        .query_map(params![since, format!("{}%", AUTO_PLAN_STYLE_PREFIX)], |row| {
+            Ok((
+                row.get::<_, i64>(0)?,
+                row.get::<_, String>(1)?,
+                row.get::<_, Option<String>>(2)?,
+            ))
+        })
+        .map_err(|e| e.to_string())?;
+
+    let folders: Vec<PathBuf> = config.watch_folders.iter().map(PathBuf::from).collect();
+    let identity = PathIdentity::new(&folders);
+
+    Ok(rows
+        .filter_map(|r| r.ok())
+        .filter(|(_, path, _)| !path.to_lowercase().contains("organized files"))
+        .filter(|(_, path, _)| in_watch_folders(path, &config.watch_folders, &identity))
+        .filter(|(_, _, ext)| {
+            let ext = ext.as_deref().unwrap_or_default();
+            config.file_types.iter().any(|t| t.eq_ignore_ascii_case(ext))
+        })
+        .map(|(id, _, _)| id)
+        .collect())
+}
+
+/// Saved service progress (empty before the first run)
+pub fn load_progress(conn: &Connection) -> Result<AutoOrganizeProgress, String> {
+    conn.query_row(
+        "SELECT last_run_at, last_processed_at FROM auto_organize_state WHERE id = 1",
+        [],
+        |row| Ok(AutoOrganizeProgress { last_run_at: row.get(0)?, last_processed_at: row.get(1)? }),
+    )
+    .optional()
+    .map(Option::unwrap_or_default)
+    .map_err(|e| e.to_string())
+}
+
+pub fn save_progress(conn: &Connection, progress: &AutoOrganizeProgress) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO auto_organize_state (id, last_run_at, last_processed_at) VALUES (1, ?1, ?2)
+         ON CONFLICT(id) DO UPDATE SET last_run_at = ?1, last_processed_at = ?2",
+        params![progress.last_run_at, progress.last_processed_at],
+    )
+    .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+/// Candidates without an AI classification yet
+fn load_unclassified(conn: &Connection, file_ids: &[i64]) -> Result<Vec<FileForClassification>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT f.id, f.filename, f.extension, f.size, f.created_at, f.modified_at, cs.snippet
+             FROM files f
+             LEFT JOIN content_snippets cs ON f.id = cs.file_id
+             LEFT JOIN ai_metadata m ON f.id = m.file_id
+             WHERE f.id = ?1 AND m.file_id IS NULL",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let mut files = Vec::new();
+    for file_id in file_ids {
+        if let Ok(file) = stmt.query_row([file_id], |row| {
+            Ok(FileForClassification {
+                id: row.get(0)?,
+                filename: row.get(1)?,
+                extension: row.get(2)?,
+                size: row.get(3)?,
+                created_at: row.get(4)?,
+                modified_at: row.get(5)?,
+                snippet: row.get(6)?,
+            })
+        }) {
+            files.push(file);
+        }
+    }
+    Ok(files)
+}
+
+/// Build, save and (outside Conservative mode) execute a plan for the candidates.
+/// Classification must already have happened; unclassified files fall back to
+/// the rule-based categories used by `generate_organization_plan`.
+pub fn organize_candidates(
+    conn: &Connection,
+    config: &AutoOrganizeConfig,
+    file_ids: &[i64],
+) -> Result<CycleResult, String> {
+    if file_ids.is_empty() {
+        return Ok(CycleResult::default());
+    }
+
+    let organize_base = config
+        .destination_root
+        .clone()
+        .unwrap_or_else(commands::default_organize_base);
+    let only: HashSet<i64> = file_ids.iter().copied().collect();
+    let mut plan = commands::build_organization_plan(conn, &OrganizationStyle::Simple, &organize_base, "moderate", Some(&only))?;
+    plan.name = format!("Auto-Organize ({})", config.mode.as_str());
+
+    if config.mode != AutoOrganizeMode::Conservative {
+        // Below threshold → Review (doc 08 "Automatic Mode")
+        let review_dir = PathBuf::from(&organize_base).join("Review");
+        for item in plan.items.iter_mut().filter(|i| i.confidence < config.confidence_threshold) {
+            if let Some(filename) = Path::new(&item.source_path).file_name() {
+                item.destination_path = review_dir.join(filename).to_string_lossy().to_string();
+            }
+            item.reason = format!(
+                "{} (below {:.0}% auto-organize threshold)",
+                item.reason,
+                config.confidence_threshold * 100.0
+            );
+            item.requires_review = true;
+        }
+    }
+
+    let style = format!("{}{}", AUTO_PLAN_STYLE_PREFIX, config.mode.as_str());
+    commands::save_organization_plan(conn, &plan, &style)?;
+
+    let execution = if config.mode == AutoOrganizeMode::Conservative {
+        None
+    } else {
+        Some(commands::execute_plan_items(conn, &plan.id, &HashSet::new(), false, AUTO_SESSION_MODE)?)
+    };
+
+    Ok(CycleResult {
+        files_suggested: if execution.is_none() { plan.items.len() } else { 0 },
+        plan_id: Some(plan.id),
+        execution,
+    })
+}
+
+/// One service run: find new files, classify them, then organize them per mode.
+/// Database and filesystem work runs on the blocking pool; only the AI requests
+/// are awaited on the async runtime.
+async fn run_cycle(db_path: &Path, config: &AutoOrganizeConfig, since: &str) -> Result<CycleResult, String> {
+    let (candidates, unclassified) = {
+        let (db_path, config, since) = (db_path.to_path_buf(), config.clone(), since.to_string());
+        run_blocking(move || {
+            let conn = crate::db::open_connection(&db_path).map_err(|e| e.to_string())?;
+            let candidates = find_candidates(&conn, &config, &since)?;
+            let unclassified = load_unclassified(&conn, &candidates)?;
+            Ok((candidates, unclassified))
+        })
+        .await?
+    };
+
+    if candidates.is_empty() {
+        return Ok(CycleResult::default());
+    }
+
+    // Without an API key, files are still organized by the rule-based fallback
+    let mut classify_error = None;
+    if !unclassified.is_empty() {
+        match AIConfig::from_env().and_then(AIClient::new) {
+            Ok(client) => {
+                for batch in unclassified.chunks(CLASSIFY_BATCH_SIZE) {
+                    match client.classify_files(batch.to_vec()).await {
+                        Ok(result) => {
+                            let db_path = db_path.to_path_buf();
+                            run_blocking(move || {
+                                let conn = crate::db::open_connection(&db_path).map_err(|e| e.to_string())?;
+                                commands::store_classifications(&conn, &result.classifications)
+                            })
+                            .await?;
+                        }
+                        Err(e) => classify_error = Some(e),
+                    }
+                }
+            }
+            Err(e) => classify_error = Some(e),
+        }
+    }
+
+    let result = {
+        let (db_path, config) = (db_path.to_path_buf(), config.clone());
+        run_blocking(move || {
+            let conn = crate::db::open_connection(&db_path).map_err(|e| e.to_string())?;
+            organize_candidates(&conn, &config, &candidates)
+        })
+        .await?
+    };
+    match classify_error {
+        Some(e) if result.plan_id.is_none() => Err(e),
+        _ => Ok(result),
+    }
+}
+
+/// The current time in the format of files.discovered_at (SQLite CURRENT_TIMESTAMP, UTC)
+fn discovered_at_now() -> String {
+    chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string()
+}
+
+/// Run synchronous database/filesystem work on the blocking thread pool
+async fn run_blocking<T, F>(work: F) -> Result<T, String>
+where
+    T: Send + 'static,
+    F: FnOnce() -> Result<T, String> + Send + 'static,
+{
+    tauri::async_runtime::spawn_blocking(work)
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// The running background service
+pub struct AutoOrganizeService {
+    task: tauri::async_runtime::JoinHandle<()>,
+    status: Arc<Mutex<AutoOrganizeStatus>>,
+}
+
+impl AutoOrganizeService {
+    /// Start the service loop on the app's async runtime.
+    /// Picks up from the saved progress; on the very first start only files
+    /// discovered after this point are handled.
+    pub fn start(db_path: PathBuf, config: AutoOrganizeConfig) -> Self {
+        let status = Arc::new(Mutex::new(AutoOrganizeStatus {
+            running: true,
+            mode: config.mode,
+            ..Default::default()
+        }));
+        let started_at = discovered_at_now();
+
+        let task_status = Arc::clone(&status);
+        let task = tauri::async_runtime::spawn(async move {
+            let mut progress = {
+                let db_path = db_path.clone();
+                run_blocking(move || {
+                    let conn = crate::db::open_connection(&db_path).map_err(|e| e.to_string())?;
+                    load_progress(&conn)
+                })
+                .await
+                .unwrap_or_default()
+            };
+            if let Ok(mut s) = task_status.lock() {
+                s.last_run_at = progress.last_run_at.clone();
+            }
+            let mut since = progress.last_processed_at.clone().unwrap_or(started_at);
+
+            let mut last_scheduled_run: Option<NaiveDateTime> = None;
+            loop {
+                tokio::time::sleep(CHECK_INTERVAL).await;
+
+                if config.mode == AutoOrganizeMode::Scheduled {
+                    let now = chrono::Local::now().naive_local();
+                    if !is_schedule_due(&config.schedule_time, last_scheduled_run, now) {
+                        continue;
+                    }
+                    last_scheduled_run = Some(now);
+                }
+
+                // Files discovered while this run is going are picked up by the next one
+                // (files already planned are never candidates again)
+                let cycle_started_at = discovered_at_now();
+                let result = run_cycle(&db_path, &config, &since).await;
+                let mut save_error = None;
+                if let Ok(cycle) = &result {
+                    since = cycle_started_at;
+                    progress.last_processed_at = Some(since.clone());
+                    if cycle.plan_id.is_some() {
+                        progress.last_run_at = Some(chrono::Utc::now().to_rfc3339());
+                    }
+                    let (db_path, progress) = (db_path.clone(), progress.clone());
+                    save_error = run_blocking(move || {
+                        let conn = crate::db::open_connection(&db_path).map_err(|e| e.to_string())?;
+                        save_progress(&conn, &progress)
+                    })
+                    .await
+                    .err();
+                }
+
+                if let Ok(mut s) = task_status.lock() {
+                    match result {
+                        Ok(cycle) => {
+                            s.last_run_at = progress.last_run_at.clone();
+                            s.files_suggested += cycle.files_suggested;
+                            if let Some(execution) = cycle.execution {
+                                s.files_moved += execution.files_moved;
+                                s.last_session_id = Some(execution.session_id);
+                                s.last_error = execution.errors.first().cloned();
+                            } else {
+                                s.last_error = None;
+                            }
+                        }
+                        Err(e) => s.last_error = Some(e),
+                    }
+                    if save_error.is_some() {
+                        s.last_error = save_error;
+                    }
+                }
+            }
+        });
+
+        Self { task, status }
+    }
+
+    pub fn status(&self) -> AutoOrganizeStatus {
+        self.status.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    pub fn stop(self) -> AutoOrganizeStatus {
+        self.task.abort();
+        let mut status = self.status();
+        status.running = false;
+        status
+    }
+}
+
+/// Pending Conservative-mode suggestions, oldest first
+pub fn get_suggestions(conn: &Connection) -> Result<Vec<AutoSuggestion>, String> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT pi.plan_id, pi.file_id, pi.source_path, pi.destination_path,
+                    COALESCE(pi.confidence, 0.0), COALESCE(pi.reason, '')
+             FROM plan_items pi
+             JOIN organization_plans p ON p.id = pi.plan_id
+             WHERE p.organization_style = ?1 AND pi.status = 'pending'
+             ORDER BY p.created_at, pi.id",
+        )
+        .map_err(|e| e.to_string())?;
+
+    let style = format!("{}{}", AUTO_PLAN_STYLE_PREFIX, AutoOrganizeMode::Conservative.as_str());
+    let rows = stmt
+        .query_map([style], |row| {
+            Ok(AutoSuggestion {
+                plan_id: row.get(0)?,
+                file_id: row.get(1)?,
+                source_path: row.get(2)?,
+                destination_path: row.get(3)?,
+                confidence: row.get(4)?,
+                reason: row.get(5)?,
+            })
+        })
+        .map_err(|e| e.to_string())?;
+
+    Ok(rows.filter_map(|r| r.ok()).collect())
+}
+
+/// Approve suggestions (all, or only `file_ids`), moving them in one session per plan
+pub fn approve_suggestions(conn: &Connection, file_ids: Option<&HashSet<i64>>) -> Result<Vec<ExecutionResult>, String> {
+    let suggestions = get_suggestions(conn)?;
+    // Plans created in the same second can interleave, so drop every repeat (keeping the order)
+    let mut seen = HashSet::new();
+    let plan_ids: Vec<String> = suggestions
+        .iter()
+        .filter(|s| seen.insert(s.plan_id.as_str()))
+        .map(|s| s.plan_id.clone())
+        .collect();
+
+    let mut results = Vec::new();
+    for plan_id in plan_ids {
+        let excluded: HashSet<i64> = suggestions
+            .iter()
+            .filter(|s| s.plan_id == plan_id && !file_ids.is_none_or(|ids| ids.contains(&s.file_id)))
+            .map(|s| s.file_id)
+            .collect();
+        let selected = suggestions.iter().filter(|s| s.plan_id == plan_id).count() - excluded.len();
+        if selected > 0 {
+            results.push(commands::execute_plan_items(conn, &plan_id, &excluded, false, AUTO_SESSION_MODE)?);
+        }
+    }
+    Ok(results)
+}
+
+/// Dismiss suggestions so they are neither moved nor suggested again
+pub fn dismiss_suggestions(conn: &Connection, file_ids: &[i64]) -> Result<usize, String> {
+    let style = format!("{}{}", AUTO_PLAN_STYLE_PREFIX, AutoOrganizeMode::Conservative.as_str());
+    let mut dismissed = 0;
+    for file_id in file_ids {
+        dismissed += conn
+            .execute(
+                "UPDATE plan_items SET status = 'dismissed'
+                 WHERE file_id = ?1 AND status = 'pending'
+                   AND plan_id IN (SELECT id FROM organization_plans WHERE organization_style = ?2)",
+                params![file_id, &style],
+            )
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(dismissed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::activity_log;
+    use crate::db;
+
+    fn setup_test_db() -> (tempfile::TempDir, Connection) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.db");
+        db::init_database(&path).unwrap();
+        let conn = db::open_connection(&path).unwrap();
+        (dir, conn)
+    }
+
+    fn add_classified_file(conn: &Connection, path: &Path, category: &str, confidence: f64) -> i64 {
+        std::fs::write(path, "content").unwrap();
+        conn.execute(
+            "INSERT INTO files (path, filename, extension, size) VALUES (?1, ?2, 'pdf', 7)",
+            params![path.to_string_lossy(), path.file_name().unwrap().to_string_lossy()],
+        )
+        .unwrap();
+        let id = conn.last_insert_rowid();
+        conn.execute(
+            "INSERT INTO ai_metadata (file_id, category, confidence) VALUES (?1, ?2, ?3)",
+            params![id, category, confidence],
+        )
+        .unwrap();
+        id
+    }
+
+    fn test_config(root: &Path, mode: AutoOrganizeMode) -> AutoOrganizeConfig {
+        AutoOrganizeConfig {
+            enabled: true,
+            mode,
+            watch_folders: vec![root.join("Downloads").to_string_lossy().to_string()],
+            destination_root: Some(root.join("Organized Files").to_string_lossy().to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_schedule_due_once_per_day() {
+        let at = |s: &str| NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M").unwrap();
+        assert!(!is_schedule_due("18:00", None, at("2026-03-02 17:59")));
+        assert!(is_schedule_due("18:00", None, at("2026-03-02 18:00")));
+        assert!(!is_schedule_due("18:00", Some(at("2026-03-02 18:01")), at("2026-03-02 23:00")));
+        assert!(is_schedule_due("18:00", Some(at("2026-03-02 18:01")), at("2026-03-03 18:30")));
+        assert!(!is_schedule_due("not a time", None, at("2026-03-02 18:30")));
+    }
+
+    #[test]
+    fn test_candidates_limited_to_watch_folders_and_types() {
+        let (dir, conn) = setup_test_db();
+        let downloads = dir.path().join("Downloads");
+        std::fs::create_dir_all(&downloads).unwrap();
+        let config = test_config(dir.path(), AutoOrganizeMode::Conservative);
+
+        let wanted = add_classified_file(&conn, &downloads.join("invoice.pdf"), "Money", 0.9);
+        add_classified_file(&conn, &dir.path().join("elsewhere.pdf"), "Money", 0.9);
+        conn.execute("UPDATE files SET extension = 'exe' WHERE filename = 'elsewhere.pdf'", []).unwrap();
+        let setup = downloads.join("setup.exe");
+        std::fs::write(&setup, "x").unwrap();
+        conn.execute(
+            "INSERT INTO files (path, filename, extension, size) VALUES (?1, 'setup.exe', 'exe', 1)",
+            [setup.to_string_lossy()],
+        )
+        .unwrap();
+
+        assert_eq!(find_candidates(&conn, &config, "2000-01-01 00:00:00").unwrap(), vec![wanted]);
+        assert!(find_candidates(&conn, &config, "2999-01-01 00:00:00").unwrap().is_empty());
+
+        // Once planned, a file is not picked up again
+        organize_candidates(&conn, &config, &[wanted]).unwrap();
+        assert!(find_candidates(&conn, &config, "2000-01-01 00:00:00").unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_progress_round_trip() {
+        let (_dir, conn) = setup_test_db();
+        assert_eq!(load_progress(&conn).unwrap(), AutoOrganizeProgress::default());
+
+        let progress = AutoOrganizeProgress {
+            last_run_at: Some("2026-03-02T18:00:00+00:00".to_string()),
+            last_processed_at: Some("2026-03-02 18:00:00".to_string()),
+        };
+        save_progress(&conn, &progress).unwrap();
+        assert_eq!(load_progress(&conn).unwrap(), progress);
+
+        let later = AutoOrganizeProgress { last_processed_at: Some("2026-03-03 09:00:00".to_string()), ..progress };
+        save_progress(&conn, &later).unwrap();
+        assert_eq!(load_progress(&conn).unwrap(), later);
+    }
+
+    #[test]
+    fn test_conservative_queues_suggestions() {
+        let (dir, conn) = setup_test_db();
+        let downloads = dir.path().join("Downloads");
+        std::fs::create_dir_all(&downloads).unwrap();
+        let config = test_config(dir.path(), AutoOrganizeMode::Conservative);
+        let a = add_classified_file(&conn, &downloads.join("a.pdf"), "Money", 0.9);
+        let b = add_classified_file(&conn, &downloads.join("b.pdf"), "Work", 0.9);
+
+        let result = organize_candidates(&conn, &config, &[a, b]).unwrap();
+        assert!(result.execution.is_none());
+        assert_eq!(result.files_suggested, 2);
+        assert!(downloads.join("a.pdf").exists());
+        assert_eq!(get_suggestions(&conn).unwrap().len(), 2);
+
+        assert_eq!(dismiss_suggestions(&conn, &[b]).unwrap(), 1);
+        let approved = approve_suggestions(&conn, None).unwrap();
+        assert_eq!(approved.len(), 1);
+        assert_eq!(approved[0].files_moved, 1);
+        assert!(!downloads.join("a.pdf").exists());
+        assert!(downloads.join("b.pdf").exists());
+        assert!(get_suggestions(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_approve_runs_interleaved_plans_once() {
+        let (dir, conn) = setup_test_db();
+        let downloads = dir.path().join("Downloads");
+        std::fs::create_dir_all(&downloads).unwrap();
+        let config = test_config(dir.path(), AutoOrganizeMode::Conservative);
+        let a = add_classified_file(&conn, &downloads.join("a.pdf"), "Money", 0.9);
+        let b = add_classified_file(&conn, &downloads.join("b.pdf"), "Work", 0.9);
+        let c = add_classified_file(&conn, &downloads.join("c.pdf"), "Home", 0.9);
+        organize_candidates(&conn, &config, &[a, c]).unwrap();
+        organize_candidates(&conn, &config, &[b]).unwrap();
+
+        // Same creation time, items ordered a (plan 1), b (plan 2), c (plan 1)
+        conn.execute("UPDATE organization_plans SET created_at = '2026-03-02 18:00:00'", []).unwrap();
+        conn.execute("UPDATE plan_items SET id = id + 100 WHERE file_id = ?1", [c]).unwrap();
+        let plans: Vec<String> = get_suggestions(&conn).unwrap().into_iter().map(|s| s.plan_id).collect();
+        assert_eq!(plans.len(), 3);
+        assert_ne!(plans[0], plans[1]);
+        assert_eq!(plans[0], plans[2]);
+
+        let approved = approve_suggestions(&conn, None).unwrap();
+        assert_eq!(approved.len(), 2);
+        assert_eq!(approved.iter().map(|r| r.files_moved).sum::<usize>(), 3);
+    }
+
+    #[test]
+    fn test_automatic_moves_are_undoable_sessions() {
+        let (dir, conn) = setup_test_db();
+        let downloads = dir.path().join("Downloads");
+        std::fs::create_dir_all(&downloads).unwrap();
+        let config = test_config(dir.path(), AutoOrganizeMode::Automatic);
+        let sure = add_classified_file(&conn, &downloads.join("taxes.pdf"), "Money", 0.95);
+        let unsure = add_classified_file(&conn, &downloads.join("notes.pdf"), "Work", 0.4);
+
+        let result = organize_candidates(&conn, &config, &[sure, unsure]).unwrap();
+        let execution = result.execution.unwrap();
+        assert_eq!(execution.files_moved, 2);
+
+        let destination = |file_id: i64| -> String {
+            conn.query_row(
+                "SELECT destination_path FROM plan_items WHERE file_id = ?1",
+                [file_id],
+                |row| row.get(0),
+            )
+            .unwrap()
+        };
+        let organized = dir.path().join("Organized Files");
+        assert_eq!(Path::new(&destination(sure)), organized.join("Money").join("taxes.pdf"));
+        assert!(organized.join("Money").join("taxes.pdf").exists());
+        assert_eq!(Path::new(&destination(unsure)), organized.join("Review").join("notes.pdf"));
+        assert!(organized.join("Review").join("notes.pdf").exists());
+
+        let session = activity_log::get_session(&conn, &execution.session_id).unwrap().unwrap();
+        assert_eq!(session.selected_mode.as_deref(), Some(AUTO_SESSION_MODE));
+
+        let undo = activity_log::undo_session(&conn, &execution.session_id).unwrap();
+        assert_eq!(undo.operations_undone, 2);
+        assert!(downloads.join("taxes.pdf").exists());
+        assert!(downloads.join("notes.pdf").exists());
+    }
+}
@@ -1,22 +1,40 @@
 use crate::activity_log::{self, Operation, OperationType, OperationStatus, SessionStatus};
 use crate::ai::{
-    AIClient, AIConfig, FileForClassification, estimate_credits,
+    AIClient, AIConfig, FileClassification, FileForClassification, estimate_credits,
     PersonalizationAnswers as AIPersonalizationAnswers,
     FileSummary as AIFileSummary,
     CategoryStats as AICategoryStats,
     ClarificationQuestion as AIClarificationQuestion,
 };
+use crate::auto_organize::{
+    self, AutoOrganizeConfig, AutoOrganizeService, AutoOrganizeState, AutoOrganizeStatus, AutoSuggestion,
+};
 use crate::db::DbPath;
-use crate::scanner::{self, ScanConfig, ScannedFile};
+use crate::document_parser::ExternalExtractor;
+use crate::ignore_rules;
+use crate::duplicates::{self, DedupePlan, DuplicateGroup, KeepPolicy};
+use crate::indexer;
+use crate::path_identity::{self, PathIdentity};
+use crate::scanner::{
+    self, CancelToken, ProgressTracker, ScanConfig, ScanPhase, ScanProgress, ScanState, ScannedFile,
+};
+use crate::search::{self, SearchResult};
+use crate::watcher::{FileWatcher, WatcherState, WatcherStatus};
 use rusqlite::Connection;
 use serde::{Deserialize, Serialize};
 use std::fs;
-use std::path::PathBuf;
-use tauri::{AppHandle, Manager, State};
+use std::path::{Path, PathBuf};
+use tauri::{AppHandle, Emitter, Manager, State};
 
 /// Free tier limit - maximum number of scans allowed
 const FREE_TIER_MAX_SCANS: u32 = 10;
 
+/// Event carrying `ScanProgress` while `scan_directories` runs
+const SCAN_PROGRESS_EVENT: &str = "scan-progress";
+
+/// Error returned by `scan_directories` after `cancel_scan`
+const SCAN_CANCELLED: &str = "Scan cancelled";
+
 /// Known folder information
 #[derive(Debug, Clone, Serialize)]
 pub struct KnownFolder {
@@ -32,6 +50,9 @@ pub struct AppSettings {
     pub anthropic_model: Option<String>,
     #[serde(default)]
     pub scans_used: u32,
+    /// Commands that extract text for extensions the built-in parsers don't handle (or override)
+    #[serde(default)]
+    pub external_extractors: Vec<ExternalExtractor>,
 }
 
 /// Get the settings file path
@@ -76,6 +97,37 @@ pub fn save_settings(app: AppHandle, settings: AppSettings) -> Result<(), String
     Ok(())
 }
 
+/// Get the global ignore rules file path (gitignore syntax, like `.filesenseignore`)
+fn get_ignore_rules_path(app: &AppHandle) -> PathBuf {
+    let app_data_dir = app.path().app_data_dir().expect("Failed to get app data dir");
+    app_data_dir.join(ignore_rules::IGNORE_FILE_NAME)
+}
+
+/// Load the global ignore rules (defaults until the user saves their own)
+#[tauri::command]
+pub fn get_ignore_patterns(app: AppHandle) -> Vec<String> {
+    match fs::read_to_string(get_ignore_rules_path(&app)) {
+        Ok(contents) => contents.lines().map(|line| line.to_string()).collect(),
+        Err(_) => ignore_rules::default_ignore_patterns(),
+    }
+}
+
+/// Save the global ignore rules (atomic write to prevent corruption)
+#[tauri::command]
+pub fn save_ignore_patterns(app: AppHandle, patterns: Vec<String>) -> Result<(), String> {
+    let rules_path = get_ignore_rules_path(&app);
+
+    if let Some(parent) = rules_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let temp_path = rules_path.with_extension("tmp");
+    fs::write(&temp_path, patterns.join("\n")).map_err(|e| e.to_string())?;
+    fs::rename(&temp_path, &rules_path).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
 /// Internal: Get current scan count without tauri command wrapper
 fn get_scan_count_internal(app: &AppHandle) -> u32 {
     let settings = get_settings(app.clone());
@@ -129,59 +181,24 @@ pub struct QuickFileCount {
 
 /// Quickly count files in directories (without full scanning)
 /// If extensions is provided, only count files with those extensions
+/// Applies the same exclusions and ignore rules as `scan_directories`
 #[tauri::command]
 pub fn count_files_in_directories(
     directories: Vec<String>,
     extensions: Option<Vec<String>>,
+    app: AppHandle,
 ) -> QuickFileCount {
-    let mut total = 0;
-
-    // Convert extensions to lowercase HashSet for fast lookup
-    let ext_filter: Option<std::collections::HashSet<String>> = extensions.map(|exts| {
-        exts.into_iter().map(|e| e.to_lowercase()).collect()
-    });
-
-    for dir in directories {
-        let path = std::path::Path::new(&dir);
-        if path.exists() && path.is_dir() {
-            // Use walkdir to count files quickly
-            for entry in walkdir::WalkDir::new(path)
-                .max_depth(10)
-                .into_iter()
-                .filter_map(|e| e.ok())
-            {
-                // Skip files in "Organized Files" folders - these are already organized
-                let entry_path = entry.path().to_string_lossy();
-                if entry_path.contains("Organized Files") {
-                    continue;
-                }
-
-                if entry.file_type().is_file() {
-                    // Skip hidden files
-                    if let Some(name) = entry.file_name().to_str() {
-                        if name.starts_with('.') {
-                            continue;
-                        }
-
-                        // Check extension filter if provided
-                        if let Some(ref filter) = ext_filter {
-                            let file_ext = entry.path()
-                                .extension()
-                                .and_then(|e| e.to_str())
-                                .map(|e| e.to_lowercase())
-                                .unwrap_or_default();
-
-                            if !filter.contains(&file_ext) {
-                                continue;
-                            }
-                        }
+    let config = ScanConfig {
+        directories: directories.iter().map(PathBuf::from).collect(),
+        include_hidden: false,
+        max_depth: Some(scanner::MAX_SCAN_DEPTH),
+        compute_hashes: false,
+        extensions_filter: extensions,
+        external_extractors: get_settings(app.clone()).external_extractors,
+        ignore_patterns: get_ignore_patterns(app),
+    };
 
-                        total += 1;
-                    }
-                }
-            }
-        }
-    }
+    let total = scanner::count_files(&config);
 
     QuickFileCount {
         total_files: total,
@@ -230,6 +247,27 @@ pub struct ClassificationProgress {
     pub estimated_credits: f64,
 }
 
+/// Store AI classification results in ai_metadata
+pub(crate) fn store_classifications(conn: &Connection, classifications: &[FileClassification]) -> Result<(), String> {
+    for classification in classifications {
+        conn.execute(
+            "INSERT OR REPLACE INTO ai_metadata (file_id, category, subcategory, tags, summary, confidence, suggested_path, classified_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP)",
+            (
+                classification.file_id,
+                classification.category.as_str(),
+                &classification.subcategory,
+                classification.tags.join(", "),
+                &classification.summary,
+                classification.confidence,
+                &classification.suggested_folder,
+            ),
+        )
+        .map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
 /// Classify files using AI (batch processing)
 #[tauri::command]
 pub async fn classify_files(
@@ -301,23 +339,7 @@ pub async fn classify_files(
     // Step 3: Store results in database (new connection)
     let (final_total, final_classified) = {
         let conn = crate::db::open_connection(&db_path_clone).map_err(|e| e.to_string())?;
-
-        for classification in &result.classifications {
-            conn.execute(
-                "INSERT OR REPLACE INTO ai_metadata (file_id, category, subcategory, tags, summary, confidence, suggested_path, classified_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP)",
-                (
-                    classification.file_id,
-                    classification.category.as_str(),
-                    &classification.subcategory,
-                    classification.tags.join(", "),
-                    &classification.summary,
-                    classification.confidence,
-                    &classification.suggested_folder,
-                ),
-            )
-            .map_err(|e| e.to_string())?;
-        }
+        store_classifications(&conn, &result.classifications)?;
 
         // Get updated stats
         let total: usize = conn
@@ -381,19 +403,6 @@ pub struct ScanStatus {
     pub last_scan_at: Option<String>,
 }
 
-/// Search result item
-#[derive(Debug, Clone, Serialize)]
-pub struct SearchResult {
-    pub id: i64,
-    pub path: String,
-    pub filename: String,
-    pub category: Option<String>,
-    pub tags: Option<String>,
-    pub summary: Option<String>,
-    pub previous_path: Option<String>,
-    pub confidence: Option<f64>,
-}
-
 /// File details response
 #[derive(Debug, Clone, Serialize)]
 pub struct FileDetails {
@@ -467,44 +476,21 @@ pub struct ScanResult {
     pub unchanged_files: usize,
     pub deleted_files: usize,
     pub total_files: usize,
+    pub snippets_extracted: usize,
     pub files: Vec<ScannedFile>,
 }
 
-/// Check if a file needs to be rescanned (different hash or modified date)
-fn file_needs_rescan(
-    conn: &Connection,
-    path: &str,
-    new_hash: &Option<String>,
-    new_modified: &Option<String>,
-) -> Result<bool, rusqlite::Error> {
-    match conn.query_row(
-        "SELECT content_hash, modified_at FROM files WHERE path = ?1",
-        [path],
-        |row| {
-            Ok((
-                row.get::<_, Option<String>>(0)?,
-                row.get::<_, Option<String>>(1)?,
-            ))
-        },
-    ) {
-        Ok((old_hash, old_modified)) => {
-            // File exists - check if it changed
-            Ok(new_hash != &old_hash || new_modified != &old_modified)
-        }
-        Err(rusqlite::Error::QueryReturnedNoRows) => Ok(true), // New file
-        Err(e) => Err(e),
-    }
-}
-
 /// Scan specified directories and index files (incremental - preserves AI metadata for unchanged files)
 /// If extensions is provided, only scan files with those extensions
 /// Enforces free tier scan limit on the backend (cannot be bypassed via devtools)
+/// Runs off the async runtime, emitting "scan-progress" events; `cancel_scan` stops it
 #[tauri::command]
 pub async fn scan_directories(
     directories: Vec<String>,
     extensions: Option<Vec<String>>,
     app: AppHandle,
     db_path: State<'_, DbPath>,
+    scan_state: State<'_, ScanState>,
 ) -> Result<ScanResult, String> {
     // Backend free tier enforcement - check scan count BEFORE scanning
     let current_scans = get_scan_count_internal(&app);
@@ -516,160 +502,302 @@ pub async fn scan_directories(
     }
 
     let config = ScanConfig {
-        directories: directories.iter().map(|d| PathBuf::from(d)).collect(),
+        directories: directories.iter().map(PathBuf::from).collect(),
         include_hidden: false,
-        max_depth: Some(10),
+        max_depth: Some(scanner::MAX_SCAN_DEPTH),
         compute_hashes: true,
         extensions_filter: extensions,
+        external_extractors: get_settings(app.clone()).external_extractors,
+        ignore_patterns: get_ignore_patterns(app.clone()),
     };
 
-    let files = scanner::scan_directories(&config);
+    let cancel = CancelToken::default();
+    *scan_state.0.lock().map_err(|e| e.to_string())? = Some(cancel.clone());
 
-    // Store in database incrementally (preserve AI metadata for unchanged files)
-    let conn = crate::db::open_connection(&db_path.0).map_err(|e| e.to_string())?;
+    let db = db_path.0.clone();
+    let progress_app = app.clone();
+    let result = tauri::async_runtime::spawn_blocking(move || {
+        let on_progress = |progress: &ScanProgress| {
+            let _ = progress_app.emit(SCAN_PROGRESS_EVENT, progress);
+        };
+        run_scan(&db, &config, &cancel, &ProgressTracker::new(&on_progress))
+    })
+    .await
+    .map_err(|e| e.to_string());
 
-    // Track which paths we see in this scan (lowercase for case-insensitive comparison on Windows)
-    let mut seen_paths = std::collections::HashSet::new();
-    let mut new_files = 0;
-    let mut updated_files = 0;
-    let mut unchanged_files = 0;
+    if let Ok(mut current) = scan_state.0.lock() {
+        *current = None;
+    }
+    let result = result??;
 
-    for file in &files {
-        let path_str = file.path.to_string_lossy().to_string();
-        // Use lowercase for case-insensitive path tracking on Windows
-        seen_paths.insert(path_str.to_lowercase());
+    // Backend free tier enforcement - increment scan count AFTER successful scan
+    // This ensures the count only increases for successful scans
+    if result.total_files > 0 {
+        increment_scan_count_internal(&app)?;
+    }
 
-        let needs_update = file_needs_rescan(&conn, &path_str, &file.content_hash, &file.modified_at)
-            .map_err(|e| e.to_string())?;
+    Ok(result)
+}
 
-        if needs_update {
-            // Check if this is a new file or update
-            let is_new: bool = conn
-                .query_row(
-                    "SELECT 1 FROM files WHERE path = ?1",
-                    [&path_str],
-                    |_| Ok(false),
-                )
-                .unwrap_or(true);
-
-            // Upsert file record
-            conn.execute(
-                "INSERT INTO files (path, filename, extension, size, created_at, modified_at, content_hash, last_scanned_at)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, CURRENT_TIMESTAMP)
-                 ON CONFLICT(path) DO UPDATE SET
-                     filename = excluded.filename,
-                     extension = excluded.extension,
-                     size = excluded.size,
-                     created_at = excluded.created_at,
-                     modified_at = excluded.modified_at,
-                     content_hash = excluded.content_hash,
-                     last_scanned_at = CURRENT_TIMESTAMP",
-                rusqlite::params![
-                    &path_str,
-                    &file.filename,
-                    &file.extension,
-                    file.size as i64,
-                    &file.created_at,
-                    &file.modified_at,
-                    &file.content_hash,
-                ],
-            )
-            .map_err(|e| e.to_string())?;
+/// Walk, index and extract for `scan_directories`
+/// A cancelled scan keeps what it indexed so far but removes nothing.
+fn run_scan(
+    db_path: &PathBuf,
+    config: &ScanConfig,
+    cancel: &CancelToken,
+    progress: &ProgressTracker,
+) -> Result<ScanResult, String> {
+    let conn = crate::db::open_connection(db_path).map_err(|e| e.to_string())?;
 
-            // Clear stale AI metadata for changed files only (not new files)
-            if !is_new {
-                // Get file_id to clear related data
-                if let Ok(file_id) = conn.query_row(
-                    "SELECT id FROM files WHERE path = ?1",
-                    [&path_str],
-                    |row| row.get::<_, i64>(0),
-                ) {
-                    conn.execute(
-                        "DELETE FROM ai_metadata WHERE file_id = ?1",
-                        [file_id],
-                    )
-                    .ok();
-                    conn.execute(
-                        "DELETE FROM content_snippets WHERE file_id = ?1",
-                        [file_id],
-                    )
-                    .ok();
-                }
-                updated_files += 1;
-            } else {
-                new_files += 1;
-            }
-        } else {
-            // File unchanged - just update last_scanned_at timestamp
-            conn.execute(
-                "UPDATE files SET last_scanned_at = CURRENT_TIMESTAMP WHERE path = ?1",
-                [&path_str],
-            )
-            .ok();
-            unchanged_files += 1;
-        }
+    // Unchanged files keep the content type detected on an earlier scan
+    let mut known = scanner::KnownFiles::new();
+    for dir in &config.directories {
+        known.extend(indexer::known_files(&conn, &dir.to_string_lossy()).map_err(|e| e.to_string())?);
     }
+    let files = scanner::scan_directories_with_progress(config, &known, cancel, progress)
+        .ok_or_else(|| SCAN_CANCELLED.to_string())?;
 
-    // Remove files that no longer exist in the scanned directories
-    // Only delete files that are within the scanned directories and weren't seen
-    let mut deleted_files = 0;
-    for dir in &directories {
-        // Find files in this directory that weren't seen in this scan
-        // Ensure we match full directory path (add separator to prevent matching C:\Downloads2 when scanning C:\Downloads)
-        let dir_with_sep = if dir.ends_with('\\') || dir.ends_with('/') {
-            dir.clone()
-        } else {
-            format!("{}\\", dir)
-        };
-        let dir_pattern = format!("{}%", dir_with_sep.replace('\\', "\\\\"));
-        let stale_file_ids: Vec<i64> = {
-            let mut stmt = conn
-                .prepare(
-                    "SELECT id, path FROM files WHERE path LIKE ?1 COLLATE NOCASE",
-                )
-                .map_err(|e| e.to_string())?;
-
-            let rows = stmt
-                .query_map([&dir_pattern], |row| {
-                    Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
-                })
-                .map_err(|e| e.to_string())?;
+    // Store in database incrementally (preserve AI metadata for unchanged files)
+    progress.set_phase(ScanPhase::Indexing);
 
-            rows.filter_map(|r| r.ok())
-                .filter(|(_, path)| !seen_paths.contains(&path.to_lowercase()))
-                .map(|(id, _)| id)
-                .collect()
-        };
+    // Track which files we see in this scan, compared the way each root's volume does
+    let identity = config.path_identity();
+    let seen_keys: std::collections::HashSet<String> = files
+        .iter()
+        .map(|file| identity.path_key(&file.path))
+        .collect();
 
-        for file_id in stale_file_ids {
-            // Delete associated data first (foreign key order)
-            conn.execute("DELETE FROM ai_metadata WHERE file_id = ?1", [file_id]).ok();
-            conn.execute("DELETE FROM content_snippets WHERE file_id = ?1", [file_id]).ok();
-            conn.execute("DELETE FROM plan_items WHERE file_id = ?1", [file_id]).ok();
-            conn.execute("DELETE FROM move_history WHERE file_id = ?1", [file_id]).ok();
-            conn.execute("DELETE FROM files WHERE id = ?1", [file_id]).ok();
-            deleted_files += 1;
-        }
+    let counts = indexer::index_files(&conn, &files, &identity, cancel, progress).map_err(|e| e.to_string())?;
+    if cancel.is_cancelled() {
+        return Err(SCAN_CANCELLED.to_string());
     }
 
-    let total_files = new_files + updated_files + unchanged_files;
-
-    // Backend free tier enforcement - increment scan count AFTER successful scan
-    // This ensures the count only increases for successful scans
-    if total_files > 0 {
-        increment_scan_count_internal(&app)?;
+    progress.set_phase(ScanPhase::Extracting);
+    let snippets_extracted = indexer::extract_pending_snippets_with_progress(
+        &conn,
+        &seen_keys,
+        &identity,
+        &config.parser_registry(),
+        cancel,
+        progress,
+    )
+    .map_err(|e| e.to_string())?;
+    if cancel.is_cancelled() {
+        return Err(SCAN_CANCELLED.to_string());
     }
 
+    // Remove files that no longer exist in the scanned directories
+    let roots: Vec<String> = config
+        .directories
+        .iter()
+        .map(|d| d.to_string_lossy().to_string())
+        .collect();
+    let deleted_files = indexer::remove_stale_files(&conn, &roots, &identity, &seen_keys)
+        .map_err(|e| e.to_string())?;
+
+    progress.set_phase(ScanPhase::Done);
+
     Ok(ScanResult {
-        new_files,
-        updated_files,
-        unchanged_files,
+        new_files: counts.new_files,
+        updated_files: counts.updated_files,
+        unchanged_files: counts.unchanged_files,
         deleted_files,
-        total_files,
+        total_files: counts.total(),
+        snippets_extracted,
         files,
     })
 }
 
+/// Cancel the scan in progress, if any
+/// Returns whether a scan was running
+#[tauri::command]
+pub fn cancel_scan(scan_state: State<'_, ScanState>) -> Result<bool, String> {
+    let current = scan_state.0.lock().map_err(|e| e.to_string())?;
+    Ok(current.as_ref().map(CancelToken::cancel).is_some())
+}
+
+/// Start the filesystem watcher over the given scan roots (replaces any running watcher)
+/// Changes are written to the index as they happen; see watcher.rs
+#[tauri::command]
+pub async fn start_watcher(
+    directories: Vec<String>,
+    extensions: Option<Vec<String>>,
+    app: AppHandle,
+) -> Result<WatcherStatus, String> {
+    let config = ScanConfig {
+        directories: directories.iter().map(PathBuf::from).collect(),
+        include_hidden: false,
+        max_depth: Some(scanner::MAX_SCAN_DEPTH),
+        compute_hashes: true,
+        extensions_filter: extensions,
+        external_extractors: get_settings(app.clone()).external_extractors,
+        ignore_patterns: get_ignore_patterns(app.clone()),
+    };
+
+    // Stopping the previous watcher waits for its worker, so keep it off the UI thread
+    tauri::async_runtime::spawn_blocking(move || replace_watcher(&app, config))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Stop the running watcher, if any, and start one for `config`
+/// Blocks until the previous watcher's worker has finished.
+fn replace_watcher(app: &AppHandle, config: ScanConfig) -> Result<WatcherStatus, String> {
+    let watcher_state = app.state::<WatcherState>();
+    // Taken out first so the state lock isn't held while the old worker finishes
+    let previous = watcher_state.0.lock().map_err(|e| e.to_string())?.take();
+    if let Some(previous) = previous {
+        previous.stop();
+    }
+
+    let watcher = FileWatcher::start(app.state::<DbPath>().0.clone(), config)?;
+    let status = watcher.status();
+    *watcher_state.0.lock().map_err(|e| e.to_string())? = Some(watcher);
+    Ok(status)
+}
+
+/// Stop the filesystem watcher (pending changes are flushed first)
+#[tauri::command]
+pub async fn stop_watcher(app: AppHandle) -> Result<WatcherStatus, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let current = app.state::<WatcherState>().0.lock().map_err(|e| e.to_string())?.take();
+        Ok(current.map(FileWatcher::stop).unwrap_or_default())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Get the filesystem watcher status
+#[tauri::command]
+pub fn get_watcher_status(watcher_state: State<'_, WatcherState>) -> Result<WatcherStatus, String> {
+    let current = watcher_state.0.lock().map_err(|e| e.to_string())?;
+    Ok(current.as_ref().map(FileWatcher::status).unwrap_or_default())
+}
+
+/// Get the auto-organize config file path
+/// Kept apart from settings.json so saving AI settings doesn't reset it
+fn get_auto_organize_config_path(app: &AppHandle) -> PathBuf {
+    let app_data_dir = app.path().app_data_dir().expect("Failed to get app data dir");
+    app_data_dir.join("auto_organize.json")
+}
+
+/// Load auto-organize settings from config file
+#[tauri::command]
+pub fn get_auto_organize_config(app: AppHandle) -> AutoOrganizeConfig {
+    let config_path = get_auto_organize_config_path(&app);
+
+    if let Ok(contents) = fs::read_to_string(&config_path) {
+        if let Ok(config) = serde_json::from_str(&contents) {
+            return config;
+        }
+    }
+
+    AutoOrganizeConfig::default()
+}
+
+/// Save auto-organize settings to config file (atomic write to prevent corruption)
+#[tauri::command]
+pub fn save_auto_organize_config(app: AppHandle, config: AutoOrganizeConfig) -> Result<(), String> {
+    let config_path = get_auto_organize_config_path(&app);
+
+    if let Some(parent) = config_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+
+    let json = serde_json::to_string_pretty(&config).map_err(|e| e.to_string())?;
+
+    let temp_path = config_path.with_extension("tmp");
+    fs::write(&temp_path, &json).map_err(|e| e.to_string())?;
+    fs::rename(&temp_path, &config_path).map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Start auto-organize with the saved config (replaces any running service)
+/// Also (re)starts the watcher over the watch folders so new files get indexed
+#[tauri::command]
+pub async fn start_auto_organize(app: AppHandle) -> Result<AutoOrganizeStatus, String> {
+    let config = get_auto_organize_config(app.clone());
+    tauri::async_runtime::spawn_blocking(move || start_auto_organize_service(&app, config))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+/// Start the watcher and the auto-organize service for `config`.
+/// Used by `start_auto_organize` and at launch when the saved config is enabled;
+/// blocks while a previous watcher stops.
+pub fn start_auto_organize_service(app: &AppHandle, config: AutoOrganizeConfig) -> Result<AutoOrganizeStatus, String> {
+    if config.watch_folders.is_empty() {
+        return Err("No watch folders configured".to_string());
+    }
+
+    let scan_config = ScanConfig {
+        directories: config.watch_folders.iter().map(PathBuf::from).collect(),
+        include_hidden: false,
+        max_depth: Some(scanner::MAX_SCAN_DEPTH),
+        compute_hashes: true,
+        extensions_filter: Some(config.file_types.clone()),
+        external_extractors: get_settings(app.clone()).external_extractors,
+        ignore_patterns: get_ignore_patterns(app.clone()),
+    };
+
+    replace_watcher(app, scan_config)?;
+
+    let db_path = app.state::<DbPath>().0.clone();
+    let auto_state = app.state::<AutoOrganizeState>();
+    let mut current = auto_state.0.lock().map_err(|e| e.to_string())?;
+    if let Some(previous) = current.take() {
+        previous.stop();
+    }
+    let service = AutoOrganizeService::start(db_path, config);
+    let status = service.status();
+    *current = Some(service);
+    Ok(status)
+}
+
+/// Stop auto-organize (the watcher keeps running until stopped separately)
+#[tauri::command]
+pub fn stop_auto_organize(auto_state: State<'_, AutoOrganizeState>) -> Result<AutoOrganizeStatus, String> {
+    let mut current = auto_state.0.lock().map_err(|e| e.to_string())?;
+    Ok(current.take().map(AutoOrganizeService::stop).unwrap_or_default())
+}
+
+/// Get the auto-organize service status
+#[tauri::command]
+pub fn get_auto_organize_status(auto_state: State<'_, AutoOrganizeState>) -> Result<AutoOrganizeStatus, String> {
+    let current = auto_state.0.lock().map_err(|e| e.to_string())?;
+    Ok(current.as_ref().map(AutoOrganizeService::status).unwrap_or_default())
+}
+
+/// Get suggestions queued by Conservative mode that await approval
+#[tauri::command]
+pub async fn get_auto_organize_suggestions(db_path: State<'_, DbPath>) -> Result<Vec<AutoSuggestion>, String> {
+    let conn = crate::db::open_connection(&db_path.0).map_err(|e| e.to_string())?;
+    auto_organize::get_suggestions(&conn)
+}
+
+/// Approve Conservative-mode suggestions (all when file_ids is omitted)
+/// Each approved plan runs as its own undoable session
+#[tauri::command]
+pub async fn approve_auto_organize_suggestions(
+    file_ids: Option<Vec<i64>>,
+    db_path: State<'_, DbPath>,
+) -> Result<Vec<ExecutionResult>, String> {
+    let conn = crate::db::open_connection(&db_path.0).map_err(|e| e.to_string())?;
+    let selected: Option<std::collections::HashSet<i64>> = file_ids.map(|ids| ids.into_iter().collect());
+    auto_organize::approve_suggestions(&conn, selected.as_ref())
+}
+
+/// Dismiss Conservative-mode suggestions so they are not suggested again
+#[tauri::command]
+pub async fn dismiss_auto_organize_suggestions(
+    file_ids: Vec<i64>,
+    db_path: State<'_, DbPath>,
+) -> Result<usize, String> {
+    let conn = crate::db::open_connection(&db_path.0).map_err(|e| e.to_string())?;
+    auto_organize::dismiss_suggestions(&conn, &file_ids)
+}
+
 /// Get current scan status
 #[tauri::command]
 pub async fn get_scan_status(db_path: State<'_, DbPath>) -> Result<ScanStatus, String> {
@@ -699,43 +827,18 @@ pub async fn get_scan_status(db_path: State<'_, DbPath>) -> Result<ScanStatus, S
     })
 }
 
-/// Search files using natural language query
+/// Search files using the full-text index (bm25-ranked, with highlighted matches)
+/// Supports qualifiers mixed with free text, e.g. `tax ext:pdf after:2023-01-01 size:>5MB`
 #[tauri::command]
 pub async fn search_files(
     query: String,
+    limit: Option<usize>,
     db_path: State<'_, DbPath>,
 ) -> Result<Vec<SearchResult>, String> {
     let conn = crate::db::open_connection(&db_path.0).map_err(|e| e.to_string())?;
 
-    // Use FTS5 for search, fallback to LIKE if no results
-    let mut stmt = conn
-        .prepare(
-            "SELECT f.id, f.path, f.filename, m.category, m.tags, m.summary, m.confidence
-             FROM files f
-             LEFT JOIN ai_metadata m ON f.id = m.file_id
-             WHERE f.filename LIKE ?1 OR m.tags LIKE ?1 OR m.summary LIKE ?1
-             LIMIT 50",
-        )
-        .map_err(|e| e.to_string())?;
-
-    let search_pattern = format!("%{}%", query);
-
-    let results = stmt
-        .query_map([&search_pattern], |row| {
-            Ok(SearchResult {
-                id: row.get(0)?,
-                path: row.get(1)?,
-                filename: row.get(2)?,
-                category: row.get(3)?,
-                tags: row.get(4)?,
-                summary: row.get(5)?,
-                previous_path: None, // Note: Could be populated from operations table if needed
-                confidence: row.get(6)?,
-            })
-        })
-        .map_err(|e| e.to_string())?;
-
-    results.collect::<Result<Vec<_>, _>>().map_err(|e| e.to_string())
+    search::search(&conn, query.trim(), limit.unwrap_or(search::DEFAULT_SEARCH_LIMIT))
+        .map_err(|e| e.to_string())
 }
 
 /// Get detailed information about a specific file
@@ -776,23 +879,47 @@ pub async fn get_file_details(
     Ok(details)
 }
 
-/// Count duplicate files (same filename + extension in different locations)
+/// Count duplicate groups (same size and full content hash)
 #[tauri::command]
-pub fn count_duplicates(db_path: State<'_, DbPath>) -> Result<usize, String> {
-    let conn = crate::db::open_connection(&db_path.0).map_err(|e| e.to_string())?;
+pub async fn count_duplicates(db_path: State<'_, DbPath>) -> Result<usize, String> {
+    let groups = load_duplicate_groups(db_path.0.clone()).await?;
+    Ok(groups.len())
+}
 
-    let count: i64 = conn.query_row(
-        "SELECT COUNT(*) FROM (
-            SELECT filename, extension, COUNT(*) as cnt
-            FROM files
-            GROUP BY LOWER(filename), LOWER(COALESCE(extension, ''))
-            HAVING cnt > 1
-        )",
-        [],
-        |row| row.get(0),
-    ).unwrap_or(0);
+/// List duplicate groups for review
+#[tauri::command]
+pub async fn find_duplicates(db_path: State<'_, DbPath>) -> Result<Vec<DuplicateGroup>, String> {
+    load_duplicate_groups(db_path.0.clone()).await
+}
 
-    Ok(count as usize)
+/// Group duplicates off the async runtime: cached hashes are reused, but
+/// candidates without a full hash yet are read from disk in full
+async fn load_duplicate_groups(db: PathBuf) -> Result<Vec<DuplicateGroup>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        let conn = crate::db::open_connection(&db).map_err(|e| e.to_string())?;
+        duplicates::find_duplicate_groups(&conn).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
+}
+
+/// Generate a dedupe plan that keeps one copy per group and moves the rest
+/// into Review/Duplicates. Run it with `execute_plan` so the moves are logged and undoable.
+#[tauri::command]
+pub async fn generate_dedupe_plan(
+    policy: KeepPolicy,
+    base_path: Option<String>,
+    db_path: State<'_, DbPath>,
+) -> Result<DedupePlan, String> {
+    let organize_base = PathBuf::from(base_path.unwrap_or_else(default_organize_base));
+
+    let db = db_path.0.clone();
+    tauri::async_runtime::spawn_blocking(move || {
+        let conn = crate::db::open_connection(&db).map_err(|e| e.to_string())?;
+        duplicates::create_dedupe_plan(&conn, policy, &organize_base).map_err(|e| e.to_string())
+    })
+    .await
+    .map_err(|e| e.to_string())?
 }
 
 /// Smart categorization based on filename patterns
@@ -1003,9 +1130,6 @@ pub async fn generate_organization_plan(
     folder_depth: Option<String>,
     db_path: State<'_, DbPath>,
 ) -> Result<OrganizationPlan, String> {
-    let db_path_clone = db_path.0.clone();
-    let style_clone = style.clone();
-
     // Folder depth controls how many levels of subfolders to create:
     // - "flat": Only main category folders (e.g., "01 Work")
     // - "moderate": Category + subcategory (e.g., "01 Work/Resumes")
@@ -1013,37 +1137,48 @@ pub async fn generate_organization_plan(
     let depth = folder_depth.unwrap_or_else(|| "moderate".to_string());
 
     // Determine base path for organized files
-    let organize_base = base_path.unwrap_or_else(|| {
-        dirs::document_dir()
-            .unwrap_or_else(|| std::path::PathBuf::from("C:\\"))
-            .join("Organized Files")
-            .to_string_lossy()
-            .to_string()
-    });
-
-    // Count duplicates (same filename + extension in different locations)
-    let duplicates_found: usize = {
-        let conn = crate::db::open_connection(&db_path_clone).map_err(|e| e.to_string())?;
+    let organize_base = base_path.unwrap_or_else(default_organize_base);
 
-        // Find files with same filename + extension that appear more than once
-        let count: i64 = conn.query_row(
-            "SELECT COUNT(*) FROM (
-                SELECT filename, extension, COUNT(*) as cnt
-                FROM files
-                GROUP BY LOWER(filename), LOWER(COALESCE(extension, ''))
-                HAVING cnt > 1
-            )",
-            [],
-            |row| row.get(0),
-        ).unwrap_or(0);
+    let conn = crate::db::open_connection(&db_path.0).map_err(|e| e.to_string())?;
+    let mut plan = build_organization_plan(&conn, &style, &organize_base, &depth, None)?;
 
-        count as usize
-    };
+    // Count duplicate groups (same size and full content hash)
+    plan.summary.duplicates_found = duplicates::find_duplicate_groups(&conn)
+        .map(|groups| groups.len())
+        .unwrap_or(0);
+
+    save_organization_plan(&conn, &plan, &format!("{:?}", style).to_lowercase())?;
+    Ok(plan)
+}
+
+/// The user's Documents folder (falls back to ~/Documents when the platform has none)
+pub(crate) fn documents_dir() -> PathBuf {
+    dirs::document_dir()
+        .or_else(|| dirs::home_dir().map(|home| home.join("Documents")))
+        .unwrap_or_else(|| PathBuf::from("Documents"))
+}
+
+/// Default location for organized files (Documents/Organized Files)
+pub(crate) fn default_organize_base() -> String {
+    documents_dir()
+        .join("Organized Files")
+        .to_string_lossy()
+        .to_string()
+}
+
+/// Build an organization plan for indexed files (all of them, or only `only_file_ids`).
+/// The plan is not saved; see `save_organization_plan`.
+pub(crate) fn build_organization_plan(
+    conn: &Connection,
+    style: &OrganizationStyle,
+    organize_base: &str,
+    depth: &str,
+    only_file_ids: Option<&std::collections::HashSet<i64>>,
+) -> Result<OrganizationPlan, String> {
+    let style_clone = style.clone();
 
     // Query files with classifications
     let files_with_metadata: Vec<(i64, String, String, Option<String>, Option<String>, Option<String>, Option<String>, f64, Option<String>)> = {
-        let conn = crate::db::open_connection(&db_path_clone).map_err(|e| e.to_string())?;
-
         let mut stmt = conn.prepare(
             "SELECT f.id, f.path, f.filename, f.extension, f.modified_at,
                     a.category, a.subcategory, COALESCE(a.confidence, 0.5), a.suggested_path
@@ -1066,7 +1201,9 @@ pub async fn generate_organization_plan(
             ))
         }).map_err(|e| e.to_string())?;
 
-        rows.filter_map(|r| r.ok()).collect()
+        rows.filter_map(|r| r.ok())
+            .filter(|row| only_file_ids.is_none_or(|ids| ids.contains(&row.0)))
+            .collect()
     };
 
     let plan_id = uuid::Uuid::new_v4().to_string();
@@ -1152,11 +1289,15 @@ pub async fn generate_organization_plan(
             }
         };
 
-        let dest_path = format!("{}\\{}\\{}", organize_base, dest_folder.replace("/", "\\"), filename);
+        // dest_folder uses "/" between levels; join each level so the platform separator is used
+        let folder_path = dest_folder
+            .split('/')
+            .filter(|part| !part.is_empty())
+            .fold(PathBuf::from(organize_base), |path, part| path.join(part));
+        let dest_path = folder_path.join(&filename).to_string_lossy().to_string();
 
         // Track folders to create
-        let folder_path = format!("{}\\{}", organize_base, dest_folder.replace("/", "\\"));
-        folders_to_create.insert(folder_path);
+        folders_to_create.insert(folder_path.to_string_lossy().to_string());
 
         // Determine if review is needed
         // - Low AI confidence (<0.35) - lowered from 0.6 to reduce "needs review" count
@@ -1211,33 +1352,6 @@ pub async fn generate_organization_plan(
         OrganizationStyle::SmartGroups => "Smart Groups Organization",
     };
 
-    // Save plan to database
-    {
-        let conn = crate::db::open_connection(&db_path_clone).map_err(|e| e.to_string())?;
-
-        conn.execute(
-            "INSERT INTO organization_plans (id, name, organization_style, status) VALUES (?1, ?2, ?3, 'pending')",
-            rusqlite::params![&plan_id, plan_name, format!("{:?}", style_clone).to_lowercase()],
-        ).map_err(|e| e.to_string())?;
-
-        // Save plan items
-        for item in &items {
-            conn.execute(
-                "INSERT INTO plan_items (plan_id, file_id, source_path, destination_path, confidence, reason, requires_review)
-                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-                rusqlite::params![
-                    &plan_id,
-                    item.file_id,
-                    &item.source_path,
-                    &item.destination_path,
-                    item.confidence,
-                    &item.reason,
-                    item.requires_review as i32
-                ],
-            ).map_err(|e| e.to_string())?;
-        }
-    }
-
     let folders_vec: Vec<String> = folders_to_create.into_iter().collect();
     let total_files = items.len();
     let _ = skipped_organized; // Suppress unused warning
@@ -1251,15 +1365,47 @@ pub async fn generate_organization_plan(
             total_files,
             high_confidence,
             low_confidence,
-            duplicates_found,
+            duplicates_found: 0,
             folders_to_create: folders_vec,
         },
     })
 }
 
+/// Save a plan and its items (status 'pending') so `execute_plan` can run it
+pub(crate) fn save_organization_plan(
+    conn: &Connection,
+    plan: &OrganizationPlan,
+    organization_style: &str,
+) -> Result<(), String> {
+    conn.execute(
+        "INSERT INTO organization_plans (id, name, organization_style, status) VALUES (?1, ?2, ?3, 'pending')",
+        rusqlite::params![&plan.id, &plan.name, organization_style],
+    ).map_err(|e| e.to_string())?;
+
+    // Save plan items
+    for item in &plan.items {
+        conn.execute(
+            "INSERT INTO plan_items (plan_id, file_id, source_path, destination_path, confidence, reason, requires_review)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            rusqlite::params![
+                &plan.id,
+                item.file_id,
+                &item.source_path,
+                &item.destination_path,
+                item.confidence,
+                &item.reason,
+                item.requires_review as i32
+            ],
+        ).map_err(|e| e.to_string())?;
+    }
+
+    Ok(())
+}
+
 /// Result of executing a plan
 #[derive(Debug, Clone, Serialize)]
 pub struct ExecutionResult {
+    pub session_id: String,
     pub files_moved: usize,
     pub files_failed: usize,
     pub files_skipped: usize,
@@ -1349,16 +1495,41 @@ fn get_unique_path(dest: &std::path::Path) -> Option<std::path::PathBuf> {
     None
 }
 
+/// Path identity for a plan's destinations, probed once per execution
+/// The root is the folder holding every destination (the organize base the plan
+/// was built for), or its nearest existing ancestor before the first move creates it.
+fn destination_identity<'a>(destinations: impl IntoIterator<Item = &'a str>) -> PathIdentity {
+    let mut root: Option<PathBuf> = None;
+    for destination in destinations {
+        let parent = Path::new(destination).parent().unwrap_or(Path::new(""));
+        root = Some(match root {
+            None => parent.to_path_buf(),
+            Some(current) => current
+                .ancestors()
+                .find(|dir| parent.starts_with(dir))
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+        });
+    }
+
+    let roots: Vec<PathBuf> = root
+        .and_then(|root| root.ancestors().find(|dir| dir.is_dir()).map(Path::to_path_buf))
+        .into_iter()
+        .collect();
+    PathIdentity::new(&roots)
+}
+
 /// Safely update file path in database, handling UNIQUE constraint
-fn update_file_path_safe(conn: &Connection, file_id: i64, new_path: &str) -> Result<(), String> {
-    // First check if another file already has this path
-    let existing_id: Option<i64> = conn.query_row(
-        "SELECT id FROM files WHERE path = ?1 AND id != ?2",
-        rusqlite::params![new_path, file_id],
-        |row| row.get(0),
-    ).ok();
-
-    if let Some(other_id) = existing_id {
+fn update_file_path_safe(
+    conn: &Connection,
+    file_id: i64,
+    new_path: &str,
+    identity: &PathIdentity,
+) -> Result<(), String> {
+    // First check if another file already has this path (in any spelling that names it)
+    let existing_ids = indexer::file_ids_at(conn, new_path, identity).map_err(|e| e.to_string())?;
+
+    for other_id in existing_ids.into_iter().filter(|id| *id != file_id) {
         // Another record has this path - it's likely a stale record
         // Delete the stale record (the file at that path is now this file)
         conn.execute(
@@ -1369,6 +1540,10 @@ fn update_file_path_safe(conn: &Connection, file_id: i64, new_path: &str) -> Res
             "DELETE FROM content_snippets WHERE file_id = ?1",
             rusqlite::params![other_id],
         ).ok();
+        conn.execute(
+            "DELETE FROM document_metadata WHERE file_id = ?1",
+            rusqlite::params![other_id],
+        ).ok();
         conn.execute(
             "DELETE FROM files WHERE id = ?1",
             rusqlite::params![other_id],
@@ -1377,8 +1552,8 @@ fn update_file_path_safe(conn: &Connection, file_id: i64, new_path: &str) -> Res
 
     // Now safe to update
     conn.execute(
-        "UPDATE files SET path = ?1, last_scanned_at = CURRENT_TIMESTAMP WHERE id = ?2",
-        rusqlite::params![new_path, file_id],
+        "UPDATE files SET path = ?1, path_key = ?3, last_scanned_at = CURRENT_TIMESTAMP WHERE id = ?2",
+        rusqlite::params![new_path, file_id, path_identity::index_key(new_path)],
     ).map_err(|e| e.to_string())?;
 
     Ok(())
@@ -1393,7 +1568,6 @@ pub async fn execute_plan(
     test_mode: Option<bool>,
     db_path: State<'_, DbPath>,
 ) -> Result<ExecutionResult, String> {
-    let db_path_clone = db_path.0.clone();
     let _use_staging = stage_first.unwrap_or(true);
     let is_test_mode = test_mode.unwrap_or(false);
     let excluded: std::collections::HashSet<i64> = excluded_file_ids
@@ -1402,7 +1576,20 @@ pub async fn execute_plan(
         .collect();
 
     // Open a single DB connection for the entire execution
-    let conn = crate::db::open_connection(&db_path_clone).map_err(|e| e.to_string())?;
+    let conn = crate::db::open_connection(&db_path.0).map_err(|e| e.to_string())?;
+    execute_plan_items(&conn, &plan_id, &excluded, is_test_mode, "execute_plan")
+}
+
+/// Move a saved plan's pending items (except `excluded`) inside one activity log session
+/// recorded with `session_mode`, so the session can be undone with the activity log commands
+pub(crate) fn execute_plan_items(
+    conn: &Connection,
+    plan_id: &str,
+    excluded: &std::collections::HashSet<i64>,
+    is_test_mode: bool,
+    session_mode: &str,
+) -> Result<ExecutionResult, String> {
+    let plan_id = plan_id.to_string();
 
     // Load plan items from database
     let items: Vec<(i64, String, String)> = {
@@ -1427,9 +1614,11 @@ pub async fn execute_plan(
     };
 
     // Create an activity log session for this execution
-    let session_id = activity_log::create_session(&conn, Some("execute_plan"), None)
+    let session_id = activity_log::create_session(conn, Some(session_mode), None)
         .map_err(|e| format!("Failed to create activity session: {}", e))?;
 
+    let identity = destination_identity(items.iter().map(|(_, _, destination)| destination.as_str()));
+
     let mut files_moved = 0;
     let mut files_skipped = 0;
     let mut files_failed = 0;
@@ -1459,7 +1648,7 @@ pub async fn execute_plan(
                 "UPDATE plan_items SET status = 'completed' WHERE plan_id = ?1 AND file_id = ?2",
                 rusqlite::params![&plan_id, file_id],
             ).map_err(|e| e.to_string())?;
-            if let Err(e) = update_file_path_safe(&conn, *file_id, destination_path) {
+            if let Err(e) = update_file_path_safe(conn, *file_id, destination_path, &identity) {
                 warnings.push(format!("Database update warning for {}: {}", destination_path, e));
             }
             continue;
@@ -1503,7 +1692,7 @@ pub async fn execute_plan(
         let extension = source.extension().map(|e| e.to_string_lossy().to_string());
 
         // Log operation to activity log before attempting move
-        let op_id = activity_log::log_operation(&conn, &session_id, &Operation {
+        let op_id = activity_log::log_operation(conn, &session_id, &Operation {
             op_type: OperationType::Move,
             source_path: Some(source_path.clone()),
             destination_path: Some(final_dest_path.clone()),
@@ -1521,7 +1710,7 @@ pub async fn execute_plan(
                 "UPDATE plan_items SET status = 'completed' WHERE plan_id = ?1 AND file_id = ?2",
                 rusqlite::params![&plan_id, file_id],
             ).map_err(|e| e.to_string())?;
-            activity_log::update_operation_status(&conn, &session_id, op_id, OperationStatus::Completed, None)
+            activity_log::update_operation_status(conn, &session_id, op_id, OperationStatus::Completed, None)
                 .map_err(|e| format!("Failed to update operation status: {}", e))?;
             files_moved += 1;
             continue;
@@ -1550,7 +1739,7 @@ pub async fn execute_plan(
                         errors.push(error_msg);
                         files_failed += 1;
                         activity_log::update_operation_status(
-                            &conn, &session_id, op_id, OperationStatus::Failed,
+                            conn, &session_id, op_id, OperationStatus::Failed,
                             Some(&format!("Move failed: {}", copy_err)),
                         ).ok();
                         false
@@ -1579,11 +1768,11 @@ pub async fn execute_plan(
                 Ok(_) => {
                     // Update activity log
                     activity_log::update_operation_status(
-                        &conn, &session_id, op_id, OperationStatus::Completed, None,
+                        conn, &session_id, op_id, OperationStatus::Completed, None,
                     ).ok();
 
                     // Update file path (non-critical, outside transaction)
-                    if let Err(e) = update_file_path_safe(&conn, *file_id, &final_dest_path) {
+                    if let Err(e) = update_file_path_safe(conn, *file_id, &final_dest_path, &identity) {
                         warnings.push(format!("Database update warning for {}: {}", final_dest_path, e));
                     }
                     files_moved += 1;
@@ -1614,7 +1803,7 @@ pub async fn execute_plan(
                     }
                     files_failed += 1;
                     activity_log::update_operation_status(
-                        &conn, &session_id, op_id, OperationStatus::Failed,
+                        conn, &session_id, op_id, OperationStatus::Failed,
                         Some(&format!("DB write failed: {}", db_err)),
                     ).ok();
                 }
@@ -1636,9 +1825,10 @@ pub async fn execute_plan(
     } else {
         SessionStatus::Failed
     };
-    activity_log::complete_session(&conn, &session_id, session_status).ok();
+    activity_log::complete_session(conn, &session_id, session_status).ok();
 
     Ok(ExecutionResult {
+        session_id,
         files_moved,
         files_failed,
         files_skipped,
@@ -1723,8 +1913,8 @@ pub async fn undo_last_operation(
                     rusqlite::params![history_id],
                 ).map_err(|e| e.to_string())?;
                 conn.execute(
-                    "UPDATE files SET path = ?1, last_scanned_at = CURRENT_TIMESTAMP WHERE id = ?2",
-                    rusqlite::params![&original_source, file_id],
+                    "UPDATE files SET path = ?1, path_key = ?3, last_scanned_at = CURRENT_TIMESTAMP WHERE id = ?2",
+                    rusqlite::params![&original_source, file_id, path_identity::index_key(&original_source)],
                 ).map_err(|e| e.to_string())?;
                 files_restored += 1;
                 continue;
@@ -1762,8 +1952,8 @@ pub async fn undo_last_operation(
                 ).map_err(|e| e.to_string())?;
 
                 conn.execute(
-                    "UPDATE files SET path = ?1, last_scanned_at = CURRENT_TIMESTAMP WHERE id = ?2",
-                    rusqlite::params![&original_source, file_id],
+                    "UPDATE files SET path = ?1, path_key = ?3, last_scanned_at = CURRENT_TIMESTAMP WHERE id = ?2",
+                    rusqlite::params![&original_source, file_id, path_identity::index_key(&original_source)],
                 ).map_err(|e| e.to_string())?;
 
                 files_restored += 1;
@@ -2486,9 +2676,7 @@ fn extract_project_name_suggestion(filenames: &[String]) -> Option<String> {
 /// Get the path to the Organized Files folder
 #[tauri::command]
 pub fn get_organized_files_path() -> Result<String, String> {
-    let organized_path = dirs::document_dir()
-        .unwrap_or_else(|| std::path::PathBuf::from("C:\\"))
-        .join("Organized Files");
+    let organized_path = documents_dir().join("Organized Files");
     Ok(organized_path.to_string_lossy().to_string())
 }
 
@@ -2498,16 +2686,13 @@ pub async fn open_folder(path: String) -> Result<(), String> {
     // Resolve special paths
     let full_path = if path.starts_with("Organized Files") {
         // Handles "Organized Files", "Organized Files/01 Work", "Organized Files/01 Work/Resumes", etc.
-        let base = dirs::document_dir()
-            .unwrap_or_else(|| std::path::PathBuf::from("C:\\Documents"));
-        base.join(&path)
+        documents_dir().join(&path)
     } else if path.starts_with("Documents/") {
-        let base = dirs::document_dir()
-            .unwrap_or_else(|| std::path::PathBuf::from("C:\\Documents"));
+        let base = documents_dir();
         let subfolder = path.strip_prefix("Documents/").unwrap_or(&path);
         base.join(subfolder)
     } else if path == "Documents" {
-        dirs::document_dir().unwrap_or_else(|| std::path::PathBuf::from("C:\\Documents"))
+        documents_dir()
     } else {
         // Absolute path
         std::path::PathBuf::from(&path)
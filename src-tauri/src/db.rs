@@ -1,3 +1,4 @@
+use crate::path_identity;
 use rusqlite::{Connection, Result};
 use std::path::PathBuf;
 use std::sync::Mutex;
@@ -38,7 +39,10 @@ pub fn init_database(path: &PathBuf) -> Result<()> {
             size INTEGER NOT NULL,
             created_at TEXT,
             modified_at TEXT,
-            content_hash TEXT,
+            quick_hash TEXT,
+            full_hash TEXT,
+            detected_type TEXT,
+            path_key TEXT,
             discovered_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
             last_scanned_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP
         )",
@@ -70,12 +74,57 @@ pub fn init_database(path: &PathBuf) -> Result<()> {
             file_id INTEGER NOT NULL UNIQUE,
             snippet TEXT,
             extraction_method TEXT,
+            extraction_confidence REAL,
             extracted_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
             FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE
         )",
         [],
     )?;
 
+    // Databases created before the extraction pipeline lack this column
+    ensure_column(&conn, "content_snippets", "extraction_confidence", "REAL")?;
+
+    // Document metadata - fields read by the document parsers (title, author, EXIF, ...)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS document_metadata (
+            id INTEGER PRIMARY KEY AUTOINCREMENT,
+            file_id INTEGER NOT NULL UNIQUE,
+            title TEXT,
+            author TEXT,
+            subject TEXT,
+            keywords TEXT,
+            page_count INTEGER,
+            taken_at TEXT,
+            camera_make TEXT,
+            camera_model TEXT,
+            gps_latitude REAL,
+            gps_longitude REAL,
+            width INTEGER,
+            height INTEGER,
+            album TEXT,
+            duration_secs REAL,
+            created_at TEXT,
+            extracted_at TEXT NOT NULL DEFAULT CURRENT_TIMESTAMP,
+            FOREIGN KEY (file_id) REFERENCES files(id) ON DELETE CASCADE
+        )",
+        [],
+    )?;
+
+    // Databases created before media tags were read lack these columns
+    ensure_column(&conn, "document_metadata", "album", "TEXT")?;
+    ensure_column(&conn, "document_metadata", "duration_secs", "REAL")?;
+    ensure_column(&conn, "document_metadata", "created_at", "TEXT")?;
+
+    // Older databases only have content_hash (first 1MB), which is no longer written;
+    // quick_hash stays NULL until the next scan, so every file is re-hashed once
+    ensure_column(&conn, "files", "quick_hash", "TEXT")?;
+    ensure_column(&conn, "files", "full_hash", "TEXT")?;
+    // Filled in for every file, changed or not, on the next scan
+    ensure_column(&conn, "files", "detected_type", "TEXT")?;
+    // Lookup key for every spelling of a path (see path_identity::index_key)
+    ensure_column(&conn, "files", "path_key", "TEXT")?;
+    fill_path_keys(&conn)?;
+
     // Move history - transaction log for undo support
     conn.execute(
         "CREATE TABLE IF NOT EXISTS move_history (
@@ -123,7 +172,13 @@ pub fn init_database(path: &PathBuf) -> Result<()> {
         [],
     )?;
 
-    // Create FTS5 virtual table for full-text search
+    // Full-text search index (FTS5), one row per file keyed by files.id
+    // Older versions created a contentless table that could never be queried for
+    // snippets or updated in place, so replace it and rebuild from the index
+    let fts_current = table_has_column(&conn, "files_fts", "snippet")?;
+    if !fts_current {
+        conn.execute_batch("DROP TABLE IF EXISTS files_fts;")?;
+    }
     conn.execute(
         "CREATE VIRTUAL TABLE IF NOT EXISTS files_fts USING fts5(
             filename,
@@ -131,10 +186,15 @@ pub fn init_database(path: &PathBuf) -> Result<()> {
             category,
             tags,
             summary,
-            content=''
+            snippet,
+            tokenize = 'unicode61 remove_diacritics 2'
         )",
         [],
     )?;
+    create_fts_triggers(&conn)?;
+    if !fts_current {
+        rebuild_fts_index(&conn)?;
+    }
 
     // ========================================
     // Activity Log Tables (per doc 07)
@@ -184,6 +244,16 @@ pub fn init_database(path: &PathBuf) -> Result<()> {
         [],
     )?;
 
+    // Auto-organize progress - a single row kept across restarts (see auto_organize.rs)
+    conn.execute(
+        "CREATE TABLE IF NOT EXISTS auto_organize_state (
+            id INTEGER PRIMARY KEY CHECK (id = 1),
+            last_run_at TEXT,
+            last_processed_at TEXT
+        )",
+        [],
+    )?;
+
     // Errors table - detailed error tracking
     conn.execute(
         "CREATE TABLE IF NOT EXISTS activity_errors (
@@ -214,10 +284,25 @@ pub fn init_database(path: &PathBuf) -> Result<()> {
         "CREATE INDEX IF NOT EXISTS idx_files_extension ON files(extension)",
         [],
     )?;
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_files_size_quick_hash ON files(size, quick_hash)",
+        [],
+    )?;
+    // Path lookups that tolerate case and Unicode normalization differences (see path_identity.rs)
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_files_path_key ON files(path_key)",
+        [],
+    )?;
+    conn.execute("DROP INDEX IF EXISTS idx_files_path_nocase", [])?;
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_ai_metadata_category ON ai_metadata(category)",
         [],
     )?;
+    // Grouping photos and scans by date
+    conn.execute(
+        "CREATE INDEX IF NOT EXISTS idx_document_metadata_taken_at ON document_metadata(taken_at)",
+        [],
+    )?;
     conn.execute(
         "CREATE INDEX IF NOT EXISTS idx_move_history_plan_id ON move_history(plan_id)",
         [],
@@ -251,3 +336,99 @@ pub fn init_database(path: &PathBuf) -> Result<()> {
 
     Ok(())
 }
+
+/// Check whether a table (or virtual table) has a given column
+fn table_has_column(conn: &Connection, table: &str, column: &str) -> Result<bool> {
+    let exists = conn
+        .prepare(&format!("PRAGMA table_info({})", table))?
+        .query_map([], |row| row.get::<_, String>(1))?
+        .filter_map(|r| r.ok())
+        .any(|name| name == column);
+    Ok(exists)
+}
+
+/// Add a column to an existing table if it is missing
+/// CREATE TABLE IF NOT EXISTS won't alter tables from older app versions, so new columns go through here
+fn ensure_column(conn: &Connection, table: &str, column: &str, definition: &str) -> Result<()> {
+    if !table_has_column(conn, table, column)? {
+        conn.execute(
+            &format!("ALTER TABLE {} ADD COLUMN {} {}", table, column, definition),
+            [],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Compute path_key for rows from databases that predate it
+fn fill_path_keys(conn: &Connection) -> Result<()> {
+    let rows: Vec<(i64, String)> = conn
+        .prepare("SELECT id, path FROM files WHERE path_key IS NULL")?
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<Result<_>>()?;
+    if rows.is_empty() {
+        return Ok(());
+    }
+
+    let tx = conn.unchecked_transaction()?;
+    {
+        let mut update = tx.prepare("UPDATE files SET path_key = ?1 WHERE id = ?2")?;
+        for (id, path) in rows {
+            update.execute(rusqlite::params![path_identity::index_key(&path), id])?;
+        }
+    }
+    tx.commit()
+}
+
+/// SQL that replaces the files_fts row for one file with its current filename, path,
+/// classification and content snippet (`id` is a SQL expression such as `new.id`)
+fn fts_refresh_sql(id: &str) -> String {
+    format!(
+        "DELETE FROM files_fts WHERE rowid = {id};
+         INSERT INTO files_fts (rowid, filename, path, category, tags, summary, snippet)
+         SELECT f.id, f.filename, f.path, m.category, m.tags, m.summary, cs.snippet
+         FROM files f
+         LEFT JOIN ai_metadata m ON m.file_id = f.id
+         LEFT JOIN content_snippets cs ON cs.file_id = f.id
+         WHERE f.id = {id};",
+        id = id
+    )
+}
+
+/// Keep files_fts in sync with files, ai_metadata and content_snippets
+/// Triggers cover every write path (scan, classify, move, undo, delete) without each
+/// command having to remember to update the index
+fn create_fts_triggers(conn: &Connection) -> Result<()> {
+    let triggers = [
+        ("files_fts_files_insert", "AFTER INSERT ON files", fts_refresh_sql("new.id")),
+        ("files_fts_files_update", "AFTER UPDATE OF filename, path ON files", fts_refresh_sql("new.id")),
+        ("files_fts_files_delete", "AFTER DELETE ON files", "DELETE FROM files_fts WHERE rowid = old.id;".to_string()),
+        ("files_fts_metadata_insert", "AFTER INSERT ON ai_metadata", fts_refresh_sql("new.file_id")),
+        ("files_fts_metadata_update", "AFTER UPDATE ON ai_metadata", fts_refresh_sql("new.file_id")),
+        ("files_fts_metadata_delete", "AFTER DELETE ON ai_metadata", fts_refresh_sql("old.file_id")),
+        ("files_fts_snippets_insert", "AFTER INSERT ON content_snippets", fts_refresh_sql("new.file_id")),
+        ("files_fts_snippets_update", "AFTER UPDATE ON content_snippets", fts_refresh_sql("new.file_id")),
+        ("files_fts_snippets_delete", "AFTER DELETE ON content_snippets", fts_refresh_sql("old.file_id")),
+    ];
+
+    for (name, event, body) in triggers {
+        conn.execute_batch(&format!(
+            "CREATE TRIGGER IF NOT EXISTS {} {} BEGIN {} END;",
+            name, event, body
+        ))?;
+    }
+
+    Ok(())
+}
+
+/// Rebuild the full-text index from scratch
+pub fn rebuild_fts_index(conn: &Connection) -> Result<()> {
+    conn.execute_batch(
+        "DELETE FROM files_fts;
+         INSERT INTO files_fts (rowid, filename, path, category, tags, summary, snippet)
+         SELECT f.id, f.filename, f.path, m.category, m.tags, m.summary, cs.snippet
+         FROM files f
+         LEFT JOIN ai_metadata m ON m.file_id = f.id
+         LEFT JOIN content_snippets cs ON cs.file_id = f.id;",
+    )
+}
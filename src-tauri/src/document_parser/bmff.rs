@@ -0,0 +1,75 @@
+//! ISO base media file format boxes (shared by the HEIF image and MP4/MOV media parsers)
+//!
+//! Every box starts with a 32-bit big-endian size (1: a 64-bit size follows the type,
+//! 0: the box runs to the end) and a four-character type.
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+pub(super) fn be_u32(data: &[u8], at: usize) -> u32 {
+    data.get(at..at + 4).map_or(0, |b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]))
+}
+
+/// The (type, body) boxes packed in a box body
+pub(super) fn child_boxes(mut data: &[u8]) -> Vec<([u8; 4], &[u8])> {
+    let mut boxes = Vec::new();
+    while data.len() >= 8 {
+        let kind = [data[4], data[5], data[6], data[7]];
+        let (size, header_length) = match be_u32(data, 0) as usize {
+            0 => (data.len(), 8),
+            1 if data.len() >= 16 => (u64::from_be_bytes(data[8..16].try_into().unwrap_or_default()) as usize, 16),
+            size => (size, 8),
+        };
+        if size < header_length || size > data.len() {
+            break;
+        }
+        boxes.push((kind, &data[header_length..size]));
+        data = &data[size..];
+    }
+    boxes
+}
+
+/// Body of the first child box of a type
+pub(super) fn find_box<'a>(data: &'a [u8], kind: &[u8; 4]) -> Option<&'a [u8]> {
+    child_boxes(data).into_iter().find(|(k, _)| k == kind).map(|(_, body)| body)
+}
+
+/// Body of the first top-level box of a type, read up to `max_length` bytes.
+/// Other boxes (such as multi-gigabyte mdat) are skipped without being read.
+pub(super) fn read_top_level_box<R: Read + Seek>(
+    reader: &mut R,
+    kind: &[u8; 4],
+    max_length: u64,
+) -> io::Result<Option<Vec<u8>>> {
+    reader.seek(SeekFrom::Start(0))?;
+    loop {
+        let start = reader.stream_position()?;
+        let mut header = [0u8; 8];
+        if reader.read_exact(&mut header).is_err() {
+            return Ok(None);
+        }
+        let mut size = be_u32(&header, 0) as u64;
+        let mut header_length = 8;
+        if size == 1 {
+            let mut large = [0u8; 8];
+            reader.read_exact(&mut large)?;
+            size = u64::from_be_bytes(large);
+            header_length = 16;
+        }
+
+        if &header[4..8] == kind {
+            // Size 0 runs to the end of the file
+            let body_length = if size == 0 { max_length } else { size.saturating_sub(header_length) };
+            let mut body = Vec::new();
+            reader.take(body_length.min(max_length)).read_to_end(&mut body)?;
+            return Ok(Some(body));
+        }
+        if size < header_length {
+            return Ok(None);
+        }
+        // A size past the end of u64 leaves nothing after this box
+        let Some(next) = start.checked_add(size) else {
+            return Ok(None);
+        };
+        reader.seek(SeekFrom::Start(next))?;
+    }
+}
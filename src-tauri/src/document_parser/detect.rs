@@ -0,0 +1,235 @@
+//! Content-based file type detection
+//!
+//! Identifies a file from its leading bytes, and for ZIP and OLE containers from the
+//! entries inside, so misnamed and extensionless files still reach the right parser.
+//! Types are named by their canonical extension ("pdf", "docx", "jpg", ...), which is
+//! what `ParserRegistry::for_file` looks up.
+
+use super::ole::open_compound;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use zip::ZipArchive;
+
+/// Bytes from the start of a file that `header_type` looks at
+pub const SNIFF_BYTES: usize = 1024;
+
+const OLE_SIGNATURE: &[u8] = b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1";
+
+/// Detect a file's type from its content, as a canonical extension; None for empty,
+/// unreadable or unrecognised binary files
+pub fn detect_type(path: &Path) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let mut header = Vec::with_capacity(SNIFF_BYTES);
+    (&mut file).take(SNIFF_BYTES as u64).read_to_end(&mut header).ok()?;
+
+    let detected = if header.starts_with(b"PK\x03\x04") || header.starts_with(b"PK\x05\x06") {
+        zip_type(file)
+    } else if header.starts_with(OLE_SIGNATURE) {
+        ole_type(path)
+    } else {
+        header_type(&header)?
+    };
+    Some(detected.to_string())
+}
+
+/// OpenDocument and EPUB name their type in a mimetype entry; Office Open XML by its main part
+fn zip_type(file: File) -> &'static str {
+    let Ok(mut archive) = ZipArchive::new(file) else {
+        return "zip";
+    };
+
+    if let Ok(entry) = archive.by_name("mimetype") {
+        let mut mimetype = String::new();
+        if entry.take(100).read_to_string(&mut mimetype).is_ok() {
+            match mimetype.trim() {
+                "application/vnd.oasis.opendocument.text" => return "odt",
+                "application/vnd.oasis.opendocument.spreadsheet" => return "ods",
+                "application/vnd.oasis.opendocument.presentation" => return "odp",
+                "application/epub+zip" => return "epub",
+                _ => {}
+            }
+        }
+    }
+
+    [("word/document.xml", "docx"), ("xl/workbook.xml", "xlsx"), ("ppt/presentation.xml", "pptx")]
+        .into_iter()
+        .find(|(part, _)| archive.index_for_name(part).is_some())
+        .map_or("zip", |(_, kind)| kind)
+}
+
+/// Legacy Office and Outlook files by the streams at the root of the compound file
+fn ole_type(path: &Path) -> &'static str {
+    let Ok(compound) = open_compound(path) else {
+        return "ole";
+    };
+
+    if compound.is_stream("/WordDocument") {
+        "doc"
+    } else if compound.is_stream("/Workbook") || compound.is_stream("/Book") {
+        "xls"
+    } else if compound.is_stream("/PowerPoint Document") {
+        "ppt"
+    } else if compound.is_stream("/__properties_version1.0") {
+        "msg"
+    } else {
+        "ole"
+    }
+}
+
+/// Types recognisable from the leading bytes alone
+fn header_type(header: &[u8]) -> Option<&'static str> {
+    Some(if header.is_empty() {
+        return None;
+    } else if is_pdf(header) {
+        "pdf"
+    } else if is_rtf(header) {
+        "rtf"
+    } else if header.starts_with(b"\xFF\xD8\xFF") {
+        "jpg"
+    } else if header.starts_with(b"\x89PNG\r\n\x1a\n") {
+        "png"
+    } else if header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a") {
+        "gif"
+    } else if header.starts_with(b"II*\0") || header.starts_with(b"MM\0*") {
+        "tif"
+    } else if is_image(header) {
+        "heic"
+    // UTF-16 text starts with a byte order mark that also looks like an MPEG frame header
+    } else if header.starts_with(b"\xFF\xFE") || header.starts_with(b"\xFE\xFF") {
+        "txt"
+    } else if header.starts_with(b"fLaC") {
+        "flac"
+    } else if header.get(4..8) == Some(b"ftyp") {
+        if header.get(8..11) == Some(b"M4A") { "m4a" } else { "mp4" }
+    } else if is_media(header) {
+        "mp3"
+    } else if is_mhtml(header) {
+        "mhtml"
+    } else if is_eml(header) {
+        "eml"
+    } else if is_mbox(header) {
+        "mbox"
+    } else if is_html(header) {
+        "html"
+    } else if is_text(header) {
+        "txt"
+    } else {
+        return None;
+    })
+}
+
+/// No control characters besides whitespace (UTF-8 and single-byte encodings alike)
+fn is_text(header: &[u8]) -> bool {
+    header.iter().all(|&b| b >= 0x20 || matches!(b, b'\t' | b'\n' | b'\r' | 0x0C))
+}
+
+fn is_rtf(header: &[u8]) -> bool {
+    header.starts_with(b"{\\rtf")
+}
+
+/// Readers accept junk before the header, so look anywhere in the sniffed bytes
+fn is_pdf(header: &[u8]) -> bool {
+    header.windows(5).any(|w| w == b"%PDF-")
+}
+
+/// Leading text, lowercased, without a UTF-8 byte order mark or leading whitespace
+fn leading_text(header: &[u8]) -> String {
+    let header = header.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(header);
+    String::from_utf8_lossy(header).trim_start().to_ascii_lowercase()
+}
+
+fn is_eml(header: &[u8]) -> bool {
+    let text = leading_text(header);
+    ["return-path:", "received:", "from:", "delivered-to:", "mime-version:", "message-id:", "date:"]
+        .iter()
+        .any(|field| text.starts_with(field))
+}
+
+fn is_mhtml(header: &[u8]) -> bool {
+    is_eml(header) && leading_text(header).contains("multipart/related")
+}
+
+fn is_mbox(header: &[u8]) -> bool {
+    header.starts_with(b"From ")
+}
+
+fn is_html(header: &[u8]) -> bool {
+    let text = leading_text(header);
+    text.starts_with("<!doctype html") || text.starts_with("<html")
+}
+
+/// ISO base media brands that are HEIF images rather than audio or video
+const HEIF_BRANDS: &[&[u8]] = &[b"heic", b"heix", b"hevc", b"heim", b"heis", b"mif1", b"msf1"];
+
+fn is_image(header: &[u8]) -> bool {
+    header.starts_with(b"\xFF\xD8\xFF")
+        || header.starts_with(b"\x89PNG\r\n\x1a\n")
+        || header.starts_with(b"II*\0")
+        || header.starts_with(b"MM\0*")
+        || (header.get(4..8) == Some(b"ftyp") && header.get(8..12).is_some_and(|brand| HEIF_BRANDS.contains(&brand)))
+}
+
+fn is_media(header: &[u8]) -> bool {
+    header.starts_with(b"ID3")
+        || header.starts_with(b"fLaC")
+        // MPEG audio frame sync without an ID3 tag
+        || (header.len() >= 2 && header[0] == 0xFF && header[1] & 0xE0 == 0xE0)
+        || (header.get(4..8) == Some(b"ftyp") && !is_image(header))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    #[test]
+    fn test_detect_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, bytes: &[u8]| {
+            let path = dir.path().join(name);
+            std::fs::write(&path, bytes).unwrap();
+            detect_type(&path)
+        };
+
+        assert_eq!(write("scan", b"%PDF-1.4\n%\xE2\xE3\xCF\xD3\n").as_deref(), Some("pdf"));
+        assert_eq!(write("photo.dat", b"\xFF\xD8\xFF\xE0\0\x10JFIF").as_deref(), Some("jpg"));
+        assert_eq!(write("letter", b"{\\rtf1\\ansi Hello}").as_deref(), Some("rtf"));
+        assert_eq!(write("README", "Plain notes, caf\u{e9}\n".as_bytes()).as_deref(), Some("txt"));
+        assert_eq!(write("utf16", b"\xFF\xFEH\0i\0").as_deref(), Some("txt"));
+        assert_eq!(write("blob", b"\0\x01\x02\x03binary").as_deref(), None);
+        assert_eq!(write("empty", b"").as_deref(), None);
+
+        // A DOCX renamed .zip and a plain archive
+        let zip_with = |name: &str, parts: &[&str]| {
+            let path = dir.path().join(name);
+            let mut zip = ZipWriter::new(File::create(&path).unwrap());
+            for part in parts {
+                zip.start_file(*part, SimpleFileOptions::default()).unwrap();
+                zip.write_all(b"<x/>").unwrap();
+            }
+            zip.finish().unwrap();
+            detect_type(&path)
+        };
+        assert_eq!(zip_with("report.zip", &["[Content_Types].xml", "word/document.xml"]).as_deref(), Some("docx"));
+        assert_eq!(zip_with("photos.zip", &["a.jpg"]).as_deref(), Some("zip"));
+    }
+
+    #[test]
+    fn test_header_type() {
+        assert_eq!(header_type(b"%PDF-1.7\n"), Some("pdf"));
+        assert_eq!(header_type(b"\xFF\xD8\xFF\xE1"), Some("jpg"));
+        assert_eq!(header_type(b"\0\0\0\x18ftypheic\0\0\0\0"), Some("heic"));
+        assert_eq!(header_type(b"\0\0\0\x18ftypisom\0\0\0\0"), Some("mp4"));
+        assert_eq!(header_type(b"ID3\x03\0"), Some("mp3"));
+        assert_eq!(header_type(b"\xEF\xBB\xBF  <!DOCTYPE html><html>"), Some("html"));
+        assert_eq!(header_type(b"From: a@b.c\r\nSubject: Hi\r\n"), Some("eml"));
+        assert_eq!(
+            header_type(b"From: <Saved by Blink>\r\nContent-Type: multipart/related; boundary=x\r\n"),
+            Some("mhtml")
+        );
+        assert_eq!(header_type(b"just some words"), Some("txt"));
+    }
+}
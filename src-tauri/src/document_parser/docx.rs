@@ -1,10 +1,11 @@
 //! DOCX Parser (Group 2 - Office Open XML)
 //!
 //! Handles: .docx files
-//! Strategy: Unzip → read word/document.xml → extract text from XML
+//! Strategy: Unzip → read word/document.xml plus headers, footers, footnotes and
+//! comments → extract text from XML, tables one row per line
 
 use super::{DocumentMetadata, ParseError, ParsedDocument};
-use quick_xml::events::Event;
+use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
 use std::fs::File;
 use std::io::Read;
@@ -24,11 +25,48 @@ pub fn extract_docx(path: &Path, max_chars: usize) -> Result<ParsedDocument, Par
     let mut archive = ZipArchive::new(file)
         .map_err(|e| ParseError::ParseError(format!("Invalid DOCX file (not a valid ZIP): {}", e)))?;
 
-    // Extract metadata from docProps/core.xml
-    let metadata = extract_metadata(&mut archive).unwrap_or_default();
+    // Extract metadata from docProps/core.xml and docProps/custom.xml
+    let mut metadata = extract_metadata(&mut archive).unwrap_or_default();
+    if let Some(xml_content) = read_part(&mut archive, "docProps/custom.xml") {
+        metadata.custom_properties = read_custom_properties(&xml_content);
+    }
+
+    // Letterheads and custom properties come first: they often name the client or company
+    let mut sections = Vec::new();
+    if !metadata.custom_properties.is_empty() {
+        let lines: Vec<String> = metadata.custom_properties.iter().map(|(name, value)| format!("{}: {}", name, value)).collect();
+        sections.push(format!("[Properties]\n{}", lines.join("\n")));
+    }
+    for header in part_texts(&mut archive, "header", max_chars)? {
+        sections.push(format!("[Header]\n{}", header));
+    }
 
     // Extract text from word/document.xml
-    let content = extract_document_text(&mut archive, max_chars)?;
+    let document_xml = read_part(&mut archive, "word/document.xml")
+        .ok_or_else(|| ParseError::ParseError("DOCX file missing word/document.xml".to_string()))?;
+    let body = extract_part_text(&document_xml, max_chars)?;
+    if !body.is_empty() {
+        // Mark where the body starts only when other sections precede it
+        sections.push(if sections.is_empty() { body } else { format!("[Body]\n{}", body) });
+    }
+
+    for footer in part_texts(&mut archive, "footer", max_chars)? {
+        sections.push(format!("[Footer]\n{}", footer));
+    }
+    for (part, label) in [("word/footnotes.xml", "Footnotes"), ("word/endnotes.xml", "Endnotes"), ("word/comments.xml", "Comments")] {
+        if let Some(xml_content) = read_part(&mut archive, part) {
+            let text = extract_part_text(&xml_content, max_chars)?;
+            if !text.is_empty() {
+                sections.push(format!("[{}]\n{}", label, text));
+            }
+        }
+    }
+
+    // Truncate to max_chars (char-safe for multi-byte UTF-8)
+    let mut content = sections.join("\n\n");
+    if content.chars().count() > max_chars {
+        content = content.chars().take(max_chars).collect();
+    }
 
     // Calculate confidence based on content quality
     let word_count = content.split_whitespace().count() as u32;
@@ -95,64 +133,141 @@ fn extract_metadata(archive: &mut ZipArchive<File>) -> Result<DocumentMetadata,
     Ok(metadata)
 }
 
-/// Extract text content from word/document.xml
-fn extract_document_text(archive: &mut ZipArchive<File>, max_chars: usize) -> Result<String, ParseError> {
-    let mut document_file = archive
-        .by_name("word/document.xml")
-        .map_err(|_| ParseError::ParseError("DOCX file missing word/document.xml".to_string()))?;
-
+fn read_part(archive: &mut ZipArchive<File>, name: &str) -> Option<String> {
+    let mut part = archive.by_name(name).ok()?;
     let mut xml_content = String::new();
-    document_file
-        .read_to_string(&mut xml_content)
-        .map_err(|e| ParseError::ReadError(e.to_string()))?;
+    part.read_to_string(&mut xml_content).ok()?;
+    Some(xml_content)
+}
 
-    // Parse XML and extract text from <w:t> elements
-    let mut reader = Reader::from_str(&xml_content);
+/// Name/value pairs from docProps/custom.xml (<property name="Client"><vt:lpwstr>Acme</vt:lpwstr></property>)
+pub(super) fn read_custom_properties(xml_content: &str) -> Vec<(String, String)> {
+    let mut reader = Reader::from_str(xml_content);
     reader.config_mut().trim_text(true);
 
+    let mut properties = Vec::new();
+    let mut current: Option<(String, String)> = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) if e.local_name().as_ref() == b"property" => {
+                let name = e
+                    .try_get_attribute("name")
+                    .ok()
+                    .flatten()
+                    .and_then(|attr| attr.unescape_value().ok().map(|v| v.trim().to_string()));
+                current = name.map(|name| (name, String::new()));
+            }
+            Ok(Event::Text(e)) => {
+                if let Some((_, value)) = current.as_mut() {
+                    value.push_str(&e.unescape().unwrap_or_default());
+                }
+            }
+            Ok(Event::End(e)) if e.local_name().as_ref() == b"property" => {
+                if let Some((name, value)) = current.take().filter(|(name, value)| !name.is_empty() && !value.is_empty()) {
+                    properties.push((name, value));
+                }
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    properties
+}
+
+/// Text of the numbered header or footer parts (word/header1.xml, ...), duplicates removed:
+/// first-page and even-page variants often repeat the default one
+fn part_texts(archive: &mut ZipArchive<File>, kind: &str, max_chars: usize) -> Result<Vec<String>, ParseError> {
+    let prefix = format!("word/{}", kind);
+    let mut names: Vec<String> = archive
+        .file_names()
+        .filter(|name| {
+            name.strip_prefix(&prefix)
+                .and_then(|rest| rest.strip_suffix(".xml"))
+                .is_some_and(|number| number.chars().all(|c| c.is_ascii_digit()))
+        })
+        .map(String::from)
+        .collect();
+    // header2.xml before header10.xml
+    names.sort_by_key(|name| (name.len(), name.clone()));
+
+    let mut texts: Vec<String> = Vec::new();
+    for name in names {
+        if let Some(xml_content) = read_part(archive, &name) {
+            let text = extract_part_text(&xml_content, max_chars)?;
+            if !text.is_empty() && !texts.contains(&text) {
+                texts.push(text);
+            }
+        }
+    }
+    Ok(texts)
+}
+
+/// Author attribute of a <w:comment>
+fn comment_author(e: &BytesStart) -> Option<String> {
+    e.try_get_attribute("w:author")
+        .ok()
+        .flatten()
+        .and_then(|attr| attr.unescape_value().ok().map(|v| v.trim().to_string()))
+        .filter(|author| !author.is_empty())
+}
+
+/// Extract text from a WordprocessingML part (document, header, footer, footnotes, comments).
+/// Paragraphs become lines; table rows become one line with cells separated by " | ".
+fn extract_part_text(xml_content: &str, max_chars: usize) -> Result<String, ParseError> {
+    // Whitespace inside <w:t> is significant ("Hello " + "World" runs)
+    let mut reader = Reader::from_str(xml_content);
+
     let mut content = String::new();
     let mut in_text_element = false;
-    let mut in_paragraph = false;
+    // Nesting depth of table cells; nested tables are flattened into their outer cell
+    let mut cell_depth = 0;
+    let mut cell = String::new();
+    let mut row: Vec<String> = Vec::new();
     let mut buf = Vec::new();
 
     loop {
+        let target = if cell_depth > 0 { &mut cell } else { &mut content };
         match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                let name = e.name();
-                let local_name = String::from_utf8_lossy(name.as_ref());
-
-                match local_name.as_ref() {
-                    "w:t" => in_text_element = true,
-                    "w:p" => in_paragraph = true,
-                    _ => {}
+            Ok(Event::Start(e)) => match e.name().as_ref() {
+                b"w:t" => in_text_element = true,
+                b"w:tc" => cell_depth += 1,
+                b"w:comment" => {
+                    if let Some(author) = comment_author(&e) {
+                        target.push_str(&format!("{}: ", author));
+                    }
                 }
-            }
-            Ok(Event::End(e)) => {
-                let name = e.name();
-                let local_name = String::from_utf8_lossy(name.as_ref());
-
-                match local_name.as_ref() {
-                    "w:t" => in_text_element = false,
-                    "w:p" => {
-                        if in_paragraph && !content.is_empty() {
-                            content.push('\n');
-                        }
-                        in_paragraph = false;
+                _ => {}
+            },
+            Ok(Event::Empty(e)) => match e.name().as_ref() {
+                b"w:tab" => target.push('\t'),
+                b"w:br" | b"w:cr" => target.push(if cell_depth > 0 { ' ' } else { '\n' }),
+                _ => {}
+            },
+            Ok(Event::End(e)) => match e.name().as_ref() {
+                b"w:t" => in_text_element = false,
+                b"w:p" => target.push(if cell_depth > 0 { ' ' } else { '\n' }),
+                b"w:tc" => {
+                    cell_depth -= 1;
+                    if cell_depth == 0 {
+                        row.push(cell.split_whitespace().collect::<Vec<_>>().join(" "));
+                        cell.clear();
+                    } else {
+                        cell.push(' ');
                     }
-                    _ => {}
                 }
-            }
-            Ok(Event::Text(e)) => {
-                if in_text_element {
-                    let text = e.unescape().unwrap_or_default();
-                    content.push_str(&text);
-
-                    // Check if we've reached max_chars (char-safe for multi-byte UTF-8)
-                    if content.chars().count() >= max_chars {
-                        content = content.chars().take(max_chars).collect::<String>();
-                        break;
+                b"w:tr" if cell_depth == 0 => {
+                    if row.iter().any(|c| !c.is_empty()) {
+                        content.push_str(&row.join(" | "));
+                        content.push('\n');
                     }
+                    row.clear();
                 }
+                _ => {}
+            },
+            Ok(Event::Text(e)) if in_text_element => {
+                target.push_str(&e.unescape().unwrap_or_default());
             }
             Ok(Event::Eof) => break,
             Err(e) => {
@@ -161,6 +276,12 @@ fn extract_document_text(archive: &mut ZipArchive<File>, max_chars: usize) -> Re
             _ => {}
         }
         buf.clear();
+
+        // Check if we've reached max_chars (char-safe for multi-byte UTF-8)
+        if cell_depth == 0 && content.chars().count() >= max_chars {
+            content = content.chars().take(max_chars).collect::<String>();
+            break;
+        }
     }
 
     // Clean up: normalize whitespace
@@ -176,6 +297,81 @@ fn extract_document_text(archive: &mut ZipArchive<File>, max_chars: usize) -> Re
 
 #[cfg(test)]
 mod tests {
-    // Tests would require actual .docx files
-    // In production, consider adding test fixtures
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn write_docx(path: &Path, parts: &[(&str, &str)]) {
+        let mut zip = ZipWriter::new(File::create(path).unwrap());
+        for (name, xml) in parts {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(xml.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_extract_headers_tables_notes_and_custom_properties() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("invoice.docx");
+        let letterhead = r#"<w:hdr><w:p><w:r><w:t>ACME Corp</w:t></w:r></w:p></w:hdr>"#;
+        write_docx(
+            &path,
+            &[
+                (
+                    "word/document.xml",
+                    r#"<w:document><w:body>
+                        <w:p><w:r><w:t xml:space="preserve">Invoice </w:t></w:r><w:r><w:t>1042</w:t></w:r></w:p>
+                        <w:tbl>
+                            <w:tr><w:tc><w:p><w:r><w:t>Item</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>Amount</w:t></w:r></w:p></w:tc></w:tr>
+                            <w:tr><w:tc><w:p><w:r><w:t>Design</w:t></w:r></w:p><w:p><w:r><w:t>work</w:t></w:r></w:p></w:tc><w:tc><w:p><w:r><w:t>1,200.00</w:t></w:r></w:p></w:tc></w:tr>
+                            <w:tr><w:tc><w:p/></w:tc><w:tc><w:p/></w:tc></w:tr>
+                        </w:tbl>
+                        <w:p><w:r><w:t>Due</w:t></w:r><w:r><w:tab/><w:t>30 days</w:t></w:r></w:p>
+                    </w:body></w:document>"#,
+                ),
+                ("word/header1.xml", letterhead),
+                // The first-page header repeats the default one
+                ("word/header2.xml", letterhead),
+                ("word/footer1.xml", r#"<w:ftr><w:p><w:r><w:t>Page 1</w:t></w:r></w:p></w:ftr>"#),
+                (
+                    "word/footnotes.xml",
+                    r#"<w:footnotes><w:footnote w:type="separator"><w:p><w:r><w:separator/></w:r></w:p></w:footnote>
+                        <w:footnote w:id="1"><w:p><w:r><w:t>VAT included.</w:t></w:r></w:p></w:footnote></w:footnotes>"#,
+                ),
+                (
+                    "word/comments.xml",
+                    r#"<w:comments><w:comment w:author="Sam"><w:p><w:r><w:t>Check the rate</w:t></w:r></w:p></w:comment></w:comments>"#,
+                ),
+                (
+                    "docProps/custom.xml",
+                    r#"<Properties><property fmtid="x" pid="2" name="Client"><vt:lpwstr>Globex</vt:lpwstr></property>
+                        <property fmtid="x" pid="3" name="Empty"><vt:lpwstr></vt:lpwstr></property></Properties>"#,
+                ),
+            ],
+        );
+
+        let result = extract_docx(&path, 2000).unwrap();
+        assert_eq!(
+            result.content,
+            "[Properties]\nClient: Globex\n\n[Header]\nACME Corp\n\n[Body]\nInvoice 1042\nItem | Amount\n\
+             Design work | 1,200.00\nDue\t30 days\n\n[Footer]\nPage 1\n\n[Footnotes]\nVAT included.\n\n\
+             [Comments]\nSam: Check the rate"
+        );
+        assert_eq!(result.metadata.custom_properties, vec![("Client".to_string(), "Globex".to_string())]);
+    }
+
+    #[test]
+    fn test_body_only_has_no_section_markers() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("note.docx");
+        write_docx(
+            &path,
+            &[("word/document.xml", r#"<w:document><w:body><w:p><w:r><w:t>Just text</w:t></w:r></w:p></w:body></w:document>"#)],
+        );
+
+        let result = extract_docx(&path, 2000).unwrap();
+        assert_eq!(result.content, "Just text");
+    }
 }
@@ -0,0 +1,619 @@
+//! Email Parser (Group 5 - Messages)
+//!
+//! Handles: .eml (RFC 822 / MIME), .mbox mailboxes, .msg (Outlook, OLE compound file)
+//! Strategy: Split headers from body → decode RFC 2047 header words → walk MIME
+//! parts, decoding quoted-printable/base64 text and listing attachment names
+
+use super::html::html_to_text;
+use super::ole::{decode_cp1252, decode_utf16, open_compound, read_stream};
+use super::{calculate_confidence, clean_lines, truncate, DocumentMetadata, ParseError, ParsedDocument};
+use cfb::CompoundFile;
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader};
+use std::path::Path;
+
+/// Nested multiparts followed before giving up
+const MAX_MIME_DEPTH: usize = 10;
+
+/// A decoded message: headers, readable body and attachment names
+#[derive(Debug, Default)]
+struct EmailMessage {
+    from: Option<String>,
+    to: Option<String>,
+    date: Option<String>,
+    subject: Option<String>,
+    body: String,
+    attachments: Vec<String>,
+}
+
+/// Text and attachments found while walking MIME parts
+#[derive(Default)]
+struct MimeContent {
+    plain: Vec<String>,
+    /// Decoded but still marked up
+    html: Vec<String>,
+    attachments: Vec<String>,
+}
+
+/// Extract headers, body text and attachment names from an .eml file
+pub fn extract_eml(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    let raw = fs::read(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ParseError::NotFound(path.to_string_lossy().to_string())
+        } else {
+            ParseError::ReadError(e.to_string())
+        }
+    })?;
+
+    let message = parse_message(&raw);
+    if message.from.is_none() && message.subject.is_none() && message.date.is_none() {
+        return Err(ParseError::ParseError("Not an email message (no From, Subject or Date header)".to_string()));
+    }
+    Ok(single_message_document(message, max_chars))
+}
+
+/// Extract the messages of an mbox mailbox, reading only as many as fit in max_chars
+pub fn extract_mbox(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    let file = File::open(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ParseError::NotFound(path.to_string_lossy().to_string())
+        } else {
+            ParseError::ReadError(e.to_string())
+        }
+    })?;
+    let mut reader = BufReader::new(file);
+
+    let mut content = String::new();
+    let mut message_count = 0u32;
+    let mut current: Option<Vec<u8>> = None;
+    let mut previous_blank = true;
+    let mut line = Vec::new();
+    let mut read_all = false;
+
+    loop {
+        line.clear();
+        let read = reader.read_until(b'\n', &mut line).map_err(|e| ParseError::ReadError(e.to_string()))?;
+        // A "From " line after a blank line (or at the start) begins the next message
+        let is_separator = read > 0 && previous_blank && line.starts_with(b"From ");
+
+        if read == 0 || is_separator {
+            if let Some(raw) = current.take() {
+                message_count += 1;
+                let message = parse_message(&raw);
+                if !content.is_empty() {
+                    content.push_str("\n\n");
+                }
+                content.push_str(&format!("[Message {}]\n{}", message_count, format_message(&message)));
+            }
+            if read == 0 {
+                read_all = true;
+                break;
+            }
+            if content.chars().count() >= max_chars {
+                break;
+            }
+            current = Some(Vec::new());
+        } else if let Some(raw) = current.as_mut() {
+            // Body lines starting with "From " are stored as ">From "
+            let unescaped = line.strip_prefix(b">").filter(|rest| rest.starts_with(b"From "));
+            raw.extend_from_slice(unescaped.unwrap_or(&line));
+        }
+        previous_blank = line.iter().all(|b| b.is_ascii_whitespace());
+    }
+
+    if message_count == 0 {
+        return Err(ParseError::ParseError("Not an mbox file (no \"From \" separator lines)".to_string()));
+    }
+
+    let content = truncate(content, max_chars);
+    let word_count = content.split_whitespace().count() as u32;
+    Ok(ParsedDocument {
+        extraction_confidence: calculate_confidence(word_count),
+        content,
+        metadata: DocumentMetadata {
+            word_count: Some(word_count),
+            // Only known when the whole mailbox was read
+            page_count: read_all.then_some(message_count),
+            ..Default::default()
+        },
+    })
+}
+
+/// Extract headers, body text and attachment names from an Outlook .msg file
+pub fn extract_msg(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    let mut compound = open_compound(path)?;
+
+    let mut property = |tag: &str| msg_property(&mut compound, "", tag);
+    let sender_name = property("0C1A");
+    let sender_email = property("0C1F").or_else(|| property("5D01"));
+    let from = match (sender_name, sender_email) {
+        (Some(name), Some(email)) if name != email => Some(format!("{} <{}>", name, email)),
+        (name, email) => name.or(email),
+    };
+    // The original internet headers carry the sent date
+    let transport_headers = property("007D");
+    let date = transport_headers.as_deref().and_then(|headers| {
+        let (headers, _) = split_headers(headers.as_bytes());
+        header(&headers, "date").map(decode_header_words)
+    });
+    let subject = property("0037");
+    let to = property("0E04");
+    let body = property("1000").map(|text| clean_lines(&text)).unwrap_or_default();
+
+    let attachment_storages: Vec<String> = compound
+        .read_root_storage()
+        .filter(|entry| entry.is_storage() && entry.name().starts_with("__attach_version1.0_#"))
+        .map(|entry| format!("{}/", entry.path().to_string_lossy()))
+        .collect();
+    let attachments = attachment_storages
+        .iter()
+        .filter_map(|storage| {
+            msg_property(&mut compound, storage, "3707").or_else(|| msg_property(&mut compound, storage, "3704"))
+        })
+        .collect();
+
+    if from.is_none() && subject.is_none() && body.is_empty() {
+        return Err(ParseError::ParseError("MSG file has no message properties".to_string()));
+    }
+
+    let message = EmailMessage {
+        from,
+        to,
+        date,
+        subject,
+        body,
+        attachments,
+    };
+    Ok(single_message_document(message, max_chars))
+}
+
+/// A string property of a .msg storage, stored as __substg1.0_<tag><type>
+/// (type 001F is UTF-16, 001E 8-bit)
+fn msg_property(compound: &mut CompoundFile<File>, storage: &str, tag: &str) -> Option<String> {
+    let text = read_stream(compound, &format!("{}__substg1.0_{}001F", storage, tag))
+        .map(|data| decode_utf16(&data))
+        .or_else(|| read_stream(compound, &format!("{}__substg1.0_{}001E", storage, tag)).map(|data| decode_cp1252(&data)))?;
+    let text = text.trim_end_matches('\0').trim().to_string();
+    (!text.is_empty()).then_some(text)
+}
+
+/// Build the parsed document for one message, keeping its headers in the metadata
+fn single_message_document(message: EmailMessage, max_chars: usize) -> ParsedDocument {
+    let content = truncate(format_message(&message), max_chars);
+    let word_count = content.split_whitespace().count() as u32;
+
+    ParsedDocument {
+        extraction_confidence: calculate_confidence(word_count),
+        content,
+        metadata: DocumentMetadata {
+            author: message.from.clone(),
+            subject: message.subject.clone(),
+            word_count: Some(word_count),
+            email_from: message.from,
+            email_to: message.to,
+            email_date: message.date,
+            attachments: message.attachments,
+            ..Default::default()
+        },
+    }
+}
+
+/// Headers, subject first in the body, so short snippets still carry them
+fn format_message(message: &EmailMessage) -> String {
+    let mut lines = Vec::new();
+    for (label, value) in [
+        ("Subject", &message.subject),
+        ("From", &message.from),
+        ("To", &message.to),
+        ("Date", &message.date),
+    ] {
+        if let Some(value) = value {
+            lines.push(format!("{}: {}", label, value));
+        }
+    }
+    if !message.attachments.is_empty() {
+        lines.push(format!("Attachments: {}", message.attachments.join(", ")));
+    }
+    if !message.body.is_empty() {
+        lines.push(String::new());
+        lines.push(message.body.clone());
+    }
+    lines.join("\n")
+}
+
+/// The Subject and decoded HTML parts of a MIME document (.mhtml web archives)
+pub(super) fn mime_html_parts(raw: &[u8]) -> (Option<String>, Vec<String>) {
+    let (headers, _) = split_headers(raw);
+    let mut content = MimeContent::default();
+    walk_part(raw, &mut content, 0);
+    (header(&headers, "subject").map(decode_header_words), content.html)
+}
+
+fn parse_message(raw: &[u8]) -> EmailMessage {
+    let (headers, _) = split_headers(raw);
+    let mut content = MimeContent::default();
+    walk_part(raw, &mut content, 0);
+
+    let body = if content.plain.iter().any(|t| !t.trim().is_empty()) {
+        content.plain.join("\n")
+    } else {
+        content.html.iter().map(|html| html_to_text(html)).collect::<Vec<_>>().join("\n")
+    };
+
+    EmailMessage {
+        from: header(&headers, "from").map(decode_header_words),
+        to: header(&headers, "to").map(decode_header_words),
+        date: header(&headers, "date").map(|d| d.trim().to_string()),
+        subject: header(&headers, "subject").map(decode_header_words),
+        body: clean_lines(&body),
+        attachments: content.attachments,
+    }
+}
+
+/// Unfolded header fields and the body that follows the first blank line
+fn split_headers(raw: &[u8]) -> (Vec<(String, String)>, &[u8]) {
+    let mut headers: Vec<(String, String)> = Vec::new();
+    let mut at = 0;
+
+    while at < raw.len() {
+        let end = raw[at..].iter().position(|&b| b == b'\n').map_or(raw.len(), |p| at + p);
+        let line = String::from_utf8_lossy(&raw[at..end]);
+        let line = line.trim_end_matches('\r');
+        at = end + 1;
+
+        if line.is_empty() {
+            return (headers, raw.get(at..).unwrap_or_default());
+        }
+        if line.starts_with([' ', '\t']) {
+            // Folded continuation of the previous field
+            if let Some((_, value)) = headers.last_mut() {
+                value.push(' ');
+                value.push_str(line.trim());
+            }
+        } else if let Some((name, value)) = line.split_once(':') {
+            headers.push((name.trim().to_lowercase(), value.trim().to_string()));
+        }
+    }
+
+    (headers, &[])
+}
+
+fn header<'a>(headers: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    headers.iter().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+}
+
+/// Main value (lowercased) and parameters of a Content-Type or Content-Disposition field
+fn header_params(value: &str) -> (String, HashMap<String, String>) {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut quoted = false;
+    for c in value.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            ';' if !quoted => fields.push(std::mem::take(&mut field)),
+            _ => field.push(c),
+        }
+    }
+    fields.push(field);
+
+    let main = fields.first().map(|f| f.trim().to_lowercase()).unwrap_or_default();
+    let params = fields
+        .iter()
+        .skip(1)
+        .filter_map(|f| f.split_once('='))
+        .map(|(key, value)| {
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+            // RFC 2231: name*=charset'language'percent-encoded
+            match key.strip_suffix('*') {
+                Some(key) => {
+                    let encoded = value.splitn(3, '\'').last().unwrap_or(value);
+                    (key.to_string(), String::from_utf8_lossy(&percent_decode(encoded)).to_string())
+                }
+                None => (key, decode_header_words(value)),
+            }
+        })
+        .collect();
+    (main, params)
+}
+
+fn percent_decode(value: &str) -> Vec<u8> {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Collect the text and attachment names of a MIME part and its children
+fn walk_part(raw: &[u8], content: &mut MimeContent, depth: usize) {
+    let (headers, body) = split_headers(raw);
+    let (content_type, type_params) = header_params(header(&headers, "content-type").unwrap_or("text/plain"));
+    let (disposition, disposition_params) = header_params(header(&headers, "content-disposition").unwrap_or(""));
+
+    if content_type.starts_with("multipart/") {
+        if depth >= MAX_MIME_DEPTH {
+            return;
+        }
+        if let Some(boundary) = type_params.get("boundary") {
+            for part in split_multipart(body, boundary) {
+                walk_part(part, content, depth + 1);
+            }
+        }
+        return;
+    }
+
+    let filename = disposition_params.get("filename").or_else(|| type_params.get("name"));
+    if disposition == "attachment" || (filename.is_some() && disposition != "inline") {
+        content
+            .attachments
+            .push(filename.cloned().unwrap_or_else(|| format!("(unnamed {})", content_type)));
+        return;
+    }
+
+    let encoding = header(&headers, "content-transfer-encoding").unwrap_or("").trim().to_lowercase();
+    let decoded = decode_transfer(body, &encoding);
+    let charset = type_params.get("charset").map(String::as_str).unwrap_or("utf-8");
+
+    match content_type.as_str() {
+        "text/plain" => content.plain.push(decode_charset(&decoded, charset)),
+        "text/html" => content.html.push(decode_charset(&decoded, charset)),
+        // Forwarded messages: their text is part of this one
+        "message/rfc822" if depth < MAX_MIME_DEPTH => walk_part(&decoded, content, depth + 1),
+        _ => {}
+    }
+}
+
+/// Body parts between "--boundary" delimiter lines
+fn split_multipart<'a>(body: &'a [u8], boundary: &str) -> Vec<&'a [u8]> {
+    let delimiter = format!("--{}", boundary);
+    let mut parts = Vec::new();
+    let mut start: Option<usize> = None;
+    let mut at = 0;
+
+    while at < body.len() {
+        let end = body[at..].iter().position(|&b| b == b'\n').map_or(body.len(), |p| at + p);
+        let line = body[at..end].trim_ascii_end();
+        if let Some(rest) = line.strip_prefix(delimiter.as_bytes()) {
+            if rest.is_empty() || rest == b"--" {
+                if let Some(start) = start {
+                    parts.push(&body[start..at]);
+                }
+                if rest == b"--" {
+                    return parts;
+                }
+                start = Some((end + 1).min(body.len()));
+            }
+        }
+        at = end + 1;
+    }
+
+    if let Some(start) = start {
+        parts.push(&body[start..]);
+    }
+    parts
+}
+
+fn decode_transfer(body: &[u8], encoding: &str) -> Vec<u8> {
+    match encoding {
+        "base64" => decode_base64(body),
+        "quoted-printable" => decode_quoted_printable(body, false),
+        _ => body.to_vec(),
+    }
+}
+
+fn decode_base64(input: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len() * 3 / 4);
+    let mut buffer = 0u32;
+    let mut bits = 0;
+    for &c in input {
+        let value = match c {
+            b'A'..=b'Z' => c - b'A',
+            b'a'..=b'z' => c - b'a' + 26,
+            b'0'..=b'9' => c - b'0' + 52,
+            b'+' | b'-' => 62,
+            b'/' | b'_' => 63,
+            b'=' => break,
+            // Line breaks and other whitespace
+            _ => continue,
+        };
+        buffer = (buffer << 6) | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((buffer >> bits) as u8);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    out
+}
+
+/// Decode quoted-printable; `header` also maps "_" to a space (RFC 2047 "Q" words)
+fn decode_quoted_printable(input: &[u8], header: bool) -> Vec<u8> {
+    let mut out = Vec::with_capacity(input.len());
+    let mut i = 0;
+    while i < input.len() {
+        match input[i] {
+            b'=' => {
+                let next = &input[i + 1..];
+                if next.starts_with(b"\r\n") {
+                    i += 3;
+                } else if next.starts_with(b"\n") {
+                    i += 2;
+                } else if let Some(byte) = next
+                    .get(..2)
+                    .and_then(|h| std::str::from_utf8(h).ok())
+                    .and_then(|h| u8::from_str_radix(h, 16).ok())
+                {
+                    out.push(byte);
+                    i += 3;
+                } else {
+                    out.push(b'=');
+                    i += 1;
+                }
+            }
+            b'_' if header => {
+                out.push(b' ');
+                i += 1;
+            }
+            b => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    out
+}
+
+/// Decode text in a MIME or HTML charset (also used for web pages)
+pub(super) fn decode_charset(bytes: &[u8], charset: &str) -> String {
+    match charset.trim().to_lowercase().as_str() {
+        "iso-8859-1" | "latin1" | "latin-1" | "iso8859-1" => bytes.iter().map(|&b| b as char).collect(),
+        "windows-1252" | "cp1252" => decode_cp1252(bytes),
+        // UTF-8, US-ASCII and anything we can't convert
+        _ => String::from_utf8_lossy(bytes).to_string(),
+    }
+}
+
+/// Decode RFC 2047 encoded words (=?charset?B|Q?text?=) in a header value
+/// Whitespace between adjacent encoded words is dropped, as the RFC requires.
+fn decode_header_words(value: &str) -> String {
+    let mut out = String::new();
+    let mut rest = value;
+    let mut previous_was_word = false;
+
+    while let Some(start) = rest.find("=?") {
+        let decoded = rest[start + 2..].splitn(3, '?').collect::<Vec<_>>();
+        let word = match decoded.as_slice() {
+            [charset, encoding, tail] => tail.find("?=").map(|end| {
+                let text = &tail[..end];
+                let bytes = match encoding.to_ascii_uppercase().as_str() {
+                    "B" => decode_base64(text.as_bytes()),
+                    _ => decode_quoted_printable(text.as_bytes(), true),
+                };
+                // "=?" charset "?" encoding "?" text "?="
+                let consumed = start + 2 + charset.len() + 1 + encoding.len() + 1 + end + 2;
+                // charset may carry a language suffix (utf-8*en)
+                let charset = charset.split('*').next().unwrap_or(charset);
+                (decode_charset(&bytes, charset), consumed)
+            }),
+            _ => None,
+        };
+
+        let Some((text, consumed)) = word else {
+            out.push_str(&rest[..start + 2]);
+            rest = &rest[start + 2..];
+            previous_was_word = false;
+            continue;
+        };
+        let between = &rest[..start];
+        if !(previous_was_word && between.trim().is_empty()) {
+            out.push_str(between);
+        }
+        out.push_str(&text);
+        rest = rest.get(consumed..).unwrap_or_default();
+        previous_was_word = true;
+    }
+
+    out.push_str(rest);
+    out.trim().to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    const CONFIRMATION: &str = "From: =?UTF-8?Q?Sky_Air_=E2=9C=88?= <no-reply@skyair.example>\r\n\
+To: Pat <pat@example.com>\r\n\
+Subject: =?utf-8?B?WW91ciBmbGlnaHQg?=\r\n =?utf-8?B?Y29uZmlybWF0aW9u?=\r\n\
+Date: Tue, 4 Mar 2025 09:15:00 +0000\r\n\
+MIME-Version: 1.0\r\n\
+Content-Type: multipart/mixed; boundary=\"outer\"\r\n\
+\r\n\
+--outer\r\n\
+Content-Type: multipart/alternative; boundary=inner\r\n\
+\r\n\
+--inner\r\n\
+Content-Type: text/plain; charset=utf-8\r\n\
+Content-Transfer-Encoding: quoted-printable\r\n\
+\r\n\
+Booking reference QX7 =E2=80=93 Lisbon to Berlin, seat 14=\r\n\
+C.\r\n\
+--inner\r\n\
+Content-Type: text/html\r\n\
+\r\n\
+<p>Booking reference QX7</p>\r\n\
+--inner--\r\n\
+--outer\r\n\
+Content-Type: application/pdf; name=\"boarding-pass.pdf\"\r\n\
+Content-Disposition: attachment; filename*=utf-8''e%CC%81-ticket%20receipt.pdf\r\n\
+Content-Transfer-Encoding: base64\r\n\
+\r\n\
+JVBERi0xLjQK\r\n\
+--outer--\r\n";
+
+    #[test]
+    fn test_extract_eml_headers_parts_and_attachments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("flight.eml");
+        fs::write(&path, CONFIRMATION).unwrap();
+
+        let result = extract_eml(&path, 2000).unwrap();
+        assert_eq!(
+            result.content,
+            "Subject: Your flight confirmation\n\
+             From: Sky Air \u{2708} <no-reply@skyair.example>\n\
+             To: Pat <pat@example.com>\n\
+             Date: Tue, 4 Mar 2025 09:15:00 +0000\n\
+             Attachments: e\u{301}-ticket receipt.pdf\n\n\
+             Booking reference QX7 \u{2013} Lisbon to Berlin, seat 14C."
+        );
+        assert_eq!(result.metadata.subject.as_deref(), Some("Your flight confirmation"));
+        assert_eq!(result.metadata.email_to.as_deref(), Some("Pat <pat@example.com>"));
+        assert_eq!(result.metadata.email_date.as_deref(), Some("Tue, 4 Mar 2025 09:15:00 +0000"));
+        assert_eq!(result.metadata.attachments, vec!["e\u{301}-ticket receipt.pdf"]);
+    }
+
+    #[test]
+    fn test_html_only_body() {
+        let message = parse_message(
+            b"Subject: Receipt\nContent-Type: text/html; charset=iso-8859-1\n\n<style>p{}</style><p>Total:&nbsp;12&euro; &amp; caf\xe9</p><br>Thanks",
+        );
+        assert_eq!(message.body, "Total: 12\u{20ac} & caf\u{e9}\nThanks");
+    }
+
+    #[test]
+    fn test_extract_mbox_messages() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("archive.mbox");
+        let mut file = File::create(&path).unwrap();
+        write!(
+            file,
+            "From alice@example.com Mon Jan  6 10:00:00 2025\nSubject: Lease\nFrom: Alice <alice@example.com>\n\nRent is due.\n>From the landlord.\n\n\
+             From bob@example.com Tue Jan  7 10:00:00 2025\nSubject: Invoice 12\n\nAmount due: $40\n"
+        )
+        .unwrap();
+
+        let result = extract_mbox(&path, 2000).unwrap();
+        assert_eq!(
+            result.content,
+            "[Message 1]\nSubject: Lease\nFrom: Alice <alice@example.com>\n\nRent is due.\nFrom the landlord.\n\n\
+             [Message 2]\nSubject: Invoice 12\n\nAmount due: $40"
+        );
+        assert_eq!(result.metadata.page_count, Some(2));
+    }
+}
@@ -0,0 +1,305 @@
+//! EPUB Parser (Group 6 - E-books)
+//!
+//! Handles: .epub files
+//! Strategy: Unzip → META-INF/container.xml → OPF package (Dublin Core metadata,
+//! manifest, spine) → read the first spine documents as XHTML
+
+use super::html::{html_to_text, parse_html};
+use super::{DocumentMetadata, ParseError, ParsedDocument};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use zip::ZipArchive;
+
+/// Spine documents read at most (covers and front matter are often near-empty)
+const MAX_SPINE_DOCUMENTS: usize = 20;
+
+/// What the OPF package file says about the book
+#[derive(Debug, Default)]
+struct Package {
+    title: Option<String>,
+    creators: Vec<String>,
+    description: Option<String>,
+    subjects: Vec<String>,
+    /// Archive paths of the readable spine documents, in reading order
+    spine: Vec<String>,
+}
+
+/// Extract Dublin Core metadata and the text of the first chapters from an EPUB file
+pub fn extract_epub(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    let file = File::open(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ParseError::NotFound(path.to_string_lossy().to_string())
+        } else {
+            ParseError::ReadError(e.to_string())
+        }
+    })?;
+
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| ParseError::ParseError(format!("Invalid EPUB file (not a valid ZIP): {}", e)))?;
+
+    let opf_path = package_path(&mut archive)
+        .ok_or_else(|| ParseError::ParseError("EPUB file has no OPF package".to_string()))?;
+    let opf = read_entry(&mut archive, &opf_path)
+        .ok_or_else(|| ParseError::ParseError(format!("EPUB package {} is missing", opf_path)))?;
+    let base = opf_path.rsplit_once('/').map_or("", |(dir, _)| dir);
+    let package = parse_package(&opf, base)?;
+
+    // Read chapters in reading order until the snippet is full
+    let mut text = String::new();
+    for document in package.spine.iter().take(MAX_SPINE_DOCUMENTS) {
+        if text.chars().count() >= max_chars {
+            break;
+        }
+        let Some(xhtml) = read_entry(&mut archive, document) else {
+            continue;
+        };
+        let chapter = parse_html(&xhtml).text;
+        if !chapter.is_empty() {
+            if !text.is_empty() {
+                text.push_str("\n\n");
+            }
+            text.push_str(&chapter);
+        }
+    }
+
+    let author = (!package.creators.is_empty()).then(|| package.creators.join(", "));
+    let mut lines = Vec::new();
+    if let Some(title) = &package.title {
+        lines.push(format!("Title: {}", title));
+    }
+    if let Some(author) = &author {
+        lines.push(format!("Author: {}", author));
+    }
+    if !text.is_empty() {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(text);
+    }
+
+    // Truncate to max_chars (char-safe for multi-byte UTF-8)
+    let mut content = lines.join("\n");
+    if content.chars().count() > max_chars {
+        content = content.chars().take(max_chars).collect();
+    }
+
+    let word_count = content.split_whitespace().count() as u32;
+    let confidence = if word_count > 100 {
+        0.95
+    } else if word_count > 20 {
+        0.85
+    } else if word_count > 5 {
+        0.70
+    } else {
+        0.50
+    };
+
+    Ok(ParsedDocument {
+        content,
+        metadata: DocumentMetadata {
+            title: package.title,
+            author,
+            subject: package.description,
+            keywords: package.subjects,
+            word_count: Some(word_count),
+            ..Default::default()
+        },
+        extraction_confidence: confidence,
+    })
+}
+
+fn read_entry(archive: &mut ZipArchive<File>, name: &str) -> Option<String> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut bytes = Vec::new();
+    entry.read_to_end(&mut bytes).ok()?;
+    Some(String::from_utf8_lossy(&bytes).to_string())
+}
+
+/// Value of an attribute by qualified name
+fn attribute(e: &BytesStart, name: &str) -> Option<String> {
+    e.try_get_attribute(name)
+        .ok()
+        .flatten()
+        .and_then(|attr| attr.unescape_value().ok().map(|v| v.to_string()))
+}
+
+/// The OPF path named by META-INF/container.xml, else the first .opf in the archive
+fn package_path(archive: &mut ZipArchive<File>) -> Option<String> {
+    if let Some(container) = read_entry(archive, "META-INF/container.xml") {
+        let mut reader = Reader::from_str(&container);
+        loop {
+            match reader.read_event() {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e))
+                    if e.local_name().as_ref() == b"rootfile"
+                        && attribute(&e, "media-type").is_none_or(|t| t == "application/oebps-package+xml") =>
+                {
+                    if let Some(full_path) = attribute(&e, "full-path") {
+                        return Some(full_path);
+                    }
+                }
+                Ok(Event::Eof) | Err(_) => break,
+                _ => {}
+            }
+        }
+    }
+
+    archive.file_names().find(|name| name.to_lowercase().ends_with(".opf")).map(String::from)
+}
+
+/// Read metadata, manifest and spine from the OPF package file
+fn parse_package(xml: &str, base: &str) -> Result<Package, ParseError> {
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    let mut package = Package::default();
+    // Manifest id → (href, media type)
+    let mut manifest: HashMap<String, (String, String)> = HashMap::new();
+    let mut spine_ids = Vec::new();
+    let mut current_tag = String::new();
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                current_tag = String::from_utf8_lossy(e.name().as_ref()).to_string();
+            }
+            Ok(Event::Empty(e)) => match e.local_name().as_ref() {
+                b"item" => {
+                    if let (Some(id), Some(href)) = (attribute(&e, "id"), attribute(&e, "href")) {
+                        manifest.insert(id, (href, attribute(&e, "media-type").unwrap_or_default()));
+                    }
+                }
+                // Non-linear items (pop-up notes, answers) are outside the reading order
+                b"itemref" if attribute(&e, "linear").as_deref() != Some("no") => {
+                    spine_ids.extend(attribute(&e, "idref"));
+                }
+                _ => {}
+            },
+            Ok(Event::Text(e)) => {
+                let text = e.unescape().unwrap_or_default().trim().to_string();
+                if text.is_empty() {
+                    continue;
+                }
+                match current_tag.as_str() {
+                    "dc:title" => {
+                        package.title.get_or_insert(text);
+                    }
+                    "dc:creator" => package.creators.push(text),
+                    "dc:subject" => package.subjects.push(text),
+                    // Descriptions are often escaped HTML
+                    "dc:description" => {
+                        package.description.get_or_insert(html_to_text(&text));
+                    }
+                    _ => {}
+                }
+            }
+            Ok(Event::End(_)) => current_tag.clear(),
+            Ok(Event::Eof) => break,
+            Err(e) => return Err(ParseError::ParseError(format!("Invalid EPUB package: {}", e))),
+            _ => {}
+        }
+    }
+
+    package.spine = spine_ids
+        .iter()
+        .filter_map(|id| manifest.get(id))
+        .filter(|(_, media_type)| matches!(media_type.as_str(), "application/xhtml+xml" | "text/html"))
+        .map(|(href, _)| resolve_href(base, href))
+        .collect();
+    Ok(package)
+}
+
+/// Archive path of a manifest href relative to the package directory
+fn resolve_href(base: &str, href: &str) -> String {
+    let href = href.split('#').next().unwrap_or(href);
+    let href = percent_decode(href);
+
+    let mut parts: Vec<&str> = base.split('/').filter(|p| !p.is_empty()).collect();
+    for part in href.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// Decode %XX escapes in a URL path
+fn percent_decode(value: &str) -> String {
+    let bytes = value.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let hex = bytes.get(i + 1..i + 3).and_then(|h| std::str::from_utf8(h).ok());
+        match (bytes[i], hex.and_then(|h| u8::from_str_radix(h, 16).ok())) {
+            (b'%', Some(byte)) => {
+                out.push(byte);
+                i += 3;
+            }
+            (b, _) => {
+                out.push(b);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    #[test]
+    fn test_extract_epub_metadata_and_spine() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("novel.epub");
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        let files = [
+            ("mimetype", "application/epub+zip"),
+            (
+                "META-INF/container.xml",
+                r#"<container><rootfiles><rootfile full-path="OEBPS/content.opf" media-type="application/oebps-package+xml"/></rootfiles></container>"#,
+            ),
+            (
+                "OEBPS/content.opf",
+                r#"<package xmlns:dc="http://purl.org/dc/elements/1.1/">
+                    <metadata><dc:title>The Long Harbour</dc:title><dc:creator>Ana Reis</dc:creator>
+                    <dc:subject>Fiction</dc:subject><dc:description>&lt;p&gt;A sea story.&lt;/p&gt;</dc:description></metadata>
+                    <manifest>
+                        <item id="css" href="style.css" media-type="text/css"/>
+                        <item id="cover" href="cover.xhtml" media-type="application/xhtml+xml"/>
+                        <item id="notes" href="notes.xhtml" media-type="application/xhtml+xml"/>
+                        <item id="ch1" href="Text/chapter%201.xhtml#start" media-type="application/xhtml+xml"/>
+                    </manifest>
+                    <spine><itemref idref="cover"/><itemref idref="notes" linear="no"/><itemref idref="ch1"/></spine>
+                </package>"#,
+            ),
+            ("OEBPS/cover.xhtml", r#"<html><body><img src="cover.jpg"/></body></html>"#),
+            ("OEBPS/notes.xhtml", "<html><body><p>Endnotes</p></body></html>"),
+            (
+                "OEBPS/Text/chapter 1.xhtml",
+                "<html><head><title>Chapter 1</title></head><body><h1>One</h1><p>The tide came in.</p></body></html>",
+            ),
+        ];
+        for (name, data) in files {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(data.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let result = extract_epub(&path, 2000).unwrap();
+        assert_eq!(result.content, "Title: The Long Harbour\nAuthor: Ana Reis\n\nOne\nThe tide came in.");
+        assert_eq!(result.metadata.author.as_deref(), Some("Ana Reis"));
+        assert_eq!(result.metadata.subject.as_deref(), Some("A sea story."));
+        assert_eq!(result.metadata.keywords, vec!["Fiction"]);
+    }
+}
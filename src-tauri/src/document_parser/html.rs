@@ -0,0 +1,387 @@
+//! HTML Parser (Group 6 - Web Pages)
+//!
+//! Handles: .html, .htm (saved web pages), .mhtml, .mht (single-file web archives)
+//! Strategy: Detect charset → read <title> and <meta> tags → drop scripts, styles
+//! and markup, keeping block boundaries as line breaks
+
+use super::email::{decode_charset, mime_html_parts};
+use super::ole::{decode_cp1252, decode_utf16};
+use super::{calculate_confidence, clean_lines, truncate, DocumentMetadata, ParseError, ParsedDocument};
+use std::fs;
+use std::path::Path;
+
+/// Tags that end a line of text
+const BLOCK_TAGS: &[&str] = &[
+    "br", "p", "div", "tr", "li", "dt", "dd", "h1", "h2", "h3", "h4", "h5", "h6", "table", "blockquote", "section",
+    "article", "header", "footer", "nav", "aside", "pre", "hr", "ul", "ol", "dl",
+];
+
+/// Tags whose contents are never shown as page text
+const HIDDEN_TAGS: &[&str] = &["script", "style", "template", "title"];
+
+/// Title, meta tags and readable text of an HTML document
+#[derive(Debug, Default)]
+pub(super) struct HtmlPage {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub keywords: Vec<String>,
+    pub text: String,
+}
+
+/// Extract title, meta tags and text from a saved web page
+pub fn extract_html(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    let bytes = read_file(path)?;
+    let page = parse_html(&decode_html_bytes(&bytes));
+    Ok(page_document(page, max_chars))
+}
+
+/// Extract title, meta tags and text from the page of an MHTML web archive
+pub fn extract_mhtml(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    let raw = read_file(path)?;
+
+    // The first HTML part is the page; later ones are frames
+    let (subject, html_parts) = mime_html_parts(&raw);
+    let html = html_parts
+        .first()
+        .ok_or_else(|| ParseError::ParseError("MHTML archive has no HTML part".to_string()))?;
+
+    let mut page = parse_html(html);
+    // Browsers store the page title as the archive's Subject
+    page.title = page.title.or(subject).filter(|t| !t.is_empty());
+    Ok(page_document(page, max_chars))
+}
+
+fn read_file(path: &Path) -> Result<Vec<u8>, ParseError> {
+    fs::read(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ParseError::NotFound(path.to_string_lossy().to_string())
+        } else {
+            ParseError::ReadError(e.to_string())
+        }
+    })
+}
+
+/// Build the parsed document, leading with the title and description
+fn page_document(page: HtmlPage, max_chars: usize) -> ParsedDocument {
+    let mut lines = Vec::new();
+    if let Some(title) = &page.title {
+        lines.push(format!("Title: {}", title));
+    }
+    if let Some(description) = &page.description {
+        lines.push(format!("Description: {}", description));
+    }
+    if !page.text.is_empty() {
+        if !lines.is_empty() {
+            lines.push(String::new());
+        }
+        lines.push(page.text);
+    }
+
+    let content = truncate(lines.join("\n"), max_chars);
+    let word_count = content.split_whitespace().count() as u32;
+
+    ParsedDocument {
+        extraction_confidence: calculate_confidence(word_count),
+        content,
+        metadata: DocumentMetadata {
+            title: page.title,
+            author: page.author,
+            subject: page.description,
+            keywords: page.keywords,
+            word_count: Some(word_count),
+            ..Default::default()
+        },
+    }
+}
+
+/// Decode page bytes by their BOM, else the charset declared near the top, else UTF-8
+pub(super) fn decode_html_bytes(bytes: &[u8]) -> String {
+    if let Some(rest) = bytes.strip_prefix(b"\xEF\xBB\xBF") {
+        return String::from_utf8_lossy(rest).to_string();
+    }
+    if let Some(rest) = bytes.strip_prefix(b"\xFF\xFE") {
+        return decode_utf16(rest);
+    }
+
+    // <meta charset="..."> or <meta http-equiv="Content-Type" content="...; charset=...">
+    let head = String::from_utf8_lossy(&bytes[..bytes.len().min(1024)]).to_ascii_lowercase();
+    let declared = head.find("charset=").map(|p| {
+        head[p + "charset=".len()..]
+            .trim_start_matches(['"', '\''])
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_'))
+            .collect::<String>()
+    });
+
+    match declared {
+        Some(charset) if !charset.is_empty() => decode_charset(bytes, &charset),
+        // Undeclared pages saved by older tools are usually Windows-1252
+        _ => match std::str::from_utf8(bytes) {
+            Ok(text) => text.to_string(),
+            Err(_) => decode_cp1252(bytes),
+        },
+    }
+}
+
+/// Readable text of an HTML body: tags dropped, block ends become line breaks
+pub(super) fn html_to_text(html: &str) -> String {
+    parse_html(html).text
+}
+
+/// Read the title, meta tags and text of an HTML (or XHTML) document
+pub(super) fn parse_html(html: &str) -> HtmlPage {
+    let mut page = HtmlPage::default();
+    let mut og_title = None;
+    let mut text = String::with_capacity(html.len() / 2);
+    // ASCII lowercasing keeps byte offsets identical to `html`
+    let lower = html.to_ascii_lowercase();
+    let mut at = 0;
+
+    while at < html.len() {
+        let Some(offset) = html[at..].find('<') else {
+            push_text(&mut text, &html[at..]);
+            break;
+        };
+        push_text(&mut text, &html[at..at + offset]);
+        let tag_start = at + offset;
+
+        // Comments (and conditional comments) may contain '>'
+        if lower[tag_start..].starts_with("<!--") {
+            at = lower[tag_start..].find("-->").map_or(html.len(), |p| tag_start + p + 3);
+            continue;
+        }
+
+        let tag_end = html[tag_start..].find('>').map_or(html.len(), |p| tag_start + p + 1);
+        let tag = &html[tag_start..tag_end];
+        let closing = tag.starts_with("</");
+        let name: String = lower[tag_start..tag_end]
+            .trim_start_matches(['<', '/'])
+            .chars()
+            .take_while(|c| c.is_ascii_alphanumeric())
+            .collect();
+        at = tag_end;
+
+        if !closing && HIDDEN_TAGS.contains(&name.as_str()) {
+            let close = format!("</{}", name);
+            let content_end = lower[at..].find(&close).map_or(html.len(), |p| at + p);
+            // SVG icons carry <title>s too; the page's comes first
+            if name == "title" && page.title.is_none() {
+                page.title = non_empty(collapse_whitespace(&decode_entities(&html[at..content_end])));
+            }
+            at = content_end;
+        } else if name == "meta" {
+            read_meta_tag(tag, &mut page, &mut og_title);
+        } else if BLOCK_TAGS.contains(&name.as_str()) {
+            text.push('\n');
+        } else if matches!(name.as_str(), "td" | "th") {
+            push_text(&mut text, " ");
+        }
+    }
+
+    page.title = page.title.or(og_title);
+    page.text = clean_lines(&decode_entities(&text));
+    page
+}
+
+/// Record a <meta name|property="..." content="..."> tag we care about
+fn read_meta_tag(tag: &str, page: &mut HtmlPage, og_title: &mut Option<String>) {
+    let Some(key) = attribute(tag, "name").or_else(|| attribute(tag, "property")) else {
+        return;
+    };
+    let Some(value) = attribute(tag, "content").and_then(|v| non_empty(collapse_whitespace(&v))) else {
+        return;
+    };
+
+    match key.to_ascii_lowercase().as_str() {
+        "description" | "og:description" | "twitter:description" => {
+            page.description.get_or_insert(value);
+        }
+        "author" | "article:author" | "dc.creator" => {
+            page.author.get_or_insert(value);
+        }
+        "keywords" | "news_keywords" => {
+            let keywords = value.split(',').map(|k| k.trim()).filter(|k| !k.is_empty());
+            page.keywords.extend(keywords.map(String::from));
+        }
+        "og:title" | "twitter:title" => {
+            og_title.get_or_insert(value);
+        }
+        _ => {}
+    }
+}
+
+/// Value of an attribute in a start tag (name compared case-insensitively)
+fn attribute(tag: &str, name: &str) -> Option<String> {
+    let inner = tag.trim_start_matches('<').trim_end_matches('>').trim_end_matches('/');
+    // Skip the tag name
+    let mut rest = inner.trim_start_matches(|c: char| !c.is_whitespace());
+
+    loop {
+        rest = rest.trim_start();
+        if rest.is_empty() {
+            return None;
+        }
+        let name_end = rest.find(|c: char| c == '=' || c.is_whitespace()).unwrap_or(rest.len());
+        let attr = &rest[..name_end];
+        rest = rest[name_end..].trim_start();
+
+        let mut value = "";
+        if let Some(after) = rest.strip_prefix('=') {
+            let after = after.trim_start();
+            match after.chars().next() {
+                Some(quote @ ('"' | '\'')) => {
+                    let quoted = &after[1..];
+                    let end = quoted.find(quote).unwrap_or(quoted.len());
+                    value = &quoted[..end];
+                    rest = quoted.get(end + 1..).unwrap_or_default();
+                }
+                _ => {
+                    let end = after.find(char::is_whitespace).unwrap_or(after.len());
+                    value = &after[..end];
+                    rest = &after[end..];
+                }
+            }
+        }
+
+        if attr.eq_ignore_ascii_case(name) {
+            return Some(decode_entities(value));
+        }
+    }
+}
+
+/// Append a text run, collapsing whitespace the way browsers render it
+fn push_text(text: &mut String, run: &str) {
+    for c in run.chars() {
+        if c.is_whitespace() {
+            if !text.ends_with([' ', '\n']) {
+                text.push(' ');
+            }
+        } else {
+            text.push(c);
+        }
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+fn non_empty(text: String) -> Option<String> {
+    (!text.is_empty()).then_some(text)
+}
+
+/// Decode the common named and all numeric HTML character references
+pub(super) fn decode_entities(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let entity = rest[1..].find(';').filter(|&end| end <= 10).map(|end| &rest[1..=end]);
+        let decoded = entity.and_then(|entity| match entity {
+            "nbsp" => Some(' '),
+            "amp" => Some('&'),
+            "lt" => Some('<'),
+            "gt" => Some('>'),
+            "quot" => Some('"'),
+            "apos" => Some('\''),
+            "ndash" => Some('–'),
+            "mdash" => Some('—'),
+            "hellip" => Some('…'),
+            "copy" => Some('©'),
+            "reg" => Some('®'),
+            "euro" => Some('€'),
+            "lsquo" => Some('‘'),
+            "rsquo" => Some('’'),
+            "ldquo" => Some('“'),
+            "rdquo" => Some('”'),
+            _ => {
+                let number = entity.strip_prefix('#')?;
+                let code = match number.strip_prefix(['x', 'X']) {
+                    Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                    None => number.parse().ok()?,
+                };
+                char::from_u32(code)
+            }
+        });
+
+        match (decoded, entity) {
+            (Some(c), Some(entity)) => {
+                out.push(c);
+                rest = &rest[entity.len() + 2..];
+            }
+            _ => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_html_title_meta_and_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("booking.html");
+        let html = b"<!DOCTYPE html>\n<html><head>\n\
+            <meta http-equiv=\"Content-Type\" content=\"text/html; charset=windows-1252\">\n\
+            <title>Hotel  booking \x96 Lisbon</title>\n\
+            <meta name=\"description\" content=\"Your stay &amp; check-in details\">\n\
+            <meta name=Keywords content=\"hotel, lisbon,travel\">\n\
+            <meta property=\"og:title\" content=\"Ignored\">\n\
+            <style>body { color: red }</style>\n\
+            <script>if (a < b) { document.write('<p>hidden</p>') }</script>\n\
+            </head><body><!-- <p>tracking > pixel</p> -->\n\
+            <h1>Reservation</h1><p>Check-in\n   from   3&nbsp;pm</p>\
+            <table><tr><td>Room</td><td>412</td></tr></table></body></html>";
+        std::fs::write(&path, html).unwrap();
+
+        let result = extract_html(&path, 2000).unwrap();
+        assert_eq!(
+            result.content,
+            "Title: Hotel booking – Lisbon\nDescription: Your stay & check-in details\n\n\
+             Reservation\nCheck-in from 3 pm\nRoom 412"
+        );
+        assert_eq!(result.metadata.title.as_deref(), Some("Hotel booking – Lisbon"));
+        assert_eq!(result.metadata.subject.as_deref(), Some("Your stay & check-in details"));
+        assert_eq!(result.metadata.keywords, vec!["hotel", "lisbon", "travel"]);
+    }
+
+    #[test]
+    fn test_extract_mhtml_page() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("article.mhtml");
+        let archive = "From: <Saved by Blink>\r\n\
+            Subject: Tax deadlines 2024\r\n\
+            MIME-Version: 1.0\r\n\
+            Content-Type: multipart/related; type=\"text/html\"; boundary=\"----MultipartBoundary--x\"\r\n\
+            \r\n\
+            ------MultipartBoundary--x\r\n\
+            Content-Type: text/html\r\n\
+            Content-Transfer-Encoding: quoted-printable\r\n\
+            Content-Location: https://example.com/taxes\r\n\
+            \r\n\
+            <html><head><meta charset=3D\"utf-8\"></head><body><p>File by April 15.</p></bo=\r\n\
+            dy></html>\r\n\
+            ------MultipartBoundary--x\r\n\
+            Content-Type: image/png\r\n\
+            Content-Transfer-Encoding: base64\r\n\
+            Content-Location: https://example.com/logo.png\r\n\
+            \r\n\
+            iVBORw0KGgo=\r\n\
+            ------MultipartBoundary--x--\r\n";
+        std::fs::write(&path, archive).unwrap();
+
+        let result = extract_mhtml(&path, 2000).unwrap();
+        assert_eq!(result.content, "Title: Tax deadlines 2024\n\nFile by April 15.");
+        assert_eq!(result.metadata.title.as_deref(), Some("Tax deadlines 2024"));
+    }
+}
@@ -0,0 +1,853 @@
+//! Image Metadata Parser (Group 7 - Photos & Scans)
+//!
+//! Handles: .jpg, .jpeg, .tif, .tiff, .png, .heic, .heif
+//! Strategy: Walk the container (JPEG segments, PNG chunks, TIFF IFDs, HEIF boxes)
+//! → decode EXIF (date taken, camera, GPS) and XMP (title, keywords) → pixel size.
+//! Images carry no text, so the content is a short summary of that metadata.
+
+use super::bmff::{be_u32, child_boxes, find_box, read_top_level_box};
+use super::ole::decode_utf16;
+use super::xmp::{parse_xmp, XmpMetadata};
+use super::{read_bytes, DocumentMetadata, ParseError, ParsedDocument};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Largest metadata block read into memory (JPEG segments are under 64 KB; HEIF meta boxes are small)
+const MAX_METADATA_BYTES: u64 = 16 * 1024 * 1024;
+
+/// TIFF files are read up to this size, since IFDs may sit anywhere in the file
+/// (kept small: every scan worker holds its own copy; IFDs past it are skipped)
+const MAX_TIFF_BYTES: u64 = 8 * 1024 * 1024;
+
+/// JPEG APP1 segments holding XMP start with this namespace
+const XMP_SIGNATURE: &[u8] = b"http://ns.adobe.com/xap/1.0/\0";
+
+/// PNG iTXt keyword used for XMP packets
+const PNG_XMP_KEYWORD: &[u8] = b"XML:com.adobe.xmp";
+
+/// What the container says about the image
+#[derive(Debug, Default)]
+struct ImageInfo {
+    format: &'static str,
+    width: Option<u32>,
+    height: Option<u32>,
+    exif: Exif,
+    xmp: XmpMetadata,
+}
+
+/// The EXIF (TIFF IFD0, Exif IFD and GPS IFD) fields we index
+#[derive(Debug, Default)]
+struct Exif {
+    make: Option<String>,
+    model: Option<String>,
+    artist: Option<String>,
+    description: Option<String>,
+    /// When the photo was taken
+    date_time_original: Option<String>,
+    /// When it was scanned or digitized
+    date_time_digitized: Option<String>,
+    /// Last modification (IFD0 DateTime)
+    date_time: Option<String>,
+    /// Windows Explorer title and keywords (UTF-16)
+    xp_title: Option<String>,
+    xp_keywords: Option<String>,
+    gps_latitude: Option<f64>,
+    gps_longitude: Option<f64>,
+    width: Option<u32>,
+    height: Option<u32>,
+    /// XMP packet embedded in a TIFF (tag 700)
+    xmp: Option<String>,
+}
+
+/// Extract date taken, camera, location, title, keywords and size from an image
+pub fn extract_image(path: &Path, _max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    let file = File::open(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ParseError::NotFound(path.to_string_lossy().to_string())
+        } else {
+            ParseError::ReadError(e.to_string())
+        }
+    })?;
+    let mut reader = BufReader::new(file);
+
+    let mut magic = Vec::with_capacity(12);
+    (&mut reader)
+        .take(12)
+        .read_to_end(&mut magic)
+        .map_err(|e| ParseError::ReadError(e.to_string()))?;
+
+    let info = if magic.starts_with(&[0xFF, 0xD8]) {
+        read_jpeg(&mut reader)
+    } else if magic.starts_with(b"\x89PNG\r\n\x1a\n") {
+        read_png(&mut reader)
+    } else if magic.starts_with(b"II*\0") || magic.starts_with(b"MM\0*") {
+        read_tiff(&mut reader)
+    } else if magic.get(4..8) == Some(b"ftyp") {
+        read_heif(&mut reader)
+    } else {
+        return Err(ParseError::ParseError("Not a JPEG, PNG, TIFF or HEIF image".to_string()));
+    };
+    let info = info.map_err(|e| ParseError::ReadError(e.to_string()))?;
+
+    Ok(image_document(info))
+}
+
+/// Merge EXIF and XMP fields into metadata and a one-line-per-field summary
+fn image_document(info: ImageInfo) -> ParsedDocument {
+    let ImageInfo { format, width, height, exif, xmp } = info;
+
+    // A scan's digitized date is the best date it has; modification time is the last resort
+    let taken_at = [exif.date_time_original, exif.date_time_digitized, xmp.created, exif.date_time]
+        .into_iter()
+        .flatten()
+        .find_map(|date| normalize_date(&date));
+    let width = width.or(exif.width);
+    let height = height.or(exif.height);
+    let title = xmp.title.or(exif.xp_title);
+    let description = xmp.description.or(exif.description);
+    let keywords = if xmp.keywords.is_empty() {
+        exif.xp_keywords
+            .map(|k| k.split(';').map(|k| k.trim().to_string()).filter(|k| !k.is_empty()).collect())
+            .unwrap_or_default()
+    } else {
+        xmp.keywords
+    };
+    let author = if xmp.creators.is_empty() { exif.artist } else { Some(xmp.creators.join(", ")) };
+    let location = exif.gps_latitude.zip(exif.gps_longitude);
+
+    // Most models already start with the make ("Canon EOS R5")
+    let camera = match (&exif.make, &exif.model) {
+        (Some(make), Some(model)) if model.to_lowercase().starts_with(&make.to_lowercase()) => Some(model.clone()),
+        (Some(make), Some(model)) => Some(format!("{} {}", make, model)),
+        (make, model) => make.clone().or(model.clone()),
+    };
+
+    let mut lines = vec![match (width, height) {
+        (Some(width), Some(height)) => format!("Image: {}, {}x{}", format, width, height),
+        _ => format!("Image: {}", format),
+    }];
+    if let Some(taken_at) = &taken_at {
+        lines.push(format!("Taken: {}", taken_at));
+    }
+    if let Some(camera) = &camera {
+        lines.push(format!("Camera: {}", camera));
+    }
+    if let Some((latitude, longitude)) = location {
+        lines.push(format!("Location: {:.5}, {:.5}", latitude, longitude));
+    }
+    if let Some(title) = &title {
+        lines.push(format!("Title: {}", title));
+    }
+    if let Some(description) = &description {
+        lines.push(format!("Description: {}", description));
+    }
+    if !keywords.is_empty() {
+        lines.push(format!("Keywords: {}", keywords.join(", ")));
+    }
+    if let Some(author) = &author {
+        lines.push(format!("Author: {}", author));
+    }
+
+    // Only metadata to go on: words someone typed beat camera facts
+    let confidence = if title.is_some() || description.is_some() || !keywords.is_empty() {
+        0.60
+    } else if taken_at.is_some() || camera.is_some() {
+        0.40
+    } else {
+        0.20
+    };
+
+    ParsedDocument {
+        content: lines.join("\n"),
+        metadata: DocumentMetadata {
+            title,
+            author,
+            subject: description,
+            keywords,
+            taken_at,
+            camera_make: exif.make,
+            camera_model: exif.model,
+            gps_latitude: location.map(|(latitude, _)| latitude),
+            gps_longitude: location.map(|(_, longitude)| longitude),
+            width,
+            height,
+            ..Default::default()
+        },
+        extraction_confidence: confidence,
+    }
+}
+
+/// "2023:07:14 18:32:05" (EXIF) or "2023-07-14T18:32:05+01:00" (XMP) → "2023-07-14 18:32:05"
+pub(super) fn normalize_date(raw: &str) -> Option<String> {
+    let raw = raw.trim();
+    let bytes = raw.as_bytes();
+    if bytes.len() < 10 || !bytes[..4].iter().all(u8::is_ascii_digit) || !matches!(bytes[4], b':' | b'-') {
+        return None;
+    }
+    // Cameras without a clock write zeros
+    if raw.starts_with("0000") {
+        return None;
+    }
+
+    let date = raw.get(..10)?.replace(':', "-");
+    let time = raw
+        .get(11..19)
+        .filter(|t| t.as_bytes().iter().all(|b| b.is_ascii_digit() || *b == b':'));
+    Some(match time {
+        Some(time) => format!("{} {}", date, time),
+        None => date,
+    })
+}
+
+/// Walk JPEG segments up to the start of the image data
+fn read_jpeg<R: Read + Seek>(reader: &mut R) -> io::Result<ImageInfo> {
+    let mut info = ImageInfo { format: "JPEG", ..Default::default() };
+    reader.seek(SeekFrom::Start(2))?;
+
+    loop {
+        let mut marker = [0u8; 2];
+        if reader.read_exact(&mut marker).is_err() || marker[0] != 0xFF {
+            break;
+        }
+        match marker[1] {
+            // Fill byte before the marker
+            0xFF => {
+                reader.seek(SeekFrom::Current(-1))?;
+                continue;
+            }
+            // Markers without a length
+            0x01 | 0xD0..=0xD7 => continue,
+            // Start of scan or end of image: no metadata after this
+            0xDA | 0xD9 => break,
+            _ => {}
+        }
+
+        let mut length = [0u8; 2];
+        reader.read_exact(&mut length)?;
+        let Some(body_length) = (u16::from_be_bytes(length) as u64).checked_sub(2) else {
+            break;
+        };
+
+        match marker[1] {
+            0xE1 => {
+                let body = read_bytes(reader, body_length, MAX_METADATA_BYTES)?;
+                if let Some(tiff) = body.strip_prefix(b"Exif\0\0") {
+                    info.exif = read_exif(tiff);
+                } else if let Some(xml) = body.strip_prefix(XMP_SIGNATURE) {
+                    info.xmp = parse_xmp(&String::from_utf8_lossy(xml));
+                }
+            }
+            // Start of frame (C4, C8 and CC are tables): precision, height, width
+            0xC0..=0xCF if !matches!(marker[1], 0xC4 | 0xC8 | 0xCC) => {
+                let body = read_bytes(reader, body_length, MAX_METADATA_BYTES)?;
+                if body.len() >= 5 {
+                    info.height = Some(u16::from_be_bytes([body[1], body[2]]) as u32);
+                    info.width = Some(u16::from_be_bytes([body[3], body[4]]) as u32);
+                }
+            }
+            _ => {
+                reader.seek(SeekFrom::Current(body_length as i64))?;
+            }
+        }
+    }
+
+    Ok(info)
+}
+
+/// Walk PNG chunks, skipping image data
+fn read_png<R: Read + Seek>(reader: &mut R) -> io::Result<ImageInfo> {
+    let mut info = ImageInfo { format: "PNG", ..Default::default() };
+    reader.seek(SeekFrom::Start(8))?;
+
+    loop {
+        let mut header = [0u8; 8];
+        if reader.read_exact(&mut header).is_err() {
+            break;
+        }
+        let length = u32::from_be_bytes([header[0], header[1], header[2], header[3]]) as u64;
+        let kind = &header[4..8];
+
+        match kind {
+            b"IHDR" | b"eXIf" | b"tEXt" | b"iTXt" => {
+                let body = read_bytes(reader, length, MAX_METADATA_BYTES)?;
+                read_png_chunk(kind, &body, &mut info);
+            }
+            b"IEND" => break,
+            _ => {
+                reader.seek(SeekFrom::Current(length as i64))?;
+            }
+        }
+        // CRC
+        reader.seek(SeekFrom::Current(4))?;
+    }
+
+    Ok(info)
+}
+
+fn read_png_chunk(kind: &[u8], body: &[u8], info: &mut ImageInfo) {
+    let be_u32 = |at: usize| body.get(at..at + 4).map(|b| u32::from_be_bytes([b[0], b[1], b[2], b[3]]));
+
+    match kind {
+        b"IHDR" => {
+            info.width = be_u32(0);
+            info.height = be_u32(4);
+        }
+        b"eXIf" => info.exif = read_exif(body),
+        b"tEXt" | b"iTXt" => {
+            let Some(nul) = body.iter().position(|&b| b == 0) else {
+                return;
+            };
+            let keyword = &body[..nul];
+            let text = if kind == b"tEXt" {
+                // Latin-1
+                body[nul + 1..].iter().map(|&b| b as char).collect::<String>()
+            } else {
+                // Compression flag and method, then language and translated keyword
+                let rest = &body[nul + 1..];
+                if rest.first() != Some(&0) {
+                    return;
+                }
+                let mut fields = rest.get(2..).unwrap_or_default().splitn(3, |&b| b == 0);
+                let text = fields.nth(2).unwrap_or_default();
+                String::from_utf8_lossy(text).to_string()
+            };
+
+            let text = text.trim().to_string();
+            match keyword {
+                PNG_XMP_KEYWORD => info.xmp = parse_xmp(&text),
+                _ if text.is_empty() => {}
+                b"Title" => info.exif.xp_title = Some(text),
+                b"Author" => info.exif.artist = Some(text),
+                b"Description" => info.exif.description = Some(text),
+                b"Creation Time" => info.exif.date_time_original = Some(text),
+                _ => {}
+            }
+        }
+        _ => {}
+    }
+}
+
+/// TIFF files are themselves EXIF structures
+fn read_tiff<R: Read + Seek>(reader: &mut R) -> io::Result<ImageInfo> {
+    reader.seek(SeekFrom::Start(0))?;
+    let mut data = Vec::new();
+    reader.take(MAX_TIFF_BYTES).read_to_end(&mut data)?;
+
+    let mut exif = read_exif(&data);
+    let xmp = exif.xmp.take().map(|xml| parse_xmp(&xml)).unwrap_or_default();
+    Ok(ImageInfo { format: "TIFF", exif, xmp, ..Default::default() })
+}
+
+/// HEIF (HEIC/AVIF) files: EXIF and XMP are items of the meta box, located by iloc
+fn read_heif<R: Read + Seek>(reader: &mut R) -> io::Result<ImageInfo> {
+    let mut info = ImageInfo { format: "HEIF", ..Default::default() };
+    let Some(meta) = read_top_level_box(reader, b"meta", MAX_METADATA_BYTES)? else {
+        return Ok(info);
+    };
+
+    // meta is a full box: version and flags come first
+    let children = child_boxes(meta.get(4..).unwrap_or_default());
+    let child = |kind: &[u8; 4]| children.iter().find(|(k, _)| k == kind).map(|(_, body)| *body);
+
+    let items = child(b"iinf").map(read_item_infos).unwrap_or_default();
+    let locations = child(b"iloc").map(read_item_locations).unwrap_or_default();
+    let idat = child(b"idat").unwrap_or_default();
+
+    // Pixel size: grid images have one ispe per tile, the largest is the whole image
+    if let Some(ipco) = child(b"iprp").and_then(|iprp| find_box(iprp, b"ipco")) {
+        let largest = child_boxes(ipco)
+            .into_iter()
+            .filter(|(kind, body)| kind == b"ispe" && body.len() >= 12)
+            .map(|(_, body)| (be_u32(body, 4), be_u32(body, 8)))
+            .max_by_key(|(width, height)| *width as u64 * *height as u64);
+        if let Some((width, height)) = largest {
+            info.width = Some(width);
+            info.height = Some(height);
+        }
+    }
+
+    for (item_id, kind, content_type) in items {
+        let is_xmp = kind == *b"mime" && content_type == "application/rdf+xml";
+        if kind != *b"Exif" && !is_xmp {
+            continue;
+        }
+        let Some(&(construction_method, offset, length)) =
+            locations.iter().find(|(id, ..)| *id == item_id).map(|(_, location)| location)
+        else {
+            continue;
+        };
+
+        let data = match construction_method {
+            0 => {
+                reader.seek(SeekFrom::Start(offset))?;
+                read_bytes(reader, length, MAX_METADATA_BYTES)?
+            }
+            1 => {
+                let Some(end) = offset.checked_add(length) else {
+                    continue;
+                };
+                idat.get(offset as usize..end as usize).map(<[u8]>::to_vec).unwrap_or_default()
+            }
+            _ => continue,
+        };
+
+        if is_xmp {
+            info.xmp = parse_xmp(&String::from_utf8_lossy(&data));
+        } else if data.len() >= 4 {
+            // The EXIF item starts with the offset of the TIFF header
+            let tiff_offset = 4 + be_u32(&data, 0) as usize;
+            info.exif = read_exif(data.get(tiff_offset..).unwrap_or_default());
+        }
+    }
+
+    Ok(info)
+}
+
+/// Big-endian fields of varying width, read in order
+struct FieldReader<'a> {
+    data: &'a [u8],
+    at: usize,
+}
+
+impl FieldReader<'_> {
+    fn uint(&mut self, width: usize) -> Option<u64> {
+        let bytes = self.data.get(self.at..self.at + width)?;
+        self.at += width;
+        Some(bytes.iter().fold(0u64, |value, &b| (value << 8) | b as u64))
+    }
+}
+
+/// (item id, item type, MIME content type) from the iinf box
+fn read_item_infos(iinf: &[u8]) -> Vec<(u32, [u8; 4], String)> {
+    // Entry count is 16-bit in version 0, 32-bit after
+    let entries_start = if iinf.first() == Some(&0) { 6 } else { 8 };
+    child_boxes(iinf.get(entries_start..).unwrap_or_default())
+        .into_iter()
+        .filter(|(kind, _)| kind == b"infe")
+        .filter_map(|(_, infe)| {
+            let version = *infe.first()?;
+            // Versions 0 and 1 have no item type
+            let (item_id, type_at) = match version {
+                2 => (u16::from_be_bytes([*infe.get(4)?, *infe.get(5)?]) as u32, 8),
+                3 => (be_u32(infe, 4), 10),
+                _ => return None,
+            };
+            let kind: [u8; 4] = infe.get(type_at..type_at + 4)?.try_into().ok()?;
+            // Then item name and (for mime items) content type, both NUL-terminated
+            let mut strings = infe[type_at + 4..].split(|&b| b == 0);
+            let content_type = strings.nth(1).map(|s| String::from_utf8_lossy(s).to_string()).unwrap_or_default();
+            Some((item_id, kind, content_type))
+        })
+        .collect()
+}
+
+/// item id → (construction method, offset, length) from the iloc box
+fn read_item_locations(iloc: &[u8]) -> Vec<(u32, (u8, u64, u64))> {
+    let mut locations = Vec::new();
+    // A truncated box keeps the items read before the cut
+    read_iloc_items(&mut FieldReader { data: iloc, at: 0 }, &mut locations);
+    locations
+}
+
+fn read_iloc_items(fields: &mut FieldReader, locations: &mut Vec<(u32, (u8, u64, u64))>) -> Option<()> {
+    let version = fields.uint(1)?;
+    fields.uint(3)?;
+    let sizes = fields.uint(1)?;
+    let (offset_size, length_size) = ((sizes >> 4) as usize, (sizes & 0xF) as usize);
+    let sizes = fields.uint(1)?;
+    let base_offset_size = (sizes >> 4) as usize;
+    let index_size = if version >= 1 { (sizes & 0xF) as usize } else { 0 };
+    let item_count = fields.uint(if version < 2 { 2 } else { 4 })?;
+
+    for _ in 0..item_count {
+        let item_id = fields.uint(if version < 2 { 2 } else { 4 })? as u32;
+        let construction_method = if version >= 1 { (fields.uint(2)? & 0xF) as u8 } else { 0 };
+        // Data reference index
+        fields.uint(2)?;
+        let base_offset = fields.uint(base_offset_size)?;
+        let extent_count = fields.uint(2)?;
+
+        // Metadata items are written as one extent; later extents are skipped
+        let mut first_extent = None;
+        for i in 0..extent_count {
+            fields.uint(index_size)?;
+            let offset = fields.uint(offset_size)?;
+            let length = fields.uint(length_size)?;
+            if i == 0 {
+                // An offset past u64 leaves the item without a location
+                first_extent = base_offset.checked_add(offset).map(|offset| (offset, length));
+            }
+        }
+        if let Some((offset, length)) = first_extent {
+            locations.push((item_id, (construction_method, offset, length)));
+        }
+    }
+    Some(())
+}
+
+/// One IFD entry: where its value bytes start
+struct IfdEntry {
+    tag: u16,
+    kind: u16,
+    count: u32,
+    value_at: usize,
+}
+
+/// A TIFF structure (the body of an EXIF block) in either byte order
+struct Tiff<'a> {
+    data: &'a [u8],
+    little_endian: bool,
+}
+
+impl<'a> Tiff<'a> {
+    fn new(data: &'a [u8]) -> Option<Self> {
+        let little_endian = match data.get(..4)? {
+            b"II*\0" => true,
+            b"MM\0*" => false,
+            _ => return None,
+        };
+        Some(Tiff { data, little_endian })
+    }
+
+    fn u16(&self, at: usize) -> Option<u16> {
+        let b = self.data.get(at..at + 2)?;
+        Some(if self.little_endian { u16::from_le_bytes([b[0], b[1]]) } else { u16::from_be_bytes([b[0], b[1]]) })
+    }
+
+    fn u32(&self, at: usize) -> Option<u32> {
+        let b: [u8; 4] = self.data.get(at..at + 4)?.try_into().ok()?;
+        Some(if self.little_endian { u32::from_le_bytes(b) } else { u32::from_be_bytes(b) })
+    }
+
+    /// Entries of the IFD at `offset`
+    fn entries(&self, offset: u32) -> Vec<IfdEntry> {
+        let offset = offset as usize;
+        let count = self.u16(offset).unwrap_or(0).min(1000) as usize;
+        (0..count)
+            .filter_map(|i| {
+                let at = offset + 2 + i * 12;
+                let (tag, kind, count) = (self.u16(at)?, self.u16(at + 2)?, self.u32(at + 4)?);
+                let unit = match kind {
+                    3 | 8 => 2,
+                    4 | 9 | 11 => 4,
+                    5 | 10 | 12 => 8,
+                    _ => 1,
+                };
+                // Values of up to four bytes are stored in the entry itself
+                let value_at = if unit * count as usize <= 4 { at + 8 } else { self.u32(at + 8)? as usize };
+                Some(IfdEntry { tag, kind, count, value_at })
+            })
+            .collect()
+    }
+
+    fn bytes(&self, entry: &IfdEntry) -> Option<&'a [u8]> {
+        self.data.get(entry.value_at..entry.value_at.checked_add(entry.count as usize)?)
+    }
+
+    fn ascii(&self, entry: &IfdEntry) -> Option<String> {
+        let text = String::from_utf8_lossy(self.bytes(entry)?);
+        let text = text.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+        (!text.is_empty()).then(|| text.to_string())
+    }
+
+    fn uint(&self, entry: &IfdEntry) -> Option<u32> {
+        match entry.kind {
+            3 => self.u16(entry.value_at).map(u32::from),
+            4 => self.u32(entry.value_at),
+            _ => None,
+        }
+    }
+
+    /// Unsigned rationals (degrees, minutes, seconds for GPS)
+    fn rationals(&self, entry: &IfdEntry) -> Vec<f64> {
+        if entry.kind != 5 {
+            return Vec::new();
+        }
+        (0..entry.count.min(8) as usize)
+            .filter_map(|i| {
+                let at = entry.value_at + i * 8;
+                let (numerator, denominator) = (self.u32(at)?, self.u32(at + 4)?);
+                (denominator != 0).then(|| numerator as f64 / denominator as f64)
+            })
+            .collect()
+    }
+
+    /// Windows XP* tags hold NUL-terminated UTF-16LE in BYTE arrays
+    fn utf16(&self, entry: &IfdEntry) -> Option<String> {
+        let text = decode_utf16(self.bytes(entry)?);
+        let text = text.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+        (!text.is_empty()).then(|| text.to_string())
+    }
+}
+
+/// Read IFD0, the Exif IFD and the GPS IFD of an EXIF block
+fn read_exif(data: &[u8]) -> Exif {
+    let mut exif = Exif::default();
+    let Some(tiff) = Tiff::new(data) else {
+        return exif;
+    };
+
+    let (mut exif_ifd, mut gps_ifd) = (None, None);
+    for entry in tiff.entries(tiff.u32(4).unwrap_or(0)) {
+        match entry.tag {
+            0x0100 => exif.width = tiff.uint(&entry),
+            0x0101 => exif.height = tiff.uint(&entry),
+            0x010E => exif.description = tiff.ascii(&entry),
+            0x010F => exif.make = tiff.ascii(&entry),
+            0x0110 => exif.model = tiff.ascii(&entry),
+            0x0132 => exif.date_time = tiff.ascii(&entry),
+            0x013B => exif.artist = tiff.ascii(&entry),
+            0x02BC => exif.xmp = tiff.bytes(&entry).map(|b| String::from_utf8_lossy(b).to_string()),
+            0x8769 => exif_ifd = tiff.uint(&entry),
+            0x8825 => gps_ifd = tiff.uint(&entry),
+            0x9C9B => exif.xp_title = tiff.utf16(&entry),
+            0x9C9E => exif.xp_keywords = tiff.utf16(&entry),
+            _ => {}
+        }
+    }
+
+    for entry in exif_ifd.map(|offset| tiff.entries(offset)).unwrap_or_default() {
+        match entry.tag {
+            0x9003 => exif.date_time_original = tiff.ascii(&entry),
+            0x9004 => exif.date_time_digitized = tiff.ascii(&entry),
+            // Pixel size after cropping/rotation beats the IFD0 thumbnail-era size
+            0xA002 => exif.width = tiff.uint(&entry).or(exif.width),
+            0xA003 => exif.height = tiff.uint(&entry).or(exif.height),
+            _ => {}
+        }
+    }
+
+    let (mut latitude, mut longitude) = (None, None);
+    let (mut south, mut west) = (false, false);
+    for entry in gps_ifd.map(|offset| tiff.entries(offset)).unwrap_or_default() {
+        match entry.tag {
+            1 => south = tiff.ascii(&entry).as_deref() == Some("S"),
+            2 => latitude = degrees(&tiff.rationals(&entry)),
+            3 => west = tiff.ascii(&entry).as_deref() == Some("W"),
+            4 => longitude = degrees(&tiff.rationals(&entry)),
+            _ => {}
+        }
+    }
+    // Devices without a fix write 0,0
+    if let (Some(latitude), Some(longitude)) = (latitude, longitude) {
+        if latitude != 0.0 || longitude != 0.0 {
+            exif.gps_latitude = Some(if south { -latitude } else { latitude });
+            exif.gps_longitude = Some(if west { -longitude } else { longitude });
+        }
+    }
+
+    exif
+}
+
+/// Decimal degrees from [degrees, minutes, seconds]
+fn degrees(dms: &[f64]) -> Option<f64> {
+    let (&degrees, rest) = dms.split_first()?;
+    Some(degrees + rest.first().unwrap_or(&0.0) / 60.0 + rest.get(1).unwrap_or(&0.0) / 3600.0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// (tag, TIFF type, value bytes in little-endian order)
+    type Entry = (u16, u16, Vec<u8>);
+
+    fn write_ifd(out: &mut Vec<u8>, entries: &[Entry]) {
+        let unit = |kind: u16| match kind {
+            3 => 2,
+            4 => 4,
+            5 => 8,
+            _ => 1,
+        };
+        let mut data_at = out.len() + 2 + entries.len() * 12 + 4;
+        let mut overflow = Vec::new();
+        out.extend_from_slice(&(entries.len() as u16).to_le_bytes());
+        for (tag, kind, value) in entries {
+            out.extend_from_slice(&tag.to_le_bytes());
+            out.extend_from_slice(&kind.to_le_bytes());
+            out.extend_from_slice(&((value.len() / unit(*kind)) as u32).to_le_bytes());
+            if value.len() <= 4 {
+                let mut inline = value.clone();
+                inline.resize(4, 0);
+                out.extend_from_slice(&inline);
+            } else {
+                out.extend_from_slice(&(data_at as u32).to_le_bytes());
+                overflow.extend_from_slice(value);
+                data_at += value.len();
+            }
+        }
+        out.extend_from_slice(&0u32.to_le_bytes());
+        out.extend_from_slice(&overflow);
+    }
+
+    fn ifd_length(entries: &[Entry]) -> usize {
+        2 + entries.len() * 12 + 4 + entries.iter().map(|(_, _, v)| if v.len() > 4 { v.len() } else { 0 }).sum::<usize>()
+    }
+
+    /// A little-endian EXIF block: IFD0 (plus pointers), Exif IFD, GPS IFD
+    fn exif_block(ifd0: &[Entry], exif: &[Entry], gps: &[Entry]) -> Vec<u8> {
+        let ifd0_length = ifd_length(ifd0) + 2 * 12;
+        let exif_at = 8 + ifd0_length;
+        let gps_at = exif_at + ifd_length(exif);
+        let mut ifd0 = ifd0.to_vec();
+        ifd0.push((0x8769, 4, (exif_at as u32).to_le_bytes().to_vec()));
+        ifd0.push((0x8825, 4, (gps_at as u32).to_le_bytes().to_vec()));
+
+        let mut out = b"II*\0".to_vec();
+        out.extend_from_slice(&8u32.to_le_bytes());
+        write_ifd(&mut out, &ifd0);
+        write_ifd(&mut out, exif);
+        write_ifd(&mut out, gps);
+        out
+    }
+
+    fn ascii(text: &str) -> Vec<u8> {
+        let mut bytes = text.as_bytes().to_vec();
+        bytes.push(0);
+        bytes
+    }
+
+    fn rationals(values: &[(u32, u32)]) -> Vec<u8> {
+        values.iter().flat_map(|(n, d)| n.to_le_bytes().into_iter().chain(d.to_le_bytes())).collect()
+    }
+
+    fn camera_exif() -> Vec<u8> {
+        exif_block(
+            &[(0x010F, 2, ascii("Canon")), (0x0110, 2, ascii("Canon EOS R5")), (0x0132, 2, ascii("2023:08:01 09:00:00"))],
+            &[(0x9003, 2, ascii("2023:07:14 18:32:05"))],
+            &[
+                (1, 2, ascii("N")),
+                (2, 5, rationals(&[(38, 1), (43, 1), (2028, 100)])),
+                (3, 2, ascii("W")),
+                (4, 5, rationals(&[(9, 1), (8, 1), (2148, 100)])),
+            ],
+        )
+    }
+
+    #[test]
+    fn test_extract_jpeg_exif_xmp_and_size() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("IMG_0001.jpg");
+
+        let segment = |marker: u8, body: &[u8]| {
+            let mut out = vec![0xFF, marker];
+            out.extend_from_slice(&((body.len() + 2) as u16).to_be_bytes());
+            out.extend_from_slice(body);
+            out
+        };
+        let xmp = r#"<x:xmpmeta><rdf:RDF><rdf:Description>
+            <dc:subject><rdf:Bag><rdf:li>holiday</rdf:li></rdf:Bag></dc:subject>
+            </rdf:Description></rdf:RDF></x:xmpmeta>"#;
+
+        let mut jpeg = vec![0xFF, 0xD8];
+        jpeg.extend(segment(0xE0, b"JFIF\0\x01\x02"));
+        jpeg.extend(segment(0xE1, &[b"Exif\0\0".as_slice(), &camera_exif()].concat()));
+        jpeg.extend(segment(0xE1, &[XMP_SIGNATURE, xmp.as_bytes()].concat()));
+        jpeg.extend(segment(0xC4, &[0; 20]));
+        // 8-bit, 3000 high, 4000 wide
+        jpeg.extend(segment(0xC0, &[8, 0x0B, 0xB8, 0x0F, 0xA0, 3]));
+        jpeg.extend([0xFF, 0xDA, 0x00, 0x02, 0x12, 0x34, 0xFF, 0xD9]);
+        std::fs::write(&path, jpeg).unwrap();
+
+        let result = extract_image(&path, 2000).unwrap();
+        assert_eq!(
+            result.content,
+            "Image: JPEG, 4000x3000\nTaken: 2023-07-14 18:32:05\nCamera: Canon EOS R5\n\
+             Location: 38.72230, -9.13930\nKeywords: holiday"
+        );
+        let metadata = result.metadata;
+        assert_eq!(metadata.taken_at.as_deref(), Some("2023-07-14 18:32:05"));
+        assert_eq!(metadata.camera_make.as_deref(), Some("Canon"));
+        assert_eq!((metadata.width, metadata.height), (Some(4000), Some(3000)));
+        assert!((metadata.gps_latitude.unwrap() - 38.7223).abs() < 1e-6);
+        assert!((metadata.gps_longitude.unwrap() + 9.1393).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_extract_png_text_and_xmp() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("receipt.png");
+
+        let chunk = |kind: &[u8], body: &[u8]| {
+            let mut out = (body.len() as u32).to_be_bytes().to_vec();
+            out.extend_from_slice(kind);
+            out.extend_from_slice(body);
+            out.extend_from_slice(&[0; 4]);
+            out
+        };
+        let xmp = r#"<rdf:RDF><rdf:Description xmp:CreateDate="2024-02-03T10:11:12Z">
+            <dc:title><rdf:Alt><rdf:li>Hardware store receipt</rdf:li></rdf:Alt></dc:title>
+            </rdf:Description></rdf:RDF>"#;
+
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.extend(chunk(b"IHDR", &[0, 0, 2, 0, 0, 0, 1, 0, 8, 2, 0, 0, 0]));
+        png.extend(chunk(b"tEXt", b"Author\0Sam"));
+        png.extend(chunk(b"iTXt", &[PNG_XMP_KEYWORD, b"\0\0\0\0\0", xmp.as_bytes()].concat()));
+        png.extend(chunk(b"IDAT", &[0; 32]));
+        png.extend(chunk(b"IEND", &[]));
+        std::fs::write(&path, png).unwrap();
+
+        let result = extract_image(&path, 2000).unwrap();
+        assert_eq!(
+            result.content,
+            "Image: PNG, 512x256\nTaken: 2024-02-03 10:11:12\nTitle: Hardware store receipt\nAuthor: Sam"
+        );
+        assert_eq!(result.metadata.title.as_deref(), Some("Hardware store receipt"));
+    }
+
+    #[test]
+    fn test_extract_heic_exif_item() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("IMG_0002.heic");
+
+        let make_box = |kind: &[u8], body: &[u8]| {
+            let mut out = ((body.len() + 8) as u32).to_be_bytes().to_vec();
+            out.extend_from_slice(kind);
+            out.extend_from_slice(body);
+            out
+        };
+        let exif_item = [&[0u8, 0, 0, 0][..], &camera_exif()].concat();
+
+        let meta = |exif_offset: u32| {
+            let infe = make_box(b"infe", &[&[2, 0, 0, 0, 0, 1, 0, 0][..], b"Exif", b"\0"].concat());
+            let iinf = make_box(b"iinf", &[&[0, 0, 0, 0, 0, 1][..], &infe].concat());
+            let mut iloc = vec![0, 0, 0, 0, 0x44, 0x00, 0, 1, 0, 1, 0, 0, 0, 1];
+            iloc.extend_from_slice(&exif_offset.to_be_bytes());
+            iloc.extend_from_slice(&(exif_item.len() as u32).to_be_bytes());
+            let iloc = make_box(b"iloc", &iloc);
+            let ispe = |width: u32, height: u32| {
+                make_box(b"ispe", &[&[0u8; 4][..], &width.to_be_bytes(), &height.to_be_bytes()].concat())
+            };
+            let ipco = make_box(b"ipco", &[ispe(512, 512), ispe(4032, 3024)].concat());
+            let iprp = make_box(b"iprp", &ipco);
+            make_box(b"meta", &[&[0u8; 4][..], &iinf, &iloc, &iprp].concat())
+        };
+
+        let ftyp = make_box(b"ftyp", b"heic\0\0\0\0mif1heic");
+        let exif_offset = (ftyp.len() + meta(0).len() + 8) as u32;
+        let heic = [ftyp, meta(exif_offset), make_box(b"mdat", &exif_item)].concat();
+        std::fs::write(&path, heic).unwrap();
+
+        let result = extract_image(&path, 2000).unwrap();
+        assert_eq!(result.metadata.taken_at.as_deref(), Some("2023-07-14 18:32:05"));
+        assert_eq!(result.metadata.camera_model.as_deref(), Some("Canon EOS R5"));
+        assert_eq!((result.metadata.width, result.metadata.height), (Some(4032), Some(3024)));
+        assert!(result.content.starts_with("Image: HEIF, 4032x3024\n"));
+    }
+
+    #[test]
+    fn test_iloc_offset_overflow_skips_item() {
+        // Version 0, 8-byte offsets and base offsets, 4-byte lengths, two items
+        let mut iloc = vec![0, 0, 0, 0, 0x84, 0x80, 0, 2];
+        for (item_id, base_offset, offset) in [(1u16, u64::MAX, 1u64), (2, 16, 8)] {
+            iloc.extend_from_slice(&item_id.to_be_bytes());
+            iloc.extend_from_slice(&[0, 0]);
+            iloc.extend_from_slice(&base_offset.to_be_bytes());
+            iloc.extend_from_slice(&[0, 1]);
+            iloc.extend_from_slice(&offset.to_be_bytes());
+            iloc.extend_from_slice(&100u32.to_be_bytes());
+        }
+        assert_eq!(read_item_locations(&iloc), vec![(2, (0, 24, 100))]);
+    }
+}
@@ -0,0 +1,798 @@
+//! Audio & Video Tag Parser (Group 8 - Media)
+//!
+//! Handles: .mp3 (ID3v2, ID3v1), .flac (Vorbis comments), .m4a, .m4b, .mp4, .m4v, .mov (MP4/QuickTime atoms)
+//! Strategy: Read only the tag blocks, never the audio or video data → title, artist,
+//! album, genre, comment, recording date → duration from the stream headers
+
+use super::bmff::{be_u32, child_boxes, find_box, read_top_level_box};
+use super::image::normalize_date;
+use super::{read_bytes, DocumentMetadata, ParseError, ParsedDocument};
+use std::fs::File;
+use std::io::{self, BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// Largest tag block read into memory (cover art makes ID3 tags large)
+const MAX_TAG_BYTES: u64 = 16 * 1024 * 1024;
+
+/// Largest MP4 movie box read; long recordings carry big sample tables
+const MAX_MOOV_BYTES: u64 = 32 * 1024 * 1024;
+
+/// How far past the ID3 tag to look for the first MPEG frame
+const FRAME_SEARCH_BYTES: u64 = 64 * 1024;
+
+/// Seconds between the QuickTime epoch (1904) and the Unix epoch
+const QUICKTIME_EPOCH_OFFSET: i64 = 2_082_844_800;
+
+/// ID3v1 genres 0-79, also used by ID3v2 "(n)" references and MP4 gnre atoms
+const ID3_GENRES: [&str; 80] = [
+    "Blues", "Classic Rock", "Country", "Dance", "Disco", "Funk", "Grunge", "Hip-Hop", "Jazz", "Metal",
+    "New Age", "Oldies", "Other", "Pop", "R&B", "Rap", "Reggae", "Rock", "Techno", "Industrial",
+    "Alternative", "Ska", "Death Metal", "Pranks", "Soundtrack", "Euro-Techno", "Ambient", "Trip-Hop", "Vocal",
+    "Jazz+Funk", "Fusion", "Trance", "Classical", "Instrumental", "Acid", "House", "Game", "Sound Clip",
+    "Gospel", "Noise", "AlternRock", "Bass", "Soul", "Punk", "Space", "Meditative", "Instrumental Pop",
+    "Instrumental Rock", "Ethnic", "Gothic", "Darkwave", "Techno-Industrial", "Electronic", "Pop-Folk",
+    "Eurodance", "Dream", "Southern Rock", "Comedy", "Cult", "Gangsta", "Top 40", "Christian Rap", "Pop/Funk",
+    "Jungle", "Native American", "Cabaret", "New Wave", "Psychadelic", "Rave", "Showtunes", "Trailer", "Lo-Fi",
+    "Tribal", "Acid Punk", "Acid Jazz", "Polka", "Retro", "Musical", "Rock & Roll", "Hard Rock",
+];
+
+/// Winamp extension genre that podcast tools write
+const PODCAST_GENRE: u32 = 186;
+
+/// Tags gathered from whichever container the file uses
+#[derive(Debug, Default)]
+struct MediaTags {
+    format: &'static str,
+    video: bool,
+    title: Option<String>,
+    artist: Option<String>,
+    album: Option<String>,
+    genre: Option<String>,
+    comment: Option<String>,
+    date: Option<String>,
+    duration_secs: Option<f64>,
+    width: Option<u32>,
+    height: Option<u32>,
+}
+
+/// The tag fields shared by ID3, Vorbis comments and MP4 atoms
+#[derive(Debug, Clone, Copy)]
+enum Tag {
+    Title,
+    Artist,
+    Album,
+    Genre,
+    Comment,
+    Date,
+}
+
+impl MediaTags {
+    /// Set a field unless an earlier (preferred) source already did
+    fn set(&mut self, tag: Tag, value: &str) {
+        let slot = match tag {
+            Tag::Title => &mut self.title,
+            Tag::Artist => &mut self.artist,
+            Tag::Album => &mut self.album,
+            Tag::Genre => &mut self.genre,
+            Tag::Comment => &mut self.comment,
+            Tag::Date => &mut self.date,
+        };
+        let value = value.trim_matches(|c: char| c == '\0' || c.is_whitespace());
+        if slot.is_none() && !value.is_empty() {
+            *slot = Some(value.to_string());
+        }
+    }
+}
+
+/// Extract title, artist, album, genre, recording date and duration from an audio or video file
+pub fn extract_media(path: &Path, _max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    let file = File::open(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ParseError::NotFound(path.to_string_lossy().to_string())
+        } else {
+            ParseError::ReadError(e.to_string())
+        }
+    })?;
+    let file_size = file.metadata().map(|m| m.len()).unwrap_or(0);
+    let mut reader = BufReader::new(file);
+
+    let mut magic = Vec::with_capacity(12);
+    (&mut reader)
+        .take(12)
+        .read_to_end(&mut magic)
+        .map_err(|e| ParseError::ReadError(e.to_string()))?;
+
+    let box_type = magic.get(4..8).unwrap_or_default();
+    let tags = if magic.starts_with(b"ID3") || (magic.len() >= 2 && magic[0] == 0xFF && magic[1] & 0xE0 == 0xE0) {
+        read_mp3(&mut reader, file_size)
+    } else if magic.starts_with(b"fLaC") {
+        read_flac(&mut reader)
+    } else if matches!(box_type, b"ftyp" | b"moov" | b"mdat" | b"wide" | b"free" | b"skip") {
+        // Old QuickTime files have no ftyp box
+        read_mp4(&mut reader)
+    } else {
+        return Err(ParseError::ParseError("Not an MP3, FLAC or MP4/QuickTime file".to_string()));
+    };
+    let tags = tags.map_err(|e| ParseError::ReadError(e.to_string()))?;
+
+    Ok(media_document(tags))
+}
+
+/// Summary lines and metadata for the tags found
+fn media_document(tags: MediaTags) -> ParsedDocument {
+    let date = tags.date.map(|raw| normalize_date(&raw).unwrap_or(raw));
+
+    let mut first_line = format!("{}: {}", if tags.video { "Video" } else { "Audio" }, tags.format);
+    if let (Some(width), Some(height)) = (tags.width, tags.height) {
+        first_line.push_str(&format!(", {}x{}", width, height));
+    }
+    if let Some(duration) = tags.duration_secs {
+        first_line.push_str(&format!(", {}", format_duration(duration)));
+    }
+
+    let mut lines = vec![first_line];
+    for (label, value) in [
+        ("Title", &tags.title),
+        ("Artist", &tags.artist),
+        ("Album", &tags.album),
+        ("Genre", &tags.genre),
+        ("Recorded", &date),
+        ("Comment", &tags.comment),
+    ] {
+        if let Some(value) = value {
+            lines.push(format!("{}: {}", label, value));
+        }
+    }
+
+    // Same scale as images: typed-in names beat technical facts
+    let confidence = if tags.title.is_some() || tags.artist.is_some() || tags.album.is_some() || tags.comment.is_some() {
+        0.60
+    } else if tags.genre.is_some() || date.is_some() {
+        0.40
+    } else {
+        0.20
+    };
+
+    ParsedDocument {
+        content: lines.join("\n"),
+        metadata: DocumentMetadata {
+            title: tags.title,
+            author: tags.artist,
+            subject: tags.comment,
+            keywords: tags.genre.into_iter().collect(),
+            album: tags.album,
+            duration_secs: tags.duration_secs,
+            created_at: date,
+            width: tags.width,
+            height: tags.height,
+            ..Default::default()
+        },
+        extraction_confidence: confidence,
+    }
+}
+
+/// 42:17 or 1:02:03
+fn format_duration(seconds: f64) -> String {
+    let total = seconds.round() as u64;
+    let (hours, minutes, seconds) = (total / 3600, total / 60 % 60, total % 60);
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{}:{:02}", minutes, seconds)
+    }
+}
+
+/// Genre names from "(13)", "13", "(13)Refined" or plain text
+fn genre_name(raw: &str) -> String {
+    let raw = raw.trim();
+    let number = match raw.strip_prefix('(').and_then(|rest| rest.split_once(')')) {
+        // A refinement after the reference is more specific than the number
+        Some((_, refinement)) if !refinement.trim().is_empty() => return refinement.trim().to_string(),
+        Some((number, _)) => number,
+        None => raw,
+    };
+    match number.parse::<u32>() {
+        Ok(PODCAST_GENRE) => "Podcast".to_string(),
+        Ok(n) => ID3_GENRES.get(n as usize).map_or_else(|| raw.to_string(), |g| g.to_string()),
+        Err(_) => raw.to_string(),
+    }
+}
+
+fn latin1(bytes: &[u8]) -> String {
+    bytes.iter().map(|&b| b as char).collect()
+}
+
+fn utf16(bytes: &[u8], big_endian: bool) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|c| if big_endian { u16::from_be_bytes([c[0], c[1]]) } else { u16::from_le_bytes([c[0], c[1]]) })
+        .collect();
+    // Multi-value frames repeat the byte order mark
+    String::from_utf16_lossy(&units).replace('\u{feff}', "")
+}
+
+/// Decode an ID3v2 string in its frame's encoding (0 Latin-1, 1 UTF-16 with BOM, 2 UTF-16BE, 3 UTF-8)
+fn id3_string(encoding: u8, data: &[u8]) -> String {
+    match encoding {
+        0 => latin1(data),
+        1 => match data {
+            [0xFE, 0xFF, rest @ ..] => utf16(rest, true),
+            [0xFF, 0xFE, rest @ ..] => utf16(rest, false),
+            _ => utf16(data, false),
+        },
+        2 => utf16(data, true),
+        _ => String::from_utf8_lossy(data).to_string(),
+    }
+}
+
+/// A text frame's value; ID3v2.4 separates multiple values with NUL
+fn id3_text(body: &[u8]) -> String {
+    let Some((&encoding, data)) = body.split_first() else {
+        return String::new();
+    };
+    let text = id3_string(encoding, data);
+    let values: Vec<&str> = text.split('\0').map(str::trim).filter(|v| !v.is_empty()).collect();
+    values.join(", ")
+}
+
+fn syncsafe(bytes: &[u8]) -> u32 {
+    bytes.iter().take(4).fold(0, |value, &b| (value << 7) | (b & 0x7F) as u32)
+}
+
+/// Undo ID3 unsynchronisation (0xFF 0x00 → 0xFF)
+fn remove_unsynchronisation(data: &[u8]) -> Vec<u8> {
+    let mut out = Vec::with_capacity(data.len());
+    for (i, &b) in data.iter().enumerate() {
+        if !(b == 0x00 && i > 0 && data[i - 1] == 0xFF) {
+            out.push(b);
+        }
+    }
+    out
+}
+
+fn read_mp3<R: Read + Seek>(reader: &mut R, file_size: u64) -> io::Result<MediaTags> {
+    let mut tags = MediaTags { format: "MP3", ..Default::default() };
+    let mut audio_start = 0;
+
+    reader.seek(SeekFrom::Start(0))?;
+    let mut header = [0u8; 10];
+    if reader.read_exact(&mut header).is_ok() && header.starts_with(b"ID3") {
+        let (version, flags) = (header[3], header[5]);
+        let size = syncsafe(&header[6..10]) as u64;
+        let mut tag = read_bytes(reader, size, MAX_TAG_BYTES)?;
+        // A footer repeats the header after the tag
+        audio_start = 10 + size + if flags & 0x10 != 0 { 10 } else { 0 };
+        // Versions before 2.4 unsynchronise the whole tag, 2.4 each frame
+        if flags & 0x80 != 0 && version < 4 {
+            tag = remove_unsynchronisation(&tag);
+        }
+        read_id3v2_frames(&tag, version, flags, &mut tags);
+    }
+
+    // ID3v1 fills in what ID3v2 lacked
+    if file_size >= 128 {
+        reader.seek(SeekFrom::End(-128))?;
+        let mut v1 = [0u8; 128];
+        reader.read_exact(&mut v1)?;
+        if v1.starts_with(b"TAG") {
+            read_id3v1(&v1, &mut tags);
+        }
+    }
+
+    if tags.duration_secs.is_none() {
+        tags.duration_secs = mp3_duration(reader, audio_start, file_size)?;
+    }
+    Ok(tags)
+}
+
+fn read_id3v2_frames(tag: &[u8], version: u8, flags: u8, tags: &mut MediaTags) {
+    let mut at = 0;
+    if flags & 0x40 != 0 && version >= 3 {
+        // Extended header: its 2.3 size excludes the size field, its 2.4 size is syncsafe and includes it
+        at = if version == 3 { 4 + be_u32(tag, 0) as usize } else { syncsafe(tag.get(..4).unwrap_or_default()) as usize };
+    }
+    // ID3v2.2 frames have three-character ids and 24-bit sizes
+    let (id_length, header_length) = if version == 2 { (3, 6) } else { (4, 10) };
+
+    while at + header_length <= tag.len() {
+        let id = &tag[at..at + id_length];
+        // Padding
+        if id[0] == 0 {
+            break;
+        }
+        let size = match version {
+            2 => (be_u32(&[&[0][..], &tag[at + 3..at + 6]].concat(), 0)) as usize,
+            3 => be_u32(tag, at + 4) as usize,
+            _ => syncsafe(&tag[at + 4..at + 8]) as usize,
+        };
+        let format_flags = if version >= 3 { tag[at + 9] } else { 0 };
+        let body_start = at + header_length;
+        let Some(body) = tag.get(body_start..body_start + size) else {
+            break;
+        };
+        at = body_start + size;
+
+        // Compressed and encrypted frames are skipped
+        let body = match version {
+            3 if format_flags & 0xC0 != 0 => continue,
+            4 if format_flags & 0x0C != 0 => continue,
+            4 => {
+                // Data length indicator, then per-frame unsynchronisation
+                let body = if format_flags & 0x01 != 0 { body.get(4..).unwrap_or_default() } else { body };
+                if format_flags & 0x02 != 0 {
+                    remove_unsynchronisation(body)
+                } else {
+                    body.to_vec()
+                }
+            }
+            _ => body.to_vec(),
+        };
+
+        match id {
+            b"TIT2" | b"TT2" => tags.set(Tag::Title, &id3_text(&body)),
+            b"TPE1" | b"TP1" => tags.set(Tag::Artist, &id3_text(&body)),
+            b"TALB" | b"TAL" => tags.set(Tag::Album, &id3_text(&body)),
+            b"TCON" | b"TCO" => tags.set(Tag::Genre, &genre_name(&id3_text(&body))),
+            // Recording time (2.4) or year (2.3, 2.2)
+            b"TDRC" | b"TYER" | b"TYE" => tags.set(Tag::Date, &id3_text(&body)),
+            b"TLEN" | b"TLE" => {
+                let milliseconds = id3_text(&body).parse::<f64>().ok().filter(|ms| *ms > 0.0);
+                tags.duration_secs = tags.duration_secs.or(milliseconds.map(|ms| ms / 1000.0));
+            }
+            // Encoding, language, description, then the text
+            b"COMM" | b"COM" if body.len() > 4 => {
+                let text = id3_string(body[0], &body[4..]);
+                let (description, text) = text.split_once('\0').unwrap_or(("", &text));
+                // iTunes stores normalization and gapless data as comments
+                if !description.starts_with("iTun") {
+                    tags.set(Tag::Comment, text);
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn read_id3v1(tag: &[u8; 128], tags: &mut MediaTags) {
+    let field = |range: std::ops::Range<usize>| latin1(&tag[range]);
+    tags.set(Tag::Title, &field(3..33));
+    tags.set(Tag::Artist, &field(33..63));
+    tags.set(Tag::Album, &field(63..93));
+    tags.set(Tag::Date, &field(93..97));
+    // ID3v1.1 ends the comment early to fit a track number
+    let comment_end = if tag[125] == 0 && tag[126] != 0 { 125 } else { 127 };
+    tags.set(Tag::Comment, &field(97..comment_end));
+    if tag[127] != 255 {
+        tags.set(Tag::Genre, &genre_name(&tag[127].to_string()));
+    }
+}
+
+/// MPEG audio frame header fields needed for the duration
+struct MpegFrame {
+    bitrate: u32,
+    sample_rate: u32,
+    samples: u32,
+    /// Layer III side information, after which a Xing/Info header may sit
+    side_info: usize,
+}
+
+fn mpeg_frame(header: &[u8]) -> Option<MpegFrame> {
+    let h = header.get(..4)?;
+    if h[0] != 0xFF || h[1] & 0xE0 != 0xE0 {
+        return None;
+    }
+    // Version: 0 MPEG 2.5, 2 MPEG 2, 3 MPEG 1; layer: 1 III, 2 II, 3 I
+    let (version, layer) = ((h[1] >> 3) & 3, (h[1] >> 1) & 3);
+    let (bitrate_index, sample_index) = ((h[2] >> 4) as usize, ((h[2] >> 2) & 3) as usize);
+    if version == 1 || layer == 0 || bitrate_index == 0 || bitrate_index == 15 || sample_index == 3 {
+        return None;
+    }
+
+    const V1_L1: [u32; 14] = [32, 64, 96, 128, 160, 192, 224, 256, 288, 320, 352, 384, 416, 448];
+    const V1_L2: [u32; 14] = [32, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320, 384];
+    const V1_L3: [u32; 14] = [32, 40, 48, 56, 64, 80, 96, 112, 128, 160, 192, 224, 256, 320];
+    const V2_L1: [u32; 14] = [32, 48, 56, 64, 80, 96, 112, 128, 144, 160, 176, 192, 224, 256];
+    const V2_L23: [u32; 14] = [8, 16, 24, 32, 40, 48, 56, 64, 80, 96, 112, 128, 144, 160];
+
+    let mpeg1 = version == 3;
+    let kbps = match (mpeg1, layer) {
+        (true, 3) => V1_L1,
+        (true, 2) => V1_L2,
+        (true, _) => V1_L3,
+        (false, 3) => V2_L1,
+        (false, _) => V2_L23,
+    }[bitrate_index - 1];
+    let sample_rate = [44100, 48000, 32000][sample_index] >> if mpeg1 { 0 } else if version == 2 { 1 } else { 2 };
+    let samples = match layer {
+        3 => 384,
+        2 => 1152,
+        _ if mpeg1 => 1152,
+        _ => 576,
+    };
+    let mono = h[3] >> 6 == 3;
+    let side_info = match (mpeg1, mono) {
+        (true, true) => 17,
+        (true, false) => 32,
+        (false, true) => 9,
+        (false, false) => 17,
+    };
+
+    Some(MpegFrame { bitrate: kbps * 1000, sample_rate, samples, side_info })
+}
+
+/// Duration from the Xing/Info or VBRI frame count, else from the bitrate (constant bitrate files)
+fn mp3_duration<R: Read + Seek>(reader: &mut R, audio_start: u64, file_size: u64) -> io::Result<Option<f64>> {
+    reader.seek(SeekFrom::Start(audio_start))?;
+    let head = read_bytes(reader, FRAME_SEARCH_BYTES, MAX_TAG_BYTES)?;
+
+    let Some((at, frame)) = (0..head.len().saturating_sub(4)).find_map(|i| mpeg_frame(&head[i..]).map(|f| (i, f))) else {
+        return Ok(None);
+    };
+
+    let xing_at = at + 4 + frame.side_info;
+    let vbri_at = at + 4 + 32;
+    let frame_count = if matches!(head.get(xing_at..xing_at + 4), Some(b"Xing" | b"Info")) && be_u32(&head, xing_at + 4) & 1 != 0 {
+        Some(be_u32(&head, xing_at + 8))
+    } else if head.get(vbri_at..vbri_at + 4) == Some(b"VBRI") {
+        Some(be_u32(&head, vbri_at + 14))
+    } else {
+        None
+    };
+
+    Ok(match frame_count {
+        Some(frames) if frames > 0 => Some(frames as f64 * frame.samples as f64 / frame.sample_rate as f64),
+        _ => {
+            let audio_bytes = file_size.saturating_sub(audio_start + at as u64);
+            Some(audio_bytes as f64 * 8.0 / frame.bitrate as f64)
+        }
+    })
+}
+
+fn read_flac<R: Read + Seek>(reader: &mut R) -> io::Result<MediaTags> {
+    let mut tags = MediaTags { format: "FLAC", ..Default::default() };
+    reader.seek(SeekFrom::Start(4))?;
+
+    loop {
+        let mut header = [0u8; 4];
+        if reader.read_exact(&mut header).is_err() {
+            break;
+        }
+        let last = header[0] & 0x80 != 0;
+        let length = u32::from_be_bytes([0, header[1], header[2], header[3]]) as u64;
+
+        match header[0] & 0x7F {
+            // STREAMINFO: 20-bit sample rate and 36-bit total sample count
+            0 => {
+                let info = read_bytes(reader, length, MAX_TAG_BYTES)?;
+                if info.len() >= 18 {
+                    let sample_rate = (info[10] as u32) << 12 | (info[11] as u32) << 4 | (info[12] as u32) >> 4;
+                    let total_samples = ((info[13] & 0x0F) as u64) << 32 | be_u32(&info, 14) as u64;
+                    if sample_rate > 0 && total_samples > 0 {
+                        tags.duration_secs = Some(total_samples as f64 / sample_rate as f64);
+                    }
+                }
+            }
+            4 => read_vorbis_comments(&read_bytes(reader, length, MAX_TAG_BYTES)?, &mut tags),
+            _ => {
+                reader.seek(SeekFrom::Current(length as i64))?;
+            }
+        }
+        if last {
+            break;
+        }
+    }
+
+    Ok(tags)
+}
+
+/// Vendor string, then "KEY=value" comments, all with little-endian lengths
+fn read_vorbis_comments(block: &[u8], tags: &mut MediaTags) {
+    let le_u32 = |at: usize| block.get(at..at + 4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]) as usize);
+    let Some(vendor_length) = le_u32(0) else {
+        return;
+    };
+    let mut at = 4 + vendor_length;
+    let count = le_u32(at).unwrap_or(0);
+    at += 4;
+
+    for _ in 0..count.min(1000) {
+        let Some(length) = le_u32(at) else {
+            break;
+        };
+        let Some(comment) = block.get(at + 4..at + 4 + length) else {
+            break;
+        };
+        at += 4 + length;
+
+        let comment = String::from_utf8_lossy(comment);
+        let Some((key, value)) = comment.split_once('=') else {
+            continue;
+        };
+        let tag = match key.to_ascii_uppercase().as_str() {
+            "TITLE" => Tag::Title,
+            "ARTIST" => Tag::Artist,
+            "ALBUM" => Tag::Album,
+            "GENRE" => Tag::Genre,
+            "DATE" => Tag::Date,
+            "COMMENT" | "DESCRIPTION" => Tag::Comment,
+            _ => continue,
+        };
+        tags.set(tag, value);
+    }
+}
+
+fn read_mp4<R: Read + Seek>(reader: &mut R) -> io::Result<MediaTags> {
+    let brand = read_top_level_box(reader, b"ftyp", 64)?.and_then(|ftyp| ftyp.get(..4).map(<[u8]>::to_vec));
+    let format = match brand.as_deref() {
+        Some(b"qt  ") | None => "QuickTime",
+        Some(b"M4A " | b"M4B " | b"M4P ") => "M4A",
+        _ => "MP4",
+    };
+    let mut tags = MediaTags { format, ..Default::default() };
+
+    let Some(moov) = read_top_level_box(reader, b"moov", MAX_MOOV_BYTES)? else {
+        return Ok(tags);
+    };
+
+    let mut created = None;
+    if let Some(mvhd) = find_box(&moov, b"mvhd") {
+        // Version 1 widens the times and duration to 64 bits
+        let (creation, timescale, duration) = if mvhd.first() == Some(&1) {
+            let wide = |at: usize| (be_u32(mvhd, at) as u64) << 32 | be_u32(mvhd, at + 4) as u64;
+            (wide(4), be_u32(mvhd, 20), wide(24))
+        } else {
+            (be_u32(mvhd, 4) as u64, be_u32(mvhd, 12), be_u32(mvhd, 16) as u64)
+        };
+        if timescale > 0 && duration > 0 {
+            tags.duration_secs = Some(duration as f64 / timescale as f64);
+        }
+        if creation > 0 {
+            // A time the Unix timestamp cannot hold is treated as missing
+            created = i64::try_from(creation)
+                .ok()
+                .and_then(|creation| creation.checked_sub(QUICKTIME_EPOCH_OFFSET))
+                .and_then(|timestamp| chrono::DateTime::from_timestamp(timestamp, 0))
+                .map(|date| date.format("%Y-%m-%d %H:%M:%S").to_string());
+        }
+    }
+
+    for (_, trak) in child_boxes(&moov).into_iter().filter(|(kind, _)| kind == b"trak") {
+        let handler = find_box(trak, b"mdia").and_then(|mdia| find_box(mdia, b"hdlr")).and_then(|hdlr| hdlr.get(8..12));
+        if handler != Some(b"vide") {
+            continue;
+        }
+        tags.video = true;
+        // Track header: 16.16 fixed-point width and height at the end
+        if let Some(tkhd) = find_box(trak, b"tkhd") {
+            let at = if tkhd.first() == Some(&1) { 88 } else { 76 };
+            let (width, height) = (be_u32(tkhd, at) >> 16, be_u32(tkhd, at + 4) >> 16);
+            if width > 0 && height > 0 && tags.width.is_none() {
+                tags.width = Some(width);
+                tags.height = Some(height);
+            }
+        }
+    }
+
+    // iTunes tags (moov/udta/meta), QuickTime user data (moov/udta/©xxx) and QuickTime metadata (moov/meta)
+    if let Some(udta) = find_box(&moov, b"udta") {
+        for (kind, body) in child_boxes(udta) {
+            if &kind == b"meta" {
+                read_mp4_meta(body, &mut tags);
+            } else if let Some(tag) = mp4_atom_tag(&kind) {
+                // 16-bit length and language, then the text
+                let length = u16::from_be_bytes([body.first().copied().unwrap_or(0), body.get(1).copied().unwrap_or(0)]) as usize;
+                if let Some(text) = body.get(4..4 + length) {
+                    tags.set(tag, &String::from_utf8_lossy(text));
+                }
+            }
+        }
+    }
+    if let Some(meta) = find_box(&moov, b"meta") {
+        read_mp4_meta(meta, &mut tags);
+    }
+
+    if let Some(created) = created {
+        tags.set(Tag::Date, &created);
+    }
+    Ok(tags)
+}
+
+/// Tag for an iTunes/QuickTime atom type
+fn mp4_atom_tag(kind: &[u8; 4]) -> Option<Tag> {
+    Some(match kind {
+        b"\xA9nam" => Tag::Title,
+        b"\xA9ART" | b"aART" | b"\xA9aut" => Tag::Artist,
+        b"\xA9alb" => Tag::Album,
+        b"\xA9gen" => Tag::Genre,
+        b"\xA9day" => Tag::Date,
+        b"\xA9cmt" | b"desc" | b"\xA9des" => Tag::Comment,
+        _ => return None,
+    })
+}
+
+/// Tag for a QuickTime metadata key (com.apple.quicktime.*)
+fn quicktime_key_tag(key: &str) -> Option<Tag> {
+    Some(match key.strip_prefix("com.apple.quicktime.")? {
+        "title" | "displayname" => Tag::Title,
+        "artist" | "author" => Tag::Artist,
+        "album" => Tag::Album,
+        "genre" => Tag::Genre,
+        "creationdate" => Tag::Date,
+        "comment" | "description" => Tag::Comment,
+        _ => return None,
+    })
+}
+
+/// Items of a meta box's ilst, named by atom type or (QuickTime) by index into the keys box
+fn read_mp4_meta(meta: &[u8], tags: &mut MediaTags) {
+    // MP4 meta is a full box; QuickTime's starts directly with its children
+    let body = if meta.get(4..8) == Some(b"hdlr") { meta } else { meta.get(4..).unwrap_or_default() };
+
+    // Key entries are laid out like boxes: size, namespace, key name
+    let keys: Vec<String> = find_box(body, b"keys")
+        .map(|keys| {
+            child_boxes(keys.get(8..).unwrap_or_default())
+                .into_iter()
+                .map(|(_, key)| String::from_utf8_lossy(key).to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+
+    let Some(ilst) = find_box(body, b"ilst") else {
+        return;
+    };
+    for (kind, item) in child_boxes(ilst) {
+        let Some(data) = find_box(item, b"data") else {
+            continue;
+        };
+        // Type indicator (1 is UTF-8) and locale precede the value
+        let (data_type, value) = (be_u32(data, 0) & 0x00FF_FFFF, data.get(8..).unwrap_or_default());
+
+        let index = u32::from_be_bytes(kind) as usize;
+        let tag = if !keys.is_empty() && (1..=keys.len()).contains(&index) {
+            quicktime_key_tag(&keys[index - 1])
+        } else if &kind == b"gnre" && value.len() >= 2 {
+            // Numeric genre: ID3v1 index plus one
+            let number = u16::from_be_bytes([value[0], value[1]]).saturating_sub(1);
+            tags.set(Tag::Genre, &genre_name(&number.to_string()));
+            None
+        } else {
+            mp4_atom_tag(&kind)
+        };
+
+        if let (Some(tag), 1) = (tag, data_type) {
+            let text = String::from_utf8_lossy(value);
+            match tag {
+                Tag::Genre => tags.set(tag, &genre_name(&text)),
+                _ => tags.set(tag, &text),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_mp3_id3_tags_and_duration() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("episode.mp3");
+
+        let frame = |id: &[u8], body: &[u8]| [id, &(body.len() as u32).to_be_bytes(), &[0, 0], body].concat();
+        let frames = [
+            frame(b"TIT2", b"\0Episode 12: Taxes"),
+            // UTF-16 with byte order mark
+            frame(b"TPE1", &[&[1u8, 0xFF, 0xFE][..], &"Money Talk".encode_utf16().flat_map(u16::to_le_bytes).collect::<Vec<_>>()].concat()),
+            frame(b"TCON", b"\0(186)"),
+            frame(b"TYER", b"\x002023"),
+            frame(b"COMM", b"\0engiTunNORM\0 000001\0"),
+            frame(b"COMM", b"\0eng\0Deductions explained"),
+        ]
+        .concat();
+        let mut mp3 = b"ID3\x03\0\0".to_vec();
+        let size = frames.len() as u32 + 16;
+        mp3.extend([(size >> 21) as u8 & 0x7F, (size >> 14) as u8 & 0x7F, (size >> 7) as u8 & 0x7F, size as u8 & 0x7F]);
+        mp3.extend(&frames);
+        mp3.extend([0; 16]);
+
+        // MPEG-1 Layer III, 128 kbps, 44.1 kHz, stereo; Xing header with 1000 frames
+        let mut first_frame = vec![0xFF, 0xFB, 0x90, 0x00];
+        first_frame.extend([0; 32]);
+        first_frame.extend(b"Xing\0\0\0\x01");
+        first_frame.extend(1000u32.to_be_bytes());
+        first_frame.resize(417, 0);
+        mp3.extend(&first_frame);
+
+        // ID3v1 supplies the album ID3v2 lacks
+        let mut v1 = [0u8; 128];
+        v1[..3].copy_from_slice(b"TAG");
+        v1[63..73].copy_from_slice(b"Season Two");
+        v1[127] = 255;
+        mp3.extend(v1);
+        std::fs::write(&path, mp3).unwrap();
+
+        let result = extract_media(&path, 2000).unwrap();
+        assert_eq!(
+            result.content,
+            "Audio: MP3, 0:26\nTitle: Episode 12: Taxes\nArtist: Money Talk\nAlbum: Season Two\n\
+             Genre: Podcast\nRecorded: 2023\nComment: Deductions explained"
+        );
+        // 1000 frames of 1152 samples at 44.1 kHz
+        assert!((result.metadata.duration_secs.unwrap() - 26.122).abs() < 0.001);
+        assert_eq!(result.metadata.author.as_deref(), Some("Money Talk"));
+        assert_eq!(result.metadata.keywords, vec!["Podcast"]);
+    }
+
+    #[test]
+    fn test_extract_flac_vorbis_comments() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("memo.flac");
+
+        let mut streaminfo = vec![0u8; 34];
+        // 16 kHz, 1 channel, 16 bits, 960000 samples (one minute)
+        let packed: u64 = (16_000 << 44) | (15 << 36) | 960_000;
+        streaminfo[10..18].copy_from_slice(&packed.to_be_bytes());
+
+        let mut comments = Vec::new();
+        comments.extend(6u32.to_le_bytes());
+        comments.extend(b"vendor");
+        let entries = ["TITLE=Standup notes", "date=2024-03-05", "OTHER=x"];
+        comments.extend((entries.len() as u32).to_le_bytes());
+        for entry in entries {
+            comments.extend((entry.len() as u32).to_le_bytes());
+            comments.extend(entry.as_bytes());
+        }
+
+        let mut flac = b"fLaC".to_vec();
+        flac.extend([0x00, 0, 0, 34]);
+        flac.extend(&streaminfo);
+        flac.extend([0x84, 0, 0, comments.len() as u8]);
+        flac.extend(&comments);
+        std::fs::write(&path, flac).unwrap();
+
+        let result = extract_media(&path, 2000).unwrap();
+        assert_eq!(result.content, "Audio: FLAC, 1:00\nTitle: Standup notes\nRecorded: 2024-03-05");
+        assert_eq!(result.metadata.created_at.as_deref(), Some("2024-03-05"));
+    }
+
+    #[test]
+    fn test_extract_mp4_video_atoms() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("meeting.mp4");
+
+        let make_box = |kind: &[u8], body: &[u8]| [&((body.len() + 8) as u32).to_be_bytes()[..], kind, body].concat();
+
+        // Version 0: created 2024-01-02 03:04:05 UTC, timescale 1000, 95.5 seconds
+        let mut mvhd = vec![0u8; 100];
+        mvhd[4..8].copy_from_slice(&((1_704_164_645 + QUICKTIME_EPOCH_OFFSET) as u32).to_be_bytes());
+        mvhd[12..16].copy_from_slice(&1000u32.to_be_bytes());
+        mvhd[16..20].copy_from_slice(&95_500u32.to_be_bytes());
+
+        let mut tkhd = vec![0u8; 84];
+        tkhd[76..80].copy_from_slice(&(1280u32 << 16).to_be_bytes());
+        tkhd[80..84].copy_from_slice(&(720u32 << 16).to_be_bytes());
+        let hdlr = [&[0u8; 8][..], b"vide", &[0; 12]].concat();
+        let trak = make_box(b"trak", &[make_box(b"tkhd", &tkhd), make_box(b"mdia", &make_box(b"hdlr", &hdlr))].concat());
+
+        let data = |text: &str| make_box(b"data", &[&[0, 0, 0, 1, 0, 0, 0, 0][..], text.as_bytes()].concat());
+        let ilst = make_box(
+            b"ilst",
+            &[make_box(b"\xA9nam", &data("Quarterly review")), make_box(b"gnre", &make_box(b"data", &[0, 0, 0, 0, 0, 0, 0, 0, 0, 66]))]
+                .concat(),
+        );
+        let meta = make_box(b"meta", &[&[0u8; 4][..], &make_box(b"hdlr", &[0; 24]), &ilst].concat());
+        let moov = make_box(b"moov", &[make_box(b"mvhd", &mvhd), trak, make_box(b"udta", &meta)].concat());
+
+        let mp4 = [make_box(b"ftyp", b"isom\0\0\x02\0isomiso2"), make_box(b"mdat", &[0; 64]), moov].concat();
+        std::fs::write(&path, mp4).unwrap();
+
+        let result = extract_media(&path, 2000).unwrap();
+        assert_eq!(
+            result.content,
+            "Video: MP4, 1280x720, 1:36\nTitle: Quarterly review\nGenre: Cabaret\nRecorded: 2024-01-02 03:04:05"
+        );
+        assert_eq!(result.metadata.duration_secs, Some(95.5));
+        assert_eq!((result.metadata.width, result.metadata.height), (Some(1280), Some(720)));
+    }
+}
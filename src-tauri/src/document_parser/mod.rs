@@ -4,15 +4,41 @@
 //! Organized by parsing + analysis method per the architecture doc:
 //!
 //! - Group 1 (Plain Text): .txt, .md, .log
-//! - Group 2 (Office Open XML): .docx, .pptx
+//! - Group 1 (Rich Text): .rtf
+//! - Group 2 (Office Open XML): .docx, .pptx, .xlsx
+//! - Group 2 (OpenDocument): .odt, .ods, .odp
 //! - Group 3 (PDF): .pdf (text-based)
+//! - Group 4 (Legacy Office, OLE compound files): .doc, .xls, .ppt
+//! - Group 5 (Email): .eml, .mbox, .msg
+//! - Group 6 (Web pages & e-books): .html, .htm, .mhtml, .mht, .epub
+//! - Group 7 (Images, EXIF/XMP metadata): .jpg, .jpeg, .png, .tif, .tiff, .heic, .heif
+//! - Group 8 (Audio & video tags): .mp3, .flac, .m4a, .m4b, .mp4, .m4v, .mov
+//!
+//! Each format is registered as a `DocumentParser` in registry.rs; users can add
+//! external extractors (a command that prints text) for other extensions.
 
 mod text;
+mod rtf;
 mod docx;
 mod pptx;
+mod xlsx;
+mod odf;
 mod pdf;
+mod ole;
+mod email;
+mod html;
+mod epub;
+mod image;
+mod bmff;
+mod media;
+mod xmp;
+mod registry;
+mod detect;
+
+pub use detect::detect_type;
+pub use registry::{ExternalExtractor, ParserRegistry};
 
-use std::path::Path;
+use std::io::{self, Read};
 
 /// Result of parsing a document
 #[derive(Debug, Clone)]
@@ -34,6 +60,30 @@ pub struct DocumentMetadata {
     pub keywords: Vec<String>,
     pub page_count: Option<u32>,
     pub word_count: Option<u32>,
+    /// Email headers (decoded), for .eml and .msg files
+    pub email_from: Option<String>,
+    pub email_to: Option<String>,
+    pub email_date: Option<String>,
+    /// Attachment file names (email)
+    pub attachments: Vec<String>,
+    /// When a photo was taken or a page scanned ("YYYY-MM-DD HH:MM:SS", EXIF/XMP)
+    pub taken_at: Option<String>,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    /// GPS position in decimal degrees (negative south and west)
+    pub gps_latitude: Option<f64>,
+    pub gps_longitude: Option<f64>,
+    /// Pixel dimensions (images)
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    /// Album or show name (audio tags)
+    pub album: Option<String>,
+    /// Playing time in seconds (audio and video)
+    pub duration_secs: Option<f64>,
+    /// When the content was recorded or created, as the file states it
+    pub created_at: Option<String>,
+    /// Custom document properties as (name, value), Office docProps/custom.xml
+    pub custom_properties: Vec<(String, String)>,
 }
 
 /// Errors that can occur during document parsing
@@ -41,8 +91,6 @@ pub struct DocumentMetadata {
 pub enum ParseError {
     /// File not found
     NotFound(String),
-    /// Unsupported file type
-    UnsupportedType(String),
     /// Failed to read file
     ReadError(String),
     /// Failed to parse document structure
@@ -55,7 +103,6 @@ impl std::fmt::Display for ParseError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ParseError::NotFound(p) => write!(f, "File not found: {}", p),
-            ParseError::UnsupportedType(t) => write!(f, "Unsupported file type: {}", t),
             ParseError::ReadError(e) => write!(f, "Read error: {}", e),
             ParseError::ParseError(e) => write!(f, "Parse error: {}", e),
             ParseError::EncodingError(e) => write!(f, "Encoding error: {}", e),
@@ -65,55 +112,104 @@ impl std::fmt::Display for ParseError {
 
 impl std::error::Error for ParseError {}
 
-/// Main entry point for document parsing
-///
-/// Extracts text content from a document, limited to max_chars.
-/// Returns a ParsedDocument with content, metadata, and confidence score.
-pub fn extract_document_content(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
-    let extension = path
-        .extension()
-        .and_then(|e| e.to_str())
-        .map(|e| e.to_lowercase())
-        .unwrap_or_default();
-
-    match extension.as_str() {
-        // Group 1: Plain Text
-        "txt" | "md" | "markdown" | "log" | "csv" | "tsv" => {
-            text::extract_text(path, max_chars)
-        }
+/// Non-empty rows sampled from the top of each sheet (the first one is the header)
+pub(super) const SAMPLE_ROWS_PER_SHEET: usize = 20;
+
+/// Cells read from each sampled row
+pub(super) const MAX_COLUMNS: usize = 30;
 
-        // Group 2: Office Open XML
-        "docx" => docx::extract_docx(path, max_chars),
-        "pptx" => pptx::extract_pptx(path, max_chars),
+/// Confidence for sampled spreadsheet text
+/// Spreadsheets are mostly short labels and numbers, so fewer words still classify well
+pub(super) fn spreadsheet_confidence(word_count: u32) -> f64 {
+    if word_count > 60 {
+        0.90
+    } else if word_count > 15 {
+        0.80
+    } else if word_count > 3 {
+        0.65
+    } else {
+        0.40
+    }
+}
 
-        // Group 3: PDF
-        "pdf" => pdf::extract_pdf(path, max_chars),
+/// Trim lines and drop empty ones
+pub(super) fn clean_lines(text: &str) -> String {
+    text.lines()
+        .map(|l| l.trim())
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n")
+}
 
-        // Unsupported
-        _ => Err(ParseError::UnsupportedType(extension)),
+/// Truncate to max_chars (char-safe for multi-byte UTF-8)
+pub(super) fn truncate(content: String, max_chars: usize) -> String {
+    if content.chars().count() > max_chars {
+        content.chars().take(max_chars).collect::<String>()
+    } else {
+        content
     }
 }
 
-/// Check if a file type is supported for content extraction
-pub fn is_supported_type(extension: &str) -> bool {
-    matches!(
-        extension.to_lowercase().as_str(),
-        "txt" | "md" | "markdown" | "log" | "csv" | "tsv" | "docx" | "pptx" | "pdf"
-    )
+/// Confidence for extracted prose, by word count
+pub(super) fn calculate_confidence(word_count: u32) -> f64 {
+    if word_count > 100 {
+        0.95
+    } else if word_count > 20 {
+        0.85
+    } else if word_count > 5 {
+        0.70
+    } else {
+        0.50
+    }
+}
+
+/// Read up to `length` bytes, but never more than `max_length`, from a length field in the file
+pub(super) fn read_bytes<R: Read>(reader: &mut R, length: u64, max_length: u64) -> io::Result<Vec<u8>> {
+    let mut bytes = Vec::new();
+    reader.take(length.min(max_length)).read_to_end(&mut bytes)?;
+    Ok(bytes)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    fn parser_name(extension: &str) -> Option<String> {
+        ParserRegistry::builtin().for_extension(extension).map(|parser| parser.name().to_string())
+    }
+
     #[test]
     fn test_supported_types() {
+        let is_supported_type = |extension| parser_name(extension).is_some();
         assert!(is_supported_type("txt"));
         assert!(is_supported_type("TXT"));
         assert!(is_supported_type("docx"));
         assert!(is_supported_type("pdf"));
         assert!(is_supported_type("pptx"));
+        assert!(is_supported_type("xlsx"));
+        assert!(is_supported_type("ODT"));
+        assert!(is_supported_type("ods"));
+        assert!(is_supported_type("odp"));
+        assert!(is_supported_type("doc"));
+        assert!(is_supported_type("XLS"));
+        assert!(is_supported_type("ppt"));
+        assert!(is_supported_type("rtf"));
+        assert!(is_supported_type("eml"));
+        assert!(is_supported_type("mbox"));
+        assert!(is_supported_type("htm"));
+        assert!(is_supported_type("epub"));
+        assert!(is_supported_type("JPG"));
+        assert!(is_supported_type("heic"));
+        assert!(is_supported_type("mp3"));
+        assert!(is_supported_type("MOV"));
         assert!(!is_supported_type("exe"));
         assert!(!is_supported_type("unknown"));
     }
+
+    #[test]
+    fn test_parser_name() {
+        assert_eq!(parser_name("MD").as_deref(), Some("text"));
+        assert_eq!(parser_name("docx").as_deref(), Some("docx"));
+        assert_eq!(parser_name("exe"), None);
+    }
 }
@@ -0,0 +1,421 @@
+//! OpenDocument Parser (Group 2 - ODF)
+//!
+//! Handles: .odt, .ods, .odp files (LibreOffice / OpenOffice)
+//! Strategy: Unzip → read content.xml (text, sheets or slides) + meta.xml → extract text from XML
+
+use super::{
+    clean_lines, spreadsheet_confidence, truncate, DocumentMetadata, ParseError, ParsedDocument, MAX_COLUMNS,
+    SAMPLE_ROWS_PER_SHEET,
+};
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Read};
+use std::path::Path;
+use zip::ZipArchive;
+
+/// Which ODF application wrote the document
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum OdfKind {
+    Text,
+    Spreadsheet,
+    Presentation,
+}
+
+/// Extract text content from an ODT (Writer) file
+pub fn extract_odt(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    extract_odf(path, OdfKind::Text, max_chars)
+}
+
+/// Extract sheet names, header rows and sample cells from an ODS (Calc) file
+pub fn extract_ods(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    extract_odf(path, OdfKind::Spreadsheet, max_chars)
+}
+
+/// Extract slide text from an ODP (Impress) file
+pub fn extract_odp(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    extract_odf(path, OdfKind::Presentation, max_chars)
+}
+
+fn extract_odf(path: &Path, kind: OdfKind, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    let file = File::open(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ParseError::NotFound(path.to_string_lossy().to_string())
+        } else {
+            ParseError::ReadError(e.to_string())
+        }
+    })?;
+
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| ParseError::ParseError(format!("Invalid OpenDocument file (not a valid ZIP): {}", e)))?;
+
+    // Extract metadata from meta.xml
+    let mut metadata = extract_metadata(&mut archive).unwrap_or_default();
+
+    // Extract text from content.xml (streamed, so large sheets stop early)
+    let content_file = archive
+        .by_name("content.xml")
+        .map_err(|_| ParseError::ParseError("OpenDocument file missing content.xml".to_string()))?;
+    let mut reader = Reader::from_reader(BufReader::new(content_file));
+
+    let (content, part_count) = match kind {
+        OdfKind::Text => (extract_text_body(&mut reader, max_chars)?, None),
+        OdfKind::Spreadsheet => {
+            let (content, sheets) = extract_sheets(&mut reader, max_chars)?;
+            (content, Some(sheets))
+        }
+        OdfKind::Presentation => {
+            let (content, slides) = extract_slides(&mut reader, max_chars)?;
+            (content, Some(slides))
+        }
+    };
+    if part_count.is_some() {
+        metadata.page_count = part_count;
+    }
+
+    // Calculate confidence based on content quality
+    let word_count = content.split_whitespace().count() as u32;
+    let confidence = match kind {
+        OdfKind::Spreadsheet => spreadsheet_confidence(word_count),
+        _ if word_count > 100 => 0.95,
+        _ if word_count > 20 => 0.85,
+        _ if word_count > 5 => 0.70,
+        // Presentations often have less text than documents
+        OdfKind::Presentation => 0.60,
+        OdfKind::Text => 0.50,
+    };
+
+    Ok(ParsedDocument {
+        content,
+        metadata: DocumentMetadata {
+            word_count: Some(word_count),
+            ..metadata
+        },
+        extraction_confidence: confidence,
+    })
+}
+
+/// Value of an attribute by qualified name (e.g. "table:name")
+fn attribute(e: &BytesStart, name: &str) -> Option<String> {
+    e.try_get_attribute(name)
+        .ok()
+        .flatten()
+        .and_then(|attr| attr.unescape_value().ok().map(|v| v.to_string()))
+}
+
+/// Extract metadata from meta.xml
+fn extract_metadata(archive: &mut ZipArchive<File>) -> Result<DocumentMetadata, ParseError> {
+    let mut metadata = DocumentMetadata::default();
+
+    if let Ok(mut meta_file) = archive.by_name("meta.xml") {
+        let mut xml_content = String::new();
+        if meta_file.read_to_string(&mut xml_content).is_ok() {
+            let mut reader = Reader::from_str(&xml_content);
+            reader.config_mut().trim_text(true);
+
+            let mut current_tag = String::new();
+            let mut buf = Vec::new();
+
+            loop {
+                match reader.read_event_into(&mut buf) {
+                    Ok(Event::Start(e)) => {
+                        current_tag = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                    }
+                    Ok(Event::Empty(e)) if e.name().as_ref() == b"meta:document-statistic" => {
+                        metadata.page_count = attribute(&e, "meta:page-count").and_then(|v| v.parse().ok());
+                    }
+                    Ok(Event::Text(e)) => {
+                        let text = e.unescape().unwrap_or_default().to_string();
+                        match current_tag.as_str() {
+                            "dc:title" => metadata.title = Some(text),
+                            "dc:subject" => metadata.subject = Some(text),
+                            // The last editor; the original author when there is none
+                            "dc:creator" => metadata.author = Some(text),
+                            "meta:initial-creator" => {
+                                metadata.author.get_or_insert(text);
+                            }
+                            // One element per keyword
+                            "meta:keyword" => metadata.keywords.push(text),
+                            _ => {}
+                        }
+                    }
+                    Ok(Event::End(_)) => current_tag.clear(),
+                    Ok(Event::Eof) => break,
+                    Err(_) => break,
+                    _ => {}
+                }
+                buf.clear();
+            }
+        }
+    }
+
+    Ok(metadata)
+}
+
+/// Whitespace written as elements inside paragraphs (<text:s/>, <text:tab/>, <text:line-break/>)
+fn push_inline_space(e: &BytesStart, content: &mut String) {
+    match e.name().as_ref() {
+        b"text:s" => {
+            let count = attribute(e, "text:c").and_then(|c| c.parse().ok()).unwrap_or(1usize);
+            content.push_str(&" ".repeat(count.min(100)));
+        }
+        b"text:tab" => content.push('\t'),
+        b"text:line-break" => content.push('\n'),
+        _ => {}
+    }
+}
+
+/// Text of the paragraphs and headings in an ODT body
+/// Tracked deletions are skipped.
+fn extract_text_body<R: BufRead>(reader: &mut Reader<R>, max_chars: usize) -> Result<String, ParseError> {
+    let mut content = String::new();
+    let mut paragraph_depth = 0usize;
+    let mut in_tracked_changes = false;
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match e.name().as_ref() {
+                b"text:p" | b"text:h" => paragraph_depth += 1,
+                b"text:tracked-changes" => in_tracked_changes = true,
+                _ => {}
+            },
+            Ok(Event::Empty(e)) if paragraph_depth > 0 => push_inline_space(&e, &mut content),
+            Ok(Event::End(e)) => match e.name().as_ref() {
+                b"text:p" | b"text:h" => {
+                    paragraph_depth = paragraph_depth.saturating_sub(1);
+                    if paragraph_depth == 0 {
+                        content.push('\n');
+                    }
+                }
+                b"text:tracked-changes" => in_tracked_changes = false,
+                _ => {}
+            },
+            Ok(Event::Text(e)) if paragraph_depth > 0 && !in_tracked_changes => {
+                content.push_str(&e.unescape().unwrap_or_default());
+
+                if content.chars().count() >= max_chars {
+                    break;
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(ParseError::ParseError(format!("XML parse error: {}", e)));
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(truncate(clean_lines(&content), max_chars))
+}
+
+/// Header and sample rows of every sheet in an ODS body; also returns the sheet count
+fn extract_sheets<R: BufRead>(reader: &mut Reader<R>, max_chars: usize) -> Result<(String, u32), ParseError> {
+    let mut content = String::new();
+    let mut sheet_count = 0;
+    let mut sampled_rows = 0usize;
+    let mut row: Vec<String> = Vec::new();
+    let mut cell_text = String::new();
+    let mut cell_repeat = 1usize;
+    let mut in_cell = false;
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.name().as_ref() == b"table:table" => {
+                sheet_count += 1;
+                sampled_rows = 0;
+                if !content.is_empty() {
+                    content.push_str("\n\n");
+                }
+                let name = attribute(&e, "table:name").unwrap_or_else(|| format!("Sheet{}", sheet_count));
+                content.push_str(&format!("[Sheet: {}]", name));
+            }
+            Ok(Event::Start(e)) => match e.name().as_ref() {
+                b"table:table-row" => row.clear(),
+                b"table:table-cell" => {
+                    in_cell = true;
+                    cell_text.clear();
+                    // Identical neighbouring cells are stored once with a repeat count
+                    cell_repeat = attribute(&e, "table:number-columns-repeated")
+                        .and_then(|n| n.parse().ok())
+                        .unwrap_or(1);
+                }
+                _ => {}
+            },
+            Ok(Event::Empty(e)) if in_cell => push_inline_space(&e, &mut cell_text),
+            Ok(Event::End(e)) => match e.name().as_ref() {
+                b"text:p" if in_cell => cell_text.push(' '),
+                b"table:table-cell" => {
+                    in_cell = false;
+                    let value = cell_text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if !value.is_empty() {
+                        let repeat = cell_repeat.min(MAX_COLUMNS.saturating_sub(row.len()));
+                        row.extend(std::iter::repeat_n(value, repeat));
+                    }
+                }
+                b"table:table-row" if !row.is_empty() && sampled_rows < SAMPLE_ROWS_PER_SHEET => {
+                    content.push('\n');
+                    if sampled_rows == 0 {
+                        content.push_str("Header: ");
+                    }
+                    content.push_str(&row.join(" | "));
+                    sampled_rows += 1;
+                }
+                b"table:table" if content.chars().count() >= max_chars => break,
+                _ => {}
+            },
+            Ok(Event::Text(e)) if in_cell && sampled_rows < SAMPLE_ROWS_PER_SHEET => {
+                cell_text.push_str(&e.unescape().unwrap_or_default());
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(ParseError::ParseError(format!("XML parse error: {}", e)));
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok((truncate(content, max_chars), sheet_count))
+}
+
+/// Text of each slide in an ODP body (speaker notes skipped); also returns the slide count
+fn extract_slides<R: BufRead>(reader: &mut Reader<R>, max_chars: usize) -> Result<(String, u32), ParseError> {
+    let mut content = String::new();
+    let mut slide_count = 0;
+    let mut slide_text = String::new();
+    let mut paragraph_depth = 0usize;
+    let mut in_notes = false;
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match e.name().as_ref() {
+                b"draw:page" => {
+                    slide_count += 1;
+                    slide_text.clear();
+                }
+                b"presentation:notes" => in_notes = true,
+                b"text:p" | b"text:h" => paragraph_depth += 1,
+                _ => {}
+            },
+            Ok(Event::Empty(e)) if e.name().as_ref() == b"draw:page" => slide_count += 1,
+            Ok(Event::Empty(e)) if paragraph_depth > 0 && !in_notes => push_inline_space(&e, &mut slide_text),
+            Ok(Event::End(e)) => match e.name().as_ref() {
+                b"presentation:notes" => in_notes = false,
+                b"text:p" | b"text:h" => {
+                    paragraph_depth = paragraph_depth.saturating_sub(1);
+                    slide_text.push('\n');
+                }
+                b"draw:page" => {
+                    let text = clean_lines(&slide_text);
+                    if !text.is_empty() && content.chars().count() < max_chars {
+                        if !content.is_empty() {
+                            content.push_str("\n\n");
+                        }
+                        content.push_str(&format!("[Slide {}]\n{}", slide_count, text));
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Text(e)) if paragraph_depth > 0 && !in_notes => {
+                slide_text.push_str(&e.unescape().unwrap_or_default());
+            }
+            Ok(Event::Eof) => break,
+            Err(e) => {
+                return Err(ParseError::ParseError(format!("XML parse error: {}", e)));
+            }
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok((truncate(content, max_chars), slide_count))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn write_odf(path: &Path, content_xml: &str, meta_xml: &str) {
+        let mut zip = ZipWriter::new(File::create(path).unwrap());
+        for (name, xml) in [("content.xml", content_xml), ("meta.xml", meta_xml)] {
+            zip.start_file(name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(xml.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_extract_odt_text_and_meta() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("letter.odt");
+        write_odf(
+            &path,
+            r#"<office:document-content><office:body><office:text>
+                <text:tracked-changes><text:changed-region><text:deletion><text:p>Removed</text:p></text:deletion></text:changed-region></text:tracked-changes>
+                <text:h>Lease renewal</text:h>
+                <text:p>Dear<text:s/>tenant,<text:s text:c="2"/>rent is <text:span>due</text:span> monthly.</text:p>
+            </office:text></office:body></office:document-content>"#,
+            r#"<office:document-meta><office:meta>
+                <meta:initial-creator>Sam</meta:initial-creator><dc:title>Lease</dc:title>
+                <meta:keyword>lease</meta:keyword><meta:keyword>rent</meta:keyword>
+                <meta:document-statistic meta:page-count="3" meta:word-count="9"/>
+            </office:meta></office:document-meta>"#,
+        );
+
+        let result = extract_odt(&path, 2000).unwrap();
+        assert_eq!(result.content, "Lease renewal\nDear tenant,  rent is due monthly.");
+        assert_eq!(result.metadata.title.as_deref(), Some("Lease"));
+        assert_eq!(result.metadata.author.as_deref(), Some("Sam"));
+        assert_eq!(result.metadata.keywords, vec!["lease", "rent"]);
+        assert_eq!(result.metadata.page_count, Some(3));
+    }
+
+    #[test]
+    fn test_extract_ods_sheets() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("budget.ods");
+        write_odf(
+            &path,
+            r#"<office:document-content><office:body><office:spreadsheet>
+                <table:table table:name="Expenses">
+                    <table:table-row><table:table-cell><text:p>Item</text:p></table:table-cell><table:table-cell><text:p>Cost</text:p></table:table-cell></table:table-row>
+                    <table:table-row table:number-rows-repeated="3"><table:table-cell table:number-columns-repeated="2"/></table:table-row>
+                    <table:table-row><table:table-cell><text:p>Rent</text:p></table:table-cell><table:table-cell office:value="950"><text:p>950</text:p></table:table-cell></table:table-row>
+                </table:table>
+                <table:table table:name="Summary"/>
+            </office:spreadsheet></office:body></office:document-content>"#,
+            "<office:document-meta/>",
+        );
+
+        let result = extract_ods(&path, 2000).unwrap();
+        assert_eq!(result.content, "[Sheet: Expenses]\nHeader: Item | Cost\nRent | 950\n\n[Sheet: Summary]");
+        assert_eq!(result.metadata.page_count, Some(2));
+    }
+
+    #[test]
+    fn test_extract_odp_slides_without_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("pitch.odp");
+        write_odf(
+            &path,
+            r#"<office:document-content><office:body><office:presentation>
+                <draw:page draw:name="page1"><draw:frame><draw:text-box><text:p>Quarterly results</text:p></draw:text-box></draw:frame>
+                    <presentation:notes><draw:frame><draw:text-box><text:p>Speaker only</text:p></draw:text-box></draw:frame></presentation:notes></draw:page>
+                <draw:page draw:name="page2"/>
+                <draw:page draw:name="page3"><draw:frame><draw:text-box><text:p>Revenue up</text:p><text:p>Costs down</text:p></draw:text-box></draw:frame></draw:page>
+            </office:presentation></office:body></office:document-content>"#,
+            "<office:document-meta/>",
+        );
+
+        let result = extract_odp(&path, 2000).unwrap();
+        assert_eq!(result.content, "[Slide 1]\nQuarterly results\n\n[Slide 3]\nRevenue up\nCosts down");
+        assert_eq!(result.metadata.page_count, Some(3));
+    }
+}
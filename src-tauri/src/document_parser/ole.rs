@@ -0,0 +1,1026 @@
+//! Legacy Office Parser (Group 4 - OLE compound files)
+//!
+//! Handles: .doc, .xls, .ppt files (Office 97-2003, and Word 6/95 text)
+//! Strategy: Open the OLE/CFB container → read the binary text streams
+//! (WordDocument + piece table, Workbook BIFF records, PowerPoint Document atoms)
+//! → read title/author from the SummaryInformation property set
+
+use super::{
+    clean_lines, spreadsheet_confidence, truncate, DocumentMetadata, ParseError, ParsedDocument, MAX_COLUMNS,
+    SAMPLE_ROWS_PER_SHEET,
+};
+use cfb::CompoundFile;
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Shared strings loaded; cells referring past this are skipped
+const MAX_SHARED_STRINGS: usize = 100_000;
+
+/// Characters 0x80-0x9F of Windows-1252 (the rest match Latin-1)
+const CP1252_HIGH: [char; 32] = [
+    '€', '\u{81}', '‚', 'ƒ', '„', '…', '†', '‡', 'ˆ', '‰', 'Š', '‹', 'Œ', '\u{8d}', 'Ž', '\u{8f}',
+    '\u{90}', '‘', '’', '“', '”', '•', '–', '—', '˜', '™', 'š', '›', 'œ', '\u{9d}', 'ž', 'Ÿ',
+];
+
+/// Extract text content from a Word 97-2003 (.doc) file
+pub fn extract_doc(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    let mut compound = open_compound(path)?;
+    let mut metadata = read_summary_information(&mut compound);
+
+    let word_document = read_stream(&mut compound, "WordDocument")
+        .ok_or_else(|| ParseError::ParseError("DOC file missing WordDocument stream".to_string()))?;
+    let raw_text = read_word_text(&mut compound, &word_document, max_chars)?;
+    let content = truncate(clean_word_text(&raw_text), max_chars);
+
+    // Calculate confidence based on content quality
+    let word_count = content.split_whitespace().count() as u32;
+    let confidence = if word_count > 100 {
+        0.90
+    } else if word_count > 20 {
+        0.80
+    } else if word_count > 5 {
+        0.65
+    } else {
+        0.45
+    };
+
+    metadata.word_count = Some(word_count);
+    Ok(ParsedDocument {
+        content,
+        metadata,
+        extraction_confidence: confidence,
+    })
+}
+
+/// Extract sheet names, header rows and sample cells from an Excel 97-2003 (.xls) file
+pub fn extract_xls(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    let mut compound = open_compound(path)?;
+    let mut metadata = read_summary_information(&mut compound);
+
+    // Excel 5/95 named the stream "Book"
+    let workbook = read_stream(&mut compound, "Workbook")
+        .or_else(|| read_stream(&mut compound, "Book"))
+        .ok_or_else(|| ParseError::ParseError("XLS file missing Workbook stream".to_string()))?;
+    let sheets = read_workbook_sheets(&workbook)?;
+
+    let mut content = String::new();
+    for (name, rows) in &sheets {
+        if content.chars().count() >= max_chars {
+            break;
+        }
+        if !content.is_empty() {
+            content.push_str("\n\n");
+        }
+        content.push_str(&format!("[Sheet: {}]", name));
+        for (i, row) in rows.iter().enumerate() {
+            content.push('\n');
+            if i == 0 {
+                content.push_str("Header: ");
+            }
+            content.push_str(&row.join(" | "));
+        }
+    }
+    let content = truncate(content, max_chars);
+
+    // A notch below .xlsx/.ods: cells are decoded from binary BIFF records
+    let word_count = content.split_whitespace().count() as u32;
+    let confidence = spreadsheet_confidence(word_count) - 0.05;
+
+    metadata.word_count = Some(word_count);
+    metadata.page_count = Some(sheets.len() as u32);
+    Ok(ParsedDocument {
+        content,
+        metadata,
+        extraction_confidence: confidence,
+    })
+}
+
+/// Extract slide text from a PowerPoint 97-2003 (.ppt) file
+pub fn extract_ppt(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    let mut compound = open_compound(path)?;
+    let mut metadata = read_summary_information(&mut compound);
+
+    let document = read_stream(&mut compound, "PowerPoint Document")
+        .ok_or_else(|| ParseError::ParseError("PPT file missing PowerPoint Document stream".to_string()))?;
+    let slides = read_slide_texts(&document);
+
+    let mut content = String::new();
+    for (i, text) in slides.iter().enumerate() {
+        if content.chars().count() >= max_chars {
+            break;
+        }
+        let text = clean_lines(text);
+        if text.is_empty() {
+            continue;
+        }
+        if !content.is_empty() {
+            content.push_str("\n\n");
+        }
+        content.push_str(&format!("[Slide {}]\n{}", i + 1, text));
+    }
+    let content = truncate(content, max_chars);
+
+    // Calculate confidence based on content quality
+    let word_count = content.split_whitespace().count() as u32;
+    let confidence = if word_count > 100 {
+        0.90
+    } else if word_count > 20 {
+        0.80
+    } else if word_count > 5 {
+        0.65
+    } else {
+        // Presentations often have less text than documents
+        0.55
+    };
+
+    metadata.word_count = Some(word_count);
+    metadata.page_count = Some(slides.len() as u32);
+    Ok(ParsedDocument {
+        content,
+        metadata,
+        extraction_confidence: confidence,
+    })
+}
+
+pub(super) fn open_compound(path: &Path) -> Result<CompoundFile<File>, ParseError> {
+    cfb::open(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ParseError::NotFound(path.to_string_lossy().to_string())
+        } else {
+            ParseError::ParseError(format!("Invalid legacy Office file (not an OLE compound file): {}", e))
+        }
+    })
+}
+
+pub(super) fn read_stream(compound: &mut CompoundFile<File>, name: &str) -> Option<Vec<u8>> {
+    let mut stream = compound.open_stream(name).ok()?;
+    let mut data = Vec::new();
+    stream.read_to_end(&mut data).ok()?;
+    Some(data)
+}
+
+fn u16_at(data: &[u8], offset: usize) -> Option<u16> {
+    Some(u16::from_le_bytes(data.get(offset..offset + 2)?.try_into().ok()?))
+}
+
+fn u32_at(data: &[u8], offset: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(offset..offset + 4)?.try_into().ok()?))
+}
+
+/// Decode Windows-1252 text (also used for RTF)
+pub(super) fn decode_cp1252(bytes: &[u8]) -> String {
+    bytes
+        .iter()
+        .map(|&b| match b {
+            0x80..=0x9F => CP1252_HIGH[(b - 0x80) as usize],
+            _ => b as char,
+        })
+        .collect()
+}
+
+pub(super) fn decode_utf16(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// Read title, author, subject, keywords and page count from the
+/// "\u{5}SummaryInformation" property set (missing or unreadable → defaults)
+fn read_summary_information(compound: &mut CompoundFile<File>) -> DocumentMetadata {
+    let mut metadata = DocumentMetadata::default();
+    let Some(data) = read_stream(compound, "\u{5}SummaryInformation") else {
+        return metadata;
+    };
+
+    // Header (28 bytes) + FMTID (16 bytes), then the offset of the first property set
+    let Some(set) = u32_at(&data, 44).map(|o| o as usize) else {
+        return metadata;
+    };
+    let count = u32_at(&data, set + 4).unwrap_or(0).min(256) as usize;
+    let properties: Vec<(u32, usize)> = (0..count)
+        .filter_map(|i| {
+            let id = u32_at(&data, set + 8 + i * 8)?;
+            let offset = u32_at(&data, set + 12 + i * 8)? as usize;
+            Some((id, set + offset))
+        })
+        .collect();
+
+    // PID 1: code page of 8-bit strings (1200 = UTF-16)
+    let utf16_strings = properties
+        .iter()
+        .find(|(id, _)| *id == 1)
+        .and_then(|(_, at)| u16_at(&data, at + 4))
+        == Some(1200);
+
+    let string_at = |at: usize| -> Option<String> {
+        let value_type = u32_at(&data, at)?;
+        let size = u32_at(&data, at + 4)? as usize;
+        let text = match value_type {
+            // VT_LPSTR: byte count, code page string
+            0x1E => {
+                let bytes = data.get(at + 8..at + 8 + size)?;
+                if utf16_strings {
+                    decode_utf16(bytes)
+                } else {
+                    String::from_utf8(bytes.to_vec()).unwrap_or_else(|_| decode_cp1252(bytes))
+                }
+            }
+            // VT_LPWSTR: character count, UTF-16
+            0x1F => decode_utf16(data.get(at + 8..at + 8 + size * 2)?),
+            _ => return None,
+        };
+        let text = text.trim_end_matches('\0').trim().to_string();
+        (!text.is_empty()).then_some(text)
+    };
+
+    for &(id, at) in &properties {
+        match id {
+            2 => metadata.title = string_at(at),
+            3 => metadata.subject = string_at(at),
+            4 => metadata.author = string_at(at),
+            5 => {
+                metadata.keywords = string_at(at)
+                    .map(|k| k.split(',').map(|s| s.trim().to_string()).collect())
+                    .unwrap_or_default();
+            }
+            // PID 14: page count (VT_I4)
+            14 if u32_at(&data, at) == Some(0x03) => {
+                metadata.page_count = u32_at(&data, at + 4).filter(|&n| n > 0);
+            }
+            _ => {}
+        }
+    }
+
+    metadata
+}
+
+// --- Word ---
+
+/// Main document text of a WordDocument stream, roughly bounded by `max_chars`
+/// Word 97+ text is assembled from the piece table (CLX) in the 0Table/1Table
+/// stream; Word 6/95 files keep it contiguous between fcMin and fcMac.
+fn read_word_text(
+    compound: &mut CompoundFile<File>,
+    word_document: &[u8],
+    max_chars: usize,
+) -> Result<String, ParseError> {
+    if u16_at(word_document, 0) != Some(0xA5EC) {
+        return Err(ParseError::ParseError("Not a Word binary document".to_string()));
+    }
+    let n_fib = u16_at(word_document, 2).unwrap_or(0);
+    let flags = u16_at(word_document, 0x0A).unwrap_or(0);
+    if flags & 0x0100 != 0 {
+        return Err(ParseError::ParseError("DOC file is encrypted".to_string()));
+    }
+    // Field codes and control characters are dropped, so read some slack
+    let budget = max_chars.saturating_mul(4);
+
+    if n_fib < 0x00C1 {
+        let fc_min = u32_at(word_document, 0x18).unwrap_or(0) as usize;
+        let fc_mac = u32_at(word_document, 0x1C).unwrap_or(0) as usize;
+        let end = fc_mac.min(word_document.len()).min(fc_min.saturating_add(budget));
+        return Ok(decode_cp1252(word_document.get(fc_min..end).unwrap_or_default()));
+    }
+
+    // FibBase (32 bytes), then the variable-length FibRgW, FibRgLw and FibRgFcLcb blocks
+    let csw = u16_at(word_document, 32).unwrap_or(0) as usize;
+    let rg_lw = 32 + 2 + csw * 2 + 2;
+    let cslw = u16_at(word_document, rg_lw - 2).unwrap_or(0) as usize;
+    let ccp_text = u32_at(word_document, rg_lw + 12).unwrap_or(0) as usize;
+    let rg_fc_lcb = rg_lw + cslw * 4 + 2;
+    let fc_clx = u32_at(word_document, rg_fc_lcb + 33 * 8).unwrap_or(0) as usize;
+    let lcb_clx = u32_at(word_document, rg_fc_lcb + 33 * 8 + 4).unwrap_or(0) as usize;
+
+    let table_name = if flags & 0x0200 != 0 { "1Table" } else { "0Table" };
+    let table = read_stream(compound, table_name)
+        .ok_or_else(|| ParseError::ParseError(format!("DOC file missing {} stream", table_name)))?;
+    let clx = table
+        .get(fc_clx..fc_clx.saturating_add(lcb_clx))
+        .ok_or_else(|| ParseError::ParseError("DOC piece table out of range".to_string()))?;
+
+    let pieces = read_piece_table(clx).ok_or_else(|| ParseError::ParseError("Invalid DOC piece table".to_string()))?;
+    let limit = ccp_text.min(budget);
+    let mut text = String::new();
+    for (cp_start, cp_end, fc, compressed) in pieces {
+        if cp_start >= limit {
+            break;
+        }
+        let chars = cp_end.min(limit).saturating_sub(cp_start);
+        if compressed {
+            if let Some(bytes) = word_document.get(fc..fc + chars) {
+                text.push_str(&decode_cp1252(bytes));
+            }
+        } else if let Some(bytes) = word_document.get(fc..fc + chars * 2) {
+            text.push_str(&decode_utf16(bytes));
+        }
+    }
+    Ok(text)
+}
+
+/// Pieces of the main text as (first CP, end CP, byte offset, 8-bit) from a CLX
+fn read_piece_table(clx: &[u8]) -> Option<Vec<(usize, usize, usize, bool)>> {
+    // Skip Prc entries (formatting), up to the Pcdt (0x02)
+    let mut at = 0;
+    while *clx.get(at)? == 0x01 {
+        at += 3 + u16_at(clx, at + 1)? as usize;
+    }
+    if *clx.get(at)? != 0x02 {
+        return None;
+    }
+    let lcb = u32_at(clx, at + 1)? as usize;
+    let plc = clx.get(at + 5..at + 5 + lcb)?;
+
+    // (n + 1) CPs followed by n 8-byte piece descriptors
+    let n = plc.len().checked_sub(4)? / 12;
+    let pieces = (0..n)
+        .filter_map(|i| {
+            let cp_start = u32_at(plc, i * 4)? as usize;
+            let cp_end = u32_at(plc, (i + 1) * 4)? as usize;
+            let fc_compressed = u32_at(plc, (n + 1) * 4 + i * 8 + 2)?;
+            let compressed = fc_compressed & 0x4000_0000 != 0;
+            let fc = (fc_compressed & 0x3FFF_FFFF) as usize;
+            Some((cp_start, cp_end, if compressed { fc / 2 } else { fc }, compressed))
+        })
+        .collect();
+    Some(pieces)
+}
+
+/// Turn Word's control characters into plain text
+/// Field instructions (between 0x13 and 0x14) are dropped, field results kept.
+fn clean_word_text(raw: &str) -> String {
+    let mut text = String::with_capacity(raw.len());
+    // One entry per open field: true while in its instruction part
+    let mut fields: Vec<bool> = Vec::new();
+
+    for c in raw.chars() {
+        match c {
+            '\u{13}' => fields.push(true),
+            '\u{14}' => {
+                if let Some(in_instructions) = fields.last_mut() {
+                    *in_instructions = false;
+                }
+            }
+            '\u{15}' => {
+                fields.pop();
+            }
+            _ if fields.iter().any(|&in_instructions| in_instructions) => {}
+            // Paragraph, line, page and section breaks
+            '\r' | '\u{0b}' | '\u{0c}' => text.push('\n'),
+            // Table cell / row end
+            '\u{07}' => text.push('\t'),
+            '\u{1e}' => text.push('-'),
+            '\t' => text.push('\t'),
+            // Soft hyphens, picture and object anchors, other control characters
+            c if (c as u32) < 0x20 => {}
+            c => text.push(c),
+        }
+    }
+
+    clean_lines(&text)
+}
+
+// --- Excel ---
+
+/// Records of a BIFF stream as (stream offset, type, data)
+fn biff_records(data: &[u8]) -> Vec<(usize, u16, &[u8])> {
+    let mut records = Vec::new();
+    let mut at = 0;
+    while let (Some(record_type), Some(size)) = (u16_at(data, at), u16_at(data, at + 2)) {
+        let Some(body) = data.get(at + 4..at + 4 + size as usize) else {
+            break;
+        };
+        records.push((at, record_type, body));
+        at += 4 + size as usize;
+    }
+    records
+}
+
+/// Cursor over a record and its CONTINUE records
+/// Character data split across records restarts with a fresh 8-bit/16-bit flag.
+struct ContinuedReader<'a> {
+    segments: Vec<&'a [u8]>,
+    segment: usize,
+    at: usize,
+}
+
+impl<'a> ContinuedReader<'a> {
+    fn remaining_in_segment(&self) -> usize {
+        self.segments.get(self.segment).map_or(0, |s| s.len().saturating_sub(self.at))
+    }
+
+    fn next_segment(&mut self) -> Option<()> {
+        self.segment += 1;
+        self.at = 0;
+        self.segments.get(self.segment).map(|_| ())
+    }
+
+    /// Bytes left in this segment and the ones after it
+    fn remaining(&self) -> usize {
+        let later: usize = self.segments.iter().skip(self.segment + 1).map(|s| s.len()).sum();
+        self.remaining_in_segment() + later
+    }
+
+    fn bytes(&mut self, mut count: usize) -> Option<Vec<u8>> {
+        // Counts come from the file, so never allocate more than the stream still holds
+        if count > self.remaining_in_segment() && count > self.remaining() {
+            return None;
+        }
+        let mut out = Vec::with_capacity(count);
+        while count > 0 {
+            if self.remaining_in_segment() == 0 {
+                self.next_segment()?;
+                continue;
+            }
+            let take = count.min(self.remaining_in_segment());
+            out.extend_from_slice(&self.segments[self.segment][self.at..self.at + take]);
+            self.at += take;
+            count -= take;
+        }
+        Some(out)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        self.bytes(1).map(|b| b[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        self.bytes(2).map(|b| u16::from_le_bytes([b[0], b[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        self.bytes(4).map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    }
+
+    fn chars(&mut self, mut count: usize, mut high_byte: bool) -> Option<String> {
+        let mut text = String::new();
+        while count > 0 {
+            if self.remaining_in_segment() == 0 {
+                self.next_segment()?;
+                high_byte = self.u8()? & 0x01 != 0;
+                continue;
+            }
+            let width = if high_byte { 2 } else { 1 };
+            let take = count.min(self.remaining_in_segment() / width).max(1);
+            let bytes = self.bytes(take * width)?;
+            if high_byte {
+                text.push_str(&decode_utf16(&bytes));
+            } else {
+                text.extend(bytes.iter().map(|&b| b as char));
+            }
+            count -= take;
+        }
+        Some(text)
+    }
+
+    /// An XLUnicodeRichExtendedString (SST entry)
+    fn rich_string(&mut self) -> Option<String> {
+        let count = self.u16()? as usize;
+        let flags = self.u8()?;
+        let runs = if flags & 0x08 != 0 { self.u16()? as usize } else { 0 };
+        let ext = if flags & 0x04 != 0 { self.u32()? as usize } else { 0 };
+        let text = self.chars(count, flags & 0x01 != 0)?;
+        self.bytes(runs * 4 + ext)?;
+        Some(text)
+    }
+}
+
+/// A BIFF8 XLUnicodeString (16-bit count) or BIFF5 byte string at `at`
+fn biff_string(data: &[u8], at: usize, biff8: bool) -> Option<String> {
+    let count = u16_at(data, at)? as usize;
+    if !biff8 {
+        return Some(decode_cp1252(data.get(at + 2..at + 2 + count)?));
+    }
+    let flags = *data.get(at + 2)?;
+    let start = at + 3;
+    Some(if flags & 0x01 != 0 {
+        decode_utf16(data.get(start..start + count * 2)?)
+    } else {
+        data.get(start..start + count)?.iter().map(|&b| b as char).collect()
+    })
+}
+
+fn format_number(value: f64) -> String {
+    if value.fract() == 0.0 && value.abs() < 1e15 {
+        format!("{}", value as i64)
+    } else {
+        format!("{}", value)
+    }
+}
+
+/// Decode an RK number (compressed float or integer, optionally ×100)
+fn decode_rk(rk: u32) -> f64 {
+    let value = if rk & 0x02 != 0 {
+        ((rk as i32) >> 2) as f64
+    } else {
+        f64::from_bits(((rk & 0xFFFF_FFFC) as u64) << 32)
+    };
+    if rk & 0x01 != 0 {
+        value / 100.0
+    } else {
+        value
+    }
+}
+
+/// Sampled cells of a worksheet: row → column → text
+type SheetCells = BTreeMap<u16, BTreeMap<u16, String>>;
+
+/// A worksheet's name and sampled rows
+type SheetSample = (String, Vec<Vec<String>>);
+
+/// Worksheets in workbook order
+fn read_workbook_sheets(workbook: &[u8]) -> Result<Vec<SheetSample>, ParseError> {
+    let records = biff_records(workbook);
+    let mut biff8 = true;
+    let mut sheet_names: Vec<(usize, String)> = Vec::new();
+    let mut shared_strings: Vec<String> = Vec::new();
+    let mut sheets = Vec::new();
+    // Name and cells of the worksheet substream being read
+    let mut current: Option<(String, SheetCells)> = None;
+    let mut pending_formula: Option<(u16, u16)> = None;
+
+    for (i, &(offset, record_type, data)) in records.iter().enumerate() {
+        let mut cell: Option<(u16, u16, String)> = None;
+        match record_type {
+            // BOF: the first one is the workbook globals, then one per sheet
+            0x0809 | 0x0409 | 0x0209 => {
+                if offset == 0 {
+                    biff8 = u16_at(data, 0) == Some(0x0600);
+                } else if u16_at(data, 2) == Some(0x0010) {
+                    let name = sheet_names
+                        .iter()
+                        .find(|(position, _)| *position == offset)
+                        .map(|(_, name)| name.clone())
+                        .unwrap_or_else(|| format!("Sheet{}", sheets.len() + 1));
+                    current = Some((name, BTreeMap::new()));
+                }
+            }
+            // EOF
+            0x000A => {
+                if let Some((name, rows)) = current.take() {
+                    let rows = rows
+                        .into_values()
+                        .map(|cells| cells.into_values().take(MAX_COLUMNS).collect())
+                        .collect();
+                    sheets.push((name, rows));
+                }
+            }
+            // FILEPASS
+            0x002F => return Err(ParseError::ParseError("XLS file is encrypted".to_string())),
+            // BOUNDSHEET: stream position, state, type, name
+            0x0085 => {
+                let position = u32_at(data, 0).unwrap_or(0) as usize;
+                let name = if biff8 {
+                    let count = *data.get(6).unwrap_or(&0) as usize;
+                    let high_byte = data.get(7).is_some_and(|f| f & 0x01 != 0);
+                    if high_byte {
+                        decode_utf16(data.get(8..8 + count * 2).unwrap_or_default())
+                    } else {
+                        data.get(8..8 + count).unwrap_or_default().iter().map(|&b| b as char).collect()
+                    }
+                } else {
+                    let count = *data.get(6).unwrap_or(&0) as usize;
+                    decode_cp1252(data.get(7..7 + count).unwrap_or_default())
+                };
+                sheet_names.push((position, name));
+            }
+            // SST, continued in the CONTINUE records that follow
+            0x00FC => {
+                let mut segments = vec![data];
+                segments.extend(records[i + 1..].iter().take_while(|r| r.1 == 0x003C).map(|r| r.2));
+                let mut reader = ContinuedReader { segments, segment: 0, at: 8 };
+                let unique = u32_at(data, 4).unwrap_or(0) as usize;
+                for _ in 0..unique.min(MAX_SHARED_STRINGS) {
+                    match reader.rich_string() {
+                        Some(text) => shared_strings.push(text),
+                        None => break,
+                    }
+                }
+            }
+            // LABELSST
+            0x00FD => {
+                let index = u32_at(data, 6).unwrap_or(u32::MAX) as usize;
+                if let Some(text) = shared_strings.get(index) {
+                    cell = Some((u16_at(data, 0).unwrap_or(0), u16_at(data, 2).unwrap_or(0), text.clone()));
+                }
+            }
+            // LABEL, RSTRING
+            0x0204 | 0x00D6 => {
+                if let Some(text) = biff_string(data, 6, biff8) {
+                    cell = Some((u16_at(data, 0).unwrap_or(0), u16_at(data, 2).unwrap_or(0), text));
+                }
+            }
+            // NUMBER
+            0x0203 => {
+                if let Some(bytes) = data.get(6..14) {
+                    let value = f64::from_le_bytes(bytes.try_into().unwrap_or_default());
+                    cell = Some((u16_at(data, 0).unwrap_or(0), u16_at(data, 2).unwrap_or(0), format_number(value)));
+                }
+            }
+            // RK
+            0x027E => {
+                if let Some(rk) = u32_at(data, 6) {
+                    cell = Some((u16_at(data, 0).unwrap_or(0), u16_at(data, 2).unwrap_or(0), format_number(decode_rk(rk))));
+                }
+            }
+            // MULRK: row, first column, (format, RK) pairs, last column
+            0x00BD => {
+                if let (Some(row), Some(first), Some((_, rows))) = (u16_at(data, 0), u16_at(data, 2), current.as_mut()) {
+                    let count = data.len().saturating_sub(6) / 6;
+                    for k in 0..count {
+                        let Some(column) = first.checked_add(k as u16) else {
+                            break;
+                        };
+                        if let Some(rk) = u32_at(data, 4 + k * 6 + 2) {
+                            insert_cell(rows, row, column, format_number(decode_rk(rk)));
+                        }
+                    }
+                }
+            }
+            // FORMULA: cached number, or a string result in the STRING record that follows
+            0x0006 => {
+                let (row, column) = (u16_at(data, 0).unwrap_or(0), u16_at(data, 2).unwrap_or(0));
+                if u16_at(data, 12) == Some(0xFFFF) {
+                    if data.get(6) == Some(&0x00) {
+                        pending_formula = Some((row, column));
+                    }
+                } else if let Some(bytes) = data.get(6..14) {
+                    let value = f64::from_le_bytes(bytes.try_into().unwrap_or_default());
+                    cell = Some((row, column, format_number(value)));
+                }
+            }
+            // STRING (formula result)
+            0x0207 => {
+                if let (Some((row, column)), Some(text)) = (pending_formula.take(), biff_string(data, 0, biff8)) {
+                    cell = Some((row, column, text));
+                }
+            }
+            _ => {}
+        }
+
+        if let (Some((row, column, text)), Some((_, rows))) = (cell, current.as_mut()) {
+            insert_cell(rows, row, column, text);
+        }
+    }
+
+    Ok(sheets)
+}
+
+/// Record a cell if its row is among the first sampled rows
+fn insert_cell(rows: &mut SheetCells, row: u16, column: u16, text: String) {
+    let text = text.trim();
+    if text.is_empty() || (!rows.contains_key(&row) && rows.len() >= SAMPLE_ROWS_PER_SHEET) {
+        return;
+    }
+    rows.entry(row).or_default().insert(column, text.to_string());
+}
+
+// --- PowerPoint ---
+
+const PPT_DOCUMENT: u16 = 0x03E8;
+const PPT_SLIDE: u16 = 0x03EE;
+const PPT_SLIDE_PERSIST_ATOM: u16 = 0x03F3;
+const PPT_SLIDE_LIST_WITH_TEXT: u16 = 0x0FF0;
+const PPT_TEXT_CHARS_ATOM: u16 = 0x0FA0;
+const PPT_TEXT_BYTES_ATOM: u16 = 0x0FA8;
+
+/// Container nesting followed before the rest of a branch is skipped
+/// (real files nest a handful of levels; crafted ones could overflow the stack)
+const MAX_PPT_RECORD_DEPTH: usize = 32;
+
+/// Where text atoms found while walking the record tree belong
+#[derive(Default)]
+struct SlideTexts {
+    /// From the slide list of the latest document container (one entry per SlidePersistAtom)
+    listed: Vec<String>,
+    /// From slide containers, used when the document has no slide list text
+    containers: Vec<String>,
+}
+
+/// Text of each slide, in order
+/// Masters, notes and handouts are skipped.
+fn read_slide_texts(document: &[u8]) -> Vec<String> {
+    let mut texts = SlideTexts::default();
+    walk_ppt_records(document, 0, document.len(), None, 0, &mut texts);
+    if texts.listed.iter().any(|t| !t.trim().is_empty()) {
+        texts.listed
+    } else {
+        texts.containers
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum PptScope {
+    SlideList,
+    SlideContainer,
+}
+
+fn walk_ppt_records(
+    data: &[u8],
+    start: usize,
+    end: usize,
+    scope: Option<PptScope>,
+    depth: usize,
+    texts: &mut SlideTexts,
+) {
+    if depth > MAX_PPT_RECORD_DEPTH {
+        return;
+    }
+    let mut at = start;
+    while at + 8 <= end {
+        let (Some(ver_instance), Some(record_type), Some(length)) =
+            (u16_at(data, at), u16_at(data, at + 2), u32_at(data, at + 4))
+        else {
+            break;
+        };
+        let body = at + 8;
+        let body_end = body.saturating_add(length as usize).min(end);
+        let is_container = ver_instance & 0x000F == 0x000F;
+        let instance = ver_instance >> 4;
+
+        match record_type {
+            // Incremental saves append a newer document container; its slide list wins
+            PPT_DOCUMENT if scope.is_none() => {
+                texts.listed.clear();
+                walk_ppt_records(data, body, body_end, None, depth + 1, texts);
+            }
+            // Instance 0 lists slides (1 = masters, 2 = notes)
+            PPT_SLIDE_LIST_WITH_TEXT if instance == 0 => {
+                walk_ppt_records(data, body, body_end, Some(PptScope::SlideList), depth + 1, texts);
+            }
+            PPT_SLIDE if scope.is_none() => {
+                texts.containers.push(String::new());
+                walk_ppt_records(data, body, body_end, Some(PptScope::SlideContainer), depth + 1, texts);
+            }
+            PPT_SLIDE_PERSIST_ATOM if scope == Some(PptScope::SlideList) => texts.listed.push(String::new()),
+            PPT_TEXT_CHARS_ATOM | PPT_TEXT_BYTES_ATOM if scope.is_some() => {
+                let bytes = data.get(body..body_end).unwrap_or_default();
+                let text = if record_type == PPT_TEXT_CHARS_ATOM {
+                    decode_utf16(bytes)
+                } else {
+                    decode_cp1252(bytes)
+                };
+                let slides = match scope {
+                    Some(PptScope::SlideList) => &mut texts.listed,
+                    _ => &mut texts.containers,
+                };
+                if let Some(slide) = slides.last_mut() {
+                    // Paragraphs end in \r, soft line breaks are \v
+                    slide.push_str(&text.replace(['\r', '\u{0b}'], "\n"));
+                    slide.push('\n');
+                }
+            }
+            // Other containers (drawings, text boxes) may hold slide text; masters, notes
+            // and handouts are never entered from the top level
+            _ if is_container && scope.is_some() => walk_ppt_records(data, body, body_end, scope, depth + 1, texts),
+            _ => {}
+        }
+
+        at = body.saturating_add(length as usize);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    fn write_compound(path: &Path, streams: &[(&str, Vec<u8>)]) {
+        let mut compound = cfb::create(path).unwrap();
+        for (name, data) in streams {
+            compound.create_stream(name).unwrap().write_all(data).unwrap();
+        }
+        compound.flush().unwrap();
+    }
+
+    /// SummaryInformation property set with VT_LPSTR properties
+    fn summary_information(properties: &[(u32, &str)]) -> Vec<u8> {
+        let mut values = Vec::new();
+        let mut index = Vec::new();
+        let header = 8 + properties.len() * 8;
+        for (id, text) in properties {
+            index.extend_from_slice(&id.to_le_bytes());
+            index.extend_from_slice(&((header + values.len()) as u32).to_le_bytes());
+            let mut bytes = text.as_bytes().to_vec();
+            bytes.push(0);
+            while bytes.len() % 4 != 0 {
+                bytes.push(0);
+            }
+            values.extend_from_slice(&0x1Eu32.to_le_bytes());
+            values.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            values.extend_from_slice(&bytes);
+        }
+
+        let mut data = vec![0u8; 48];
+        data[0..2].copy_from_slice(&0xFFFEu16.to_le_bytes());
+        data[24..28].copy_from_slice(&1u32.to_le_bytes());
+        data[44..48].copy_from_slice(&48u32.to_le_bytes());
+        data.extend_from_slice(&((header + values.len()) as u32).to_le_bytes());
+        data.extend_from_slice(&(properties.len() as u32).to_le_bytes());
+        data.extend_from_slice(&index);
+        data.extend_from_slice(&values);
+        data
+    }
+
+    fn biff_record(record_type: u16, data: &[u8]) -> Vec<u8> {
+        let mut record = record_type.to_le_bytes().to_vec();
+        record.extend_from_slice(&(data.len() as u16).to_le_bytes());
+        record.extend_from_slice(data);
+        record
+    }
+
+    fn ppt_record(ver_instance: u16, record_type: u16, body: &[u8]) -> Vec<u8> {
+        let mut record = ver_instance.to_le_bytes().to_vec();
+        record.extend_from_slice(&record_type.to_le_bytes());
+        record.extend_from_slice(&(body.len() as u32).to_le_bytes());
+        record.extend_from_slice(body);
+        record
+    }
+
+    #[test]
+    fn test_extract_doc_piece_table_and_summary() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("invoice.doc");
+        let text = "Invoice 2003\r\u{13} HYPERLINK \"x\" \u{14}Paid\u{15} in full\u{07}\rFooter";
+        let main_chars = text.len() - "Footer".len();
+
+        // FIB: csw = 14, cslw = 22 (ccpText third), cbRgFcLcb = 93 (fcClx/lcbClx pair 33)
+        let mut word_document = vec![0u8; 0x800];
+        word_document[0..2].copy_from_slice(&0xA5ECu16.to_le_bytes());
+        word_document[2..4].copy_from_slice(&0x00C1u16.to_le_bytes());
+        word_document[0x0A..0x0C].copy_from_slice(&0x0200u16.to_le_bytes());
+        word_document[32..34].copy_from_slice(&14u16.to_le_bytes());
+        word_document[62..64].copy_from_slice(&22u16.to_le_bytes());
+        word_document[76..80].copy_from_slice(&(main_chars as u32).to_le_bytes());
+        word_document[152..154].copy_from_slice(&93u16.to_le_bytes());
+        word_document[422..426].copy_from_slice(&21u32.to_le_bytes());
+        word_document.extend_from_slice(text.as_bytes());
+
+        // CLX: one 8-bit piece starting at byte 0x800
+        let mut clx = vec![0x02];
+        clx.extend_from_slice(&16u32.to_le_bytes());
+        clx.extend_from_slice(&0u32.to_le_bytes());
+        clx.extend_from_slice(&(text.len() as u32).to_le_bytes());
+        clx.extend_from_slice(&[0, 0]);
+        clx.extend_from_slice(&((0x800u32 * 2) | 0x4000_0000).to_le_bytes());
+        clx.extend_from_slice(&[0, 0]);
+
+        write_compound(
+            &path,
+            &[
+                ("WordDocument", word_document),
+                ("1Table", clx),
+                ("\u{5}SummaryInformation", summary_information(&[(2, "Invoice"), (4, "Pat Doe")])),
+            ],
+        );
+
+        let result = extract_doc(&path, 2000).unwrap();
+        assert_eq!(result.content, "Invoice 2003\nPaid in full");
+        assert_eq!(result.metadata.title.as_deref(), Some("Invoice"));
+        assert_eq!(result.metadata.author.as_deref(), Some("Pat Doe"));
+    }
+
+    #[test]
+    fn test_extract_xls_shared_strings_across_continue() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("budget.xls");
+
+        let bof = |dt: u16| {
+            let mut data = 0x0600u16.to_le_bytes().to_vec();
+            data.extend_from_slice(&dt.to_le_bytes());
+            data.extend_from_slice(&[0; 12]);
+            biff_record(0x0809, &data)
+        };
+        let cell = |row: u16, column: u16, rest: &[u8]| {
+            let mut data = row.to_le_bytes().to_vec();
+            data.extend_from_slice(&column.to_le_bytes());
+            data.extend_from_slice(&[0, 0]);
+            data.extend_from_slice(rest);
+            data
+        };
+
+        // SST: "Item", "Cost", then "Groceries" split after "Groc" with a 16-bit continuation
+        let mut sst = 3u32.to_le_bytes().to_vec();
+        sst.extend_from_slice(&3u32.to_le_bytes());
+        for word in ["Item", "Cost"] {
+            sst.extend_from_slice(&(word.len() as u16).to_le_bytes());
+            sst.push(0);
+            sst.extend_from_slice(word.as_bytes());
+        }
+        sst.extend_from_slice(&9u16.to_le_bytes());
+        sst.push(0);
+        sst.extend_from_slice(b"Groc");
+        let mut continued = vec![0x01];
+        continued.extend("eries".encode_utf16().flat_map(|u| u.to_le_bytes()));
+
+        let mut boundsheet = 0u32.to_le_bytes().to_vec();
+        boundsheet.extend_from_slice(&[0, 0, 6, 0]);
+        boundsheet.extend_from_slice(b"Budget");
+
+        let mut workbook = bof(0x0005);
+        let boundsheet_at = workbook.len() + 4;
+        workbook.extend(biff_record(0x0085, &boundsheet));
+        workbook.extend(biff_record(0x00FC, &sst));
+        workbook.extend(biff_record(0x003C, &continued));
+        workbook.extend(biff_record(0x000A, &[]));
+        let sheet_at = workbook.len() as u32;
+        workbook[boundsheet_at..boundsheet_at + 4].copy_from_slice(&sheet_at.to_le_bytes());
+
+        workbook.extend(bof(0x0010));
+        workbook.extend(biff_record(0x00FD, &cell(0, 0, &0u32.to_le_bytes())));
+        workbook.extend(biff_record(0x00FD, &cell(0, 1, &1u32.to_le_bytes())));
+        workbook.extend(biff_record(0x00FD, &cell(2, 0, &2u32.to_le_bytes())));
+        workbook.extend(biff_record(0x0203, &cell(2, 1, &412.5f64.to_le_bytes())));
+        workbook.extend(biff_record(0x027E, &cell(3, 1, &((1200u32 << 2) | 0x02).to_le_bytes())));
+        workbook.extend(biff_record(0x000A, &[]));
+
+        write_compound(&path, &[("Workbook", workbook)]);
+
+        let result = extract_xls(&path, 2000).unwrap();
+        assert_eq!(result.content, "[Sheet: Budget]\nHeader: Item | Cost\nGroceries | 412.5\n1200");
+        assert_eq!(result.metadata.page_count, Some(1));
+    }
+
+    #[test]
+    fn test_extract_ppt_slide_list_text() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("review.ppt");
+
+        let chars: Vec<u8> = "Agenda\rBudget review".encode_utf16().flat_map(|u| u.to_le_bytes()).collect();
+        let persist = ppt_record(0x0000, PPT_SLIDE_PERSIST_ATOM, &[0; 20]);
+        let mut slides = persist.clone();
+        slides.extend(ppt_record(0x0000, PPT_TEXT_CHARS_ATOM, &chars));
+        slides.extend(persist);
+        slides.extend(ppt_record(0x0000, PPT_TEXT_BYTES_ATOM, b"Next steps"));
+
+        let mut notes = ppt_record(0x0000, PPT_SLIDE_PERSIST_ATOM, &[0; 20]);
+        notes.extend(ppt_record(0x0000, PPT_TEXT_BYTES_ATOM, b"Speaker only"));
+
+        let mut document_body = ppt_record(0x000F, PPT_SLIDE_LIST_WITH_TEXT, &slides);
+        document_body.extend(ppt_record(0x002F, PPT_SLIDE_LIST_WITH_TEXT, &notes));
+        let document = ppt_record(0x000F, PPT_DOCUMENT, &document_body);
+
+        write_compound(&path, &[("PowerPoint Document", document)]);
+
+        let result = extract_ppt(&path, 2000).unwrap();
+        assert_eq!(result.content, "[Slide 1]\nAgenda\nBudget review\n\n[Slide 2]\nNext steps");
+        assert_eq!(result.metadata.page_count, Some(2));
+    }
+
+    #[test]
+    fn test_deeply_nested_ppt_containers_are_cut_off() {
+        // Containers nested far deeper than any real file: walked to a fixed depth, no stack overflow
+        let levels = 100_000;
+        let atom = ppt_record(0x0000, PPT_TEXT_BYTES_ATOM, b"Too deep");
+        let mut document = Vec::with_capacity(levels * 8 + atom.len());
+        for level in 0..levels {
+            let record_type = if level == 0 { PPT_SLIDE } else { 0xF000 };
+            let length = ((levels - level - 1) * 8 + atom.len()) as u32;
+            document.extend_from_slice(&0x000Fu16.to_le_bytes());
+            document.extend_from_slice(&record_type.to_le_bytes());
+            document.extend_from_slice(&length.to_le_bytes());
+        }
+        document.extend(atom);
+        assert!(read_slide_texts(&document).iter().all(|t| t.is_empty()));
+    }
+
+    #[test]
+    fn test_mulrk_past_last_column_is_dropped() {
+        // MULRK starting at the last column: cells past it are dropped instead of overflowing
+        let mut mulrk = 0u16.to_le_bytes().to_vec();
+        mulrk.extend_from_slice(&u16::MAX.to_le_bytes());
+        for value in [7u32, 8] {
+            mulrk.extend_from_slice(&[0, 0]);
+            mulrk.extend_from_slice(&((value << 2) | 0x02).to_le_bytes());
+        }
+        mulrk.extend_from_slice(&u16::MAX.to_le_bytes());
+        let bof = |dt: u16| {
+            let mut data = 0x0600u16.to_le_bytes().to_vec();
+            data.extend_from_slice(&dt.to_le_bytes());
+            data.extend_from_slice(&[0; 12]);
+            biff_record(0x0809, &data)
+        };
+        let mut workbook = bof(0x0005);
+        let boundsheet_at = workbook.len() + 4;
+        workbook.extend(biff_record(0x0085, &[0, 0, 0, 0, 0, 0, 1, 0, b'S']));
+        workbook.extend(biff_record(0x000A, &[]));
+        let sheet_at = workbook.len() as u32;
+        workbook[boundsheet_at..boundsheet_at + 4].copy_from_slice(&sheet_at.to_le_bytes());
+        workbook.extend(bof(0x0010));
+        workbook.extend(biff_record(0x00BD, &mulrk));
+        workbook.extend(biff_record(0x000A, &[]));
+        let sheets = read_workbook_sheets(&workbook).unwrap();
+        assert_eq!(sheets, vec![("S".to_string(), vec![vec!["7".to_string()]])]);
+    }
+
+    #[test]
+    fn test_oversized_rich_string_extension_is_rejected() {
+        // A rich string claiming 4 GB of extension data fails without allocating it
+        let mut sst_entry = 1u16.to_le_bytes().to_vec();
+        sst_entry.push(0x04);
+        sst_entry.extend_from_slice(&u32::MAX.to_le_bytes());
+        sst_entry.push(b'x');
+        let mut reader = ContinuedReader { segments: vec![&sst_entry], segment: 0, at: 0 };
+        assert_eq!(reader.rich_string(), None);
+    }
+}
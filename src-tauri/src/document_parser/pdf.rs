@@ -1,29 +1,64 @@
 //! PDF Parser (Group 3)
 //!
 //! Handles: .pdf files
-//! Strategy: Extract text layer using pdf-extract
+//! Strategy: Load the document → Info dictionary and XMP metadata → extract the
+//! text layer page by page with pdf-extract until enough text is gathered
 
+use super::image::normalize_date;
+use super::xmp::parse_xmp;
 use super::{DocumentMetadata, ParseError, ParsedDocument};
+use pdf_extract::{Document, PlainTextOutput};
 use std::path::Path;
 
-/// Extract text content from a PDF file
+/// Pages read at most; scanned PDFs have no text layer to fill the snippet
+const MAX_PAGES: usize = 50;
+
+/// Extract text content, Info/XMP metadata and the page count from a PDF file
 pub fn extract_pdf(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
-    // Use pdf-extract to get text content
-    let content = pdf_extract::extract_text(path)
-        .map_err(|e| ParseError::ParseError(format!("Failed to extract PDF text: {}", e)))?;
+    let mut doc = Document::load(path).map_err(|e| {
+        if !path.exists() {
+            ParseError::NotFound(path.to_string_lossy().to_string())
+        } else {
+            ParseError::ParseError(format!("Failed to load PDF: {}", e))
+        }
+    })?;
+    // Many PDFs are encrypted with an empty user password (permissions only)
+    if doc.is_encrypted() {
+        doc.decrypt("")
+            .map_err(|e| ParseError::ParseError(format!("Encrypted PDF: {}", e)))?;
+    }
 
-    // Truncate to max_chars (char-safe for multi-byte UTF-8)
-    let content = if content.chars().count() > max_chars {
-        content.chars().take(max_chars).collect::<String>()
-    } else {
-        content
-    };
+    let pages = doc.get_pages();
+    let mut metadata = read_metadata(&doc);
+    metadata.page_count = Some(pages.len() as u32);
+
+    // Stop at the first page that fills the snippet instead of extracting the whole document
+    let mut content = String::new();
+    for &page_num in pages.keys().take(MAX_PAGES) {
+        let mut page_text = String::new();
+        pdf_extract::output_doc_page(&doc, &mut PlainTextOutput::new(&mut page_text), page_num)
+            .map_err(|e| ParseError::ParseError(format!("Failed to extract PDF text: {}", e)))?;
 
-    // Clean up the extracted text
-    let content = clean_pdf_text(&content);
+        let page_text = clean_pdf_text(&page_text);
+        if !page_text.is_empty() {
+            if !content.is_empty() {
+                content.push('\n');
+            }
+            content.push_str(&page_text);
+        }
+        if content.chars().count() >= max_chars {
+            break;
+        }
+    }
+
+    // Truncate to max_chars (char-safe for multi-byte UTF-8)
+    if content.chars().count() > max_chars {
+        content = content.chars().take(max_chars).collect();
+    }
 
     // Calculate word count
     let word_count = content.split_whitespace().count() as u32;
+    metadata.word_count = Some(word_count);
 
     // Calculate confidence based on extraction quality
     // PDFs with good text layers have high confidence
@@ -32,14 +67,108 @@ pub fn extract_pdf(path: &Path, max_chars: usize) -> Result<ParsedDocument, Pars
 
     Ok(ParsedDocument {
         content,
-        metadata: DocumentMetadata {
-            word_count: Some(word_count),
-            ..Default::default()
-        },
+        metadata,
         extraction_confidence: confidence,
     })
 }
 
+/// Title, author, subject, keywords and creation date from the Info dictionary,
+/// with gaps filled from the catalog's XMP metadata stream
+fn read_metadata(doc: &Document) -> DocumentMetadata {
+    let mut metadata = DocumentMetadata::default();
+
+    let info = doc
+        .trailer
+        .get(b"Info")
+        .and_then(|info| doc.dereference(info))
+        .and_then(|(_, info)| info.as_dict());
+    if let Ok(info) = info {
+        let field = |key: &[u8]| {
+            info.get(key)
+                .and_then(|value| doc.dereference(value))
+                .and_then(|(_, value)| value.as_str())
+                .ok()
+                .map(text_string)
+                .filter(|value| !value.is_empty())
+        };
+        metadata.title = field(b"Title");
+        metadata.author = field(b"Author");
+        metadata.subject = field(b"Subject");
+        metadata.keywords = field(b"Keywords")
+            .map(|keywords| {
+                keywords
+                    .split([',', ';'])
+                    .map(str::trim)
+                    .filter(|k| !k.is_empty())
+                    .map(String::from)
+                    .collect()
+            })
+            .unwrap_or_default();
+        metadata.created_at = field(b"CreationDate").and_then(|date| pdf_date(&date));
+    }
+
+    let xmp = doc
+        .catalog()
+        .and_then(|catalog| catalog.get(b"Metadata"))
+        .and_then(|stream| doc.dereference(stream))
+        .and_then(|(_, stream)| stream.as_stream())
+        .and_then(|stream| stream.get_plain_content());
+    if let Ok(xmp) = xmp {
+        let xmp = parse_xmp(&String::from_utf8_lossy(&xmp));
+        metadata.title = metadata.title.or(xmp.title);
+        metadata.subject = metadata.subject.or(xmp.description);
+        if metadata.author.is_none() && !xmp.creators.is_empty() {
+            metadata.author = Some(xmp.creators.join(", "));
+        }
+        if metadata.keywords.is_empty() {
+            metadata.keywords = xmp.keywords;
+        }
+        if metadata.created_at.is_none() {
+            metadata.created_at = xmp.created.map(|raw| normalize_date(&raw).unwrap_or(raw));
+        }
+    }
+
+    metadata
+}
+
+/// Decode a PDF text string: UTF-16BE or UTF-8 with a byte order mark, else PDFDocEncoding
+/// (treated as Latin-1, which it matches for printable characters)
+fn text_string(bytes: &[u8]) -> String {
+    let text = match bytes {
+        [0xFE, 0xFF, rest @ ..] => {
+            let units: Vec<u16> = rest.chunks_exact(2).map(|c| u16::from_be_bytes([c[0], c[1]])).collect();
+            String::from_utf16_lossy(&units)
+        }
+        [0xEF, 0xBB, 0xBF, rest @ ..] => String::from_utf8_lossy(rest).to_string(),
+        _ => bytes.iter().map(|&b| b as char).collect(),
+    };
+    text.trim().to_string()
+}
+
+/// "D:20230714183205+01'00'" → "2023-07-14 18:32:05" (missing parts default to the start of the period)
+fn pdf_date(raw: &str) -> Option<String> {
+    let digits: String = raw
+        .trim()
+        .trim_start_matches("D:")
+        .chars()
+        .take_while(char::is_ascii_digit)
+        .take(14)
+        .collect();
+    if digits.len() < 4 {
+        return None;
+    }
+    let padded = format!("{}{}", digits, &"0101000000"[digits.len() - 4..]);
+    Some(format!(
+        "{}-{}-{} {}:{}:{}",
+        &padded[0..4],
+        &padded[4..6],
+        &padded[6..8],
+        &padded[8..10],
+        &padded[10..12],
+        &padded[12..14]
+    ))
+}
+
 /// Clean up extracted PDF text
 fn clean_pdf_text(text: &str) -> String {
     text.lines()
@@ -93,6 +222,87 @@ fn calculate_extraction_confidence(content: &str, word_count: u32) -> f64 {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use pdf_extract::{Dictionary, Object, Stream, StringFormat};
+
+    /// A PDF with one Helvetica text line per page and an Info dictionary
+    fn write_test_pdf(path: &Path, page_texts: &[&str], info: Dictionary) {
+        let mut doc = Document::with_version("1.5");
+        let pages_id = doc.new_object_id();
+
+        let mut font = Dictionary::new();
+        font.set("Type", "Font");
+        font.set("Subtype", "Type1");
+        font.set("BaseFont", "Helvetica");
+        let font_id = doc.add_object(font);
+        let mut fonts = Dictionary::new();
+        fonts.set("F1", font_id);
+        let mut resources = Dictionary::new();
+        resources.set("Font", fonts);
+        let resources_id = doc.add_object(resources);
+
+        let mut kids = Vec::new();
+        for text in page_texts {
+            let operations = format!("BT /F1 12 Tf 72 720 Td ({}) Tj ET", text);
+            let content_id = doc.add_object(Stream::new(Dictionary::new(), operations.into_bytes()));
+            let mut page = Dictionary::new();
+            page.set("Type", "Page");
+            page.set("Parent", pages_id);
+            page.set("Contents", content_id);
+            page.set("Resources", resources_id);
+            page.set("MediaBox", vec![0.into(), 0.into(), 612.into(), 792.into()]);
+            kids.push(Object::Reference(doc.add_object(page)));
+        }
+
+        let mut pages = Dictionary::new();
+        pages.set("Type", "Pages");
+        pages.set("Count", kids.len() as i64);
+        pages.set("Kids", kids);
+        doc.objects.insert(pages_id, Object::Dictionary(pages));
+
+        let mut catalog = Dictionary::new();
+        catalog.set("Type", "Catalog");
+        catalog.set("Pages", pages_id);
+        let catalog_id = doc.add_object(catalog);
+        doc.trailer.set("Root", catalog_id);
+        let info_id = doc.add_object(info);
+        doc.trailer.set("Info", info_id);
+        doc.save(path).unwrap();
+    }
+
+    #[test]
+    fn test_extract_pdf_metadata_and_stop_after_enough_pages() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("report.pdf");
+
+        let mut info = Dictionary::new();
+        // UTF-16BE with byte order mark
+        let title: Vec<u8> = [0xFE, 0xFF].into_iter().chain("Relatório anual".encode_utf16().flat_map(u16::to_be_bytes)).collect();
+        info.set("Title", Object::String(title, StringFormat::Hexadecimal));
+        info.set("Author", Object::string_literal("Ana Reis"));
+        info.set("Keywords", Object::string_literal("finance, 2023; annual"));
+        info.set("CreationDate", Object::string_literal("D:20240131093000+01'00'"));
+        write_test_pdf(&path, &["Quarterly revenue grew", "Second page text", "Third page text"], info);
+
+        let result = extract_pdf(&path, 10).unwrap();
+        // The first page already fills the snippet
+        assert_eq!(result.content, "Quarterly ");
+        assert_eq!(result.metadata.page_count, Some(3));
+        assert_eq!(result.metadata.title.as_deref(), Some("Relatório anual"));
+        assert_eq!(result.metadata.author.as_deref(), Some("Ana Reis"));
+        assert_eq!(result.metadata.keywords, vec!["finance", "2023", "annual"]);
+        assert_eq!(result.metadata.created_at.as_deref(), Some("2024-01-31 09:30:00"));
+
+        let result = extract_pdf(&path, 2000).unwrap();
+        assert!(result.content.contains("Quarterly revenue grew"));
+        assert!(result.content.contains("Third page text"));
+    }
+
+    #[test]
+    fn test_pdf_date() {
+        assert_eq!(pdf_date("D:20230714183205+01'00'").as_deref(), Some("2023-07-14 18:32:05"));
+        assert_eq!(pdf_date("D:202307").as_deref(), Some("2023-07-01 00:00:00"));
+        assert_eq!(pdf_date("garbage"), None);
+    }
 
     #[test]
     fn test_clean_pdf_text() {
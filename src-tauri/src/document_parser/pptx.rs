@@ -1,11 +1,13 @@
 //! PPTX Parser (Group 2 - Office Open XML)
 //!
 //! Handles: .pptx files
-//! Strategy: Unzip → read ppt/slides/slide*.xml → extract text from XML
+//! Strategy: Unzip → read ppt/slides/slide*.xml, their layouts and speaker notes
+//! (ppt/notesSlides) → slide titles first, then each slide's body text and notes
 
 use super::{DocumentMetadata, ParseError, ParsedDocument};
-use quick_xml::events::Event;
+use quick_xml::events::{BytesStart, Event};
 use quick_xml::Reader;
+use std::collections::HashMap;
 use std::fs::File;
 use std::io::Read;
 use std::path::Path;
@@ -113,7 +115,14 @@ fn count_slides(archive: &mut ZipArchive<File>) -> u32 {
     count
 }
 
-/// Extract text from all slide XML files
+/// Text of one slide or notes page, split by placeholder role
+#[derive(Debug, Default)]
+struct SlideText {
+    title: Option<String>,
+    body: String,
+}
+
+/// Extract slide titles first, then each slide's body text (with its layout name) and speaker notes
 fn extract_slides_text(archive: &mut ZipArchive<File>, max_chars: usize) -> Result<String, ParseError> {
     // First, collect slide file names (they need to be sorted for proper order)
     let mut slide_names: Vec<String> = Vec::new();
@@ -127,33 +136,64 @@ fn extract_slides_text(archive: &mut ZipArchive<File>, max_chars: usize) -> Resu
     }
 
     // Sort slides by number (slide1.xml, slide2.xml, etc.)
-    slide_names.sort_by(|a, b| {
-        let num_a = extract_slide_number(a);
-        let num_b = extract_slide_number(b);
-        num_a.cmp(&num_b)
-    });
+    slide_names.sort_by_key(|name| extract_slide_number(name));
 
-    let mut content = String::new();
-    let mut slide_num = 1;
+    let mut titles = Vec::new();
+    let mut sections = Vec::new();
+    let mut layout_names: HashMap<String, Option<String>> = HashMap::new();
 
-    for slide_name in slide_names {
-        if content.chars().count() >= max_chars {
-            break;
+    for (index, slide_name) in slide_names.iter().enumerate() {
+        let Some(xml_content) = read_part(archive, slide_name) else {
+            continue;
+        };
+        let slide = extract_text_from_slide_xml(&xml_content);
+        let (layout_part, notes_part) = slide_relationships(archive, slide_name);
+
+        // Titles are short and read for every slide; the rest only while the budget lasts
+        if let Some(title) = &slide.title {
+            titles.push(title.clone());
+        }
+        if sections.iter().map(|s: &String| s.chars().count()).sum::<usize>() >= max_chars {
+            continue;
         }
 
-        if let Ok(mut slide_file) = archive.by_name(&slide_name) {
-            let mut xml_content = String::new();
-            if slide_file.read_to_string(&mut xml_content).is_ok() {
-                let slide_text = extract_text_from_slide_xml(&xml_content);
-                if !slide_text.is_empty() {
-                    if !content.is_empty() {
-                        content.push_str("\n\n");
-                    }
-                    content.push_str(&format!("[Slide {}]\n{}", slide_num, slide_text));
-                }
-            }
+        let layout = layout_part.and_then(|part| {
+            layout_names
+                .entry(part.clone())
+                .or_insert_with(|| read_part(archive, &part).and_then(|xml| layout_name(&xml)))
+                .clone()
+        });
+        let notes = notes_part
+            .and_then(|part| read_part(archive, &part))
+            .map(|xml| extract_text_from_slide_xml(&xml).body)
+            .unwrap_or_default();
+
+        if slide.body.is_empty() && notes.is_empty() {
+            continue;
         }
-        slide_num += 1;
+        let mut section = match layout {
+            Some(layout) => format!("[Slide {} - {}]", index + 1, layout),
+            None => format!("[Slide {}]", index + 1),
+        };
+        if !slide.body.is_empty() {
+            section.push('\n');
+            section.push_str(&slide.body);
+        }
+        if !notes.is_empty() {
+            section.push_str(&format!("\n[Notes]\n{}", notes));
+        }
+        sections.push(section);
+    }
+
+    let mut content = String::new();
+    if !titles.is_empty() {
+        content.push_str(&format!("[Slide titles]\n{}", titles.join("\n")));
+    }
+    for section in sections {
+        if !content.is_empty() {
+            content.push_str("\n\n");
+        }
+        content.push_str(&section);
     }
 
     // Truncate if needed (char-safe for multi-byte UTF-8)
@@ -164,6 +204,100 @@ fn extract_slides_text(archive: &mut ZipArchive<File>, max_chars: usize) -> Resu
     Ok(content)
 }
 
+fn read_part(archive: &mut ZipArchive<File>, name: &str) -> Option<String> {
+    let mut part = archive.by_name(name).ok()?;
+    let mut xml_content = String::new();
+    part.read_to_string(&mut xml_content).ok()?;
+    Some(xml_content)
+}
+
+/// Archive paths of a slide's layout and notes parts, from ppt/slides/_rels/slideN.xml.rels
+fn slide_relationships(archive: &mut ZipArchive<File>, slide_name: &str) -> (Option<String>, Option<String>) {
+    let (dir, file_name) = slide_name.rsplit_once('/').unwrap_or(("", slide_name));
+    let Some(xml_content) = read_part(archive, &format!("{}/_rels/{}.rels", dir, file_name)) else {
+        return (None, None);
+    };
+
+    let mut reader = Reader::from_str(&xml_content);
+    let mut layout = None;
+    let mut notes = None;
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"Relationship" => {
+                let mut kind = String::new();
+                let mut target = None;
+                for attr in e.attributes().flatten() {
+                    let value = attr.unescape_value().unwrap_or_default().to_string();
+                    match attr.key.as_ref() {
+                        b"Type" => kind = value,
+                        b"Target" => target = Some(value),
+                        _ => {}
+                    }
+                }
+                let slot = if kind.ends_with("/slideLayout") {
+                    &mut layout
+                } else if kind.ends_with("/notesSlide") {
+                    &mut notes
+                } else {
+                    continue;
+                };
+                *slot = target.map(|target| resolve_target(dir, &target));
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    (layout, notes)
+}
+
+/// Archive path of a relationship target relative to the source part's directory
+/// ("../slideLayouts/slideLayout2.xml" from ppt/slides → ppt/slideLayouts/slideLayout2.xml)
+fn resolve_target(dir: &str, target: &str) -> String {
+    if let Some(absolute) = target.strip_prefix('/') {
+        return absolute.to_string();
+    }
+    let mut parts: Vec<&str> = dir.split('/').filter(|p| !p.is_empty()).collect();
+    for part in target.split('/') {
+        match part {
+            "" | "." => {}
+            ".." => {
+                parts.pop();
+            }
+            part => parts.push(part),
+        }
+    }
+    parts.join("/")
+}
+
+/// Layout name from a slide layout part (<p:cSld name="Title and Content">)
+fn layout_name(xml_content: &str) -> Option<String> {
+    let mut reader = Reader::from_str(xml_content);
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.name().as_ref() == b"p:cSld" => {
+                return attribute(&e, "name").filter(|name| !name.is_empty());
+            }
+            Ok(Event::Eof) | Err(_) => return None,
+            _ => {}
+        }
+        buf.clear();
+    }
+}
+
+fn attribute(e: &BytesStart, name: &str) -> Option<String> {
+    e.try_get_attribute(name)
+        .ok()
+        .flatten()
+        .and_then(|attr| attr.unescape_value().ok().map(|v| v.trim().to_string()))
+}
+
 /// Extract slide number from filename (e.g., "ppt/slides/slide5.xml" -> 5)
 fn extract_slide_number(name: &str) -> u32 {
     name.trim_start_matches("ppt/slides/slide")
@@ -172,42 +306,56 @@ fn extract_slide_number(name: &str) -> u32 {
         .unwrap_or(0)
 }
 
-/// Extract text from a single slide's XML content
-fn extract_text_from_slide_xml(xml_content: &str) -> String {
+/// Extract text from a single slide's (or notes page's) XML content.
+/// Title placeholders are returned separately; slide number, date, footer and
+/// header placeholders are skipped.
+fn extract_text_from_slide_xml(xml_content: &str) -> SlideText {
     let mut reader = Reader::from_str(xml_content);
     reader.config_mut().trim_text(true);
 
+    let mut slide = SlideText::default();
+    let mut titles = Vec::new();
     let mut content = String::new();
+    // Text of the current shape (<p:sp>) and its placeholder type
+    let mut shape_text = String::new();
+    let mut placeholder: Option<String> = None;
     let mut in_text_element = false;
     let mut buf = Vec::new();
 
     loop {
         match reader.read_event_into(&mut buf) {
-            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => {
-                let name = e.name();
-                let local_name = String::from_utf8_lossy(name.as_ref());
-
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) => match e.name().as_ref() {
                 // PowerPoint uses <a:t> for text elements
-                if local_name == "a:t" {
-                    in_text_element = true;
-                }
-            }
-            Ok(Event::End(e)) => {
-                let name = e.name();
-                let local_name = String::from_utf8_lossy(name.as_ref());
-
-                if local_name == "a:t" {
-                    in_text_element = false;
+                b"a:t" => in_text_element = true,
+                b"p:sp" => {
+                    shape_text.clear();
+                    placeholder = None;
                 }
+                // A placeholder without a type is a body placeholder
+                b"p:ph" => placeholder = Some(attribute(&e, "type").unwrap_or_else(|| "body".to_string())),
+                _ => {}
+            },
+            Ok(Event::End(e)) => match e.name().as_ref() {
+                b"a:t" => in_text_element = false,
                 // Add line break after paragraphs
-                if local_name == "a:p" && !content.is_empty() && !content.ends_with('\n') {
-                    content.push('\n');
+                b"a:p" if !shape_text.is_empty() && !shape_text.ends_with('\n') => shape_text.push('\n'),
+                b"p:sp" => {
+                    let text = std::mem::take(&mut shape_text);
+                    match placeholder.take().as_deref() {
+                        Some("title" | "ctrTitle") => titles.push(text.split_whitespace().collect::<Vec<_>>().join(" ")),
+                        Some("sldNum" | "dt" | "ftr" | "hdr" | "sldImg") => {}
+                        _ => content.push_str(&text),
+                    }
+                    if !content.is_empty() && !content.ends_with('\n') {
+                        content.push('\n');
+                    }
                 }
-            }
+                _ => {}
+            },
             Ok(Event::Text(e)) => {
                 if in_text_element {
                     let text = e.unescape().unwrap_or_default();
-                    content.push_str(&text);
+                    shape_text.push_str(&text);
                 }
             }
             Ok(Event::Eof) => break,
@@ -217,16 +365,82 @@ fn extract_text_from_slide_xml(xml_content: &str) -> String {
         buf.clear();
     }
 
+    slide.title = Some(titles.join(" - ")).filter(|title| !title.is_empty());
     // Clean up whitespace
-    content
+    slide.body = content
         .lines()
         .map(|l| l.trim())
         .filter(|l| !l.is_empty())
         .collect::<Vec<_>>()
-        .join("\n")
+        .join("\n");
+    slide
 }
 
 #[cfg(test)]
 mod tests {
-    // Tests would require actual .pptx files
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    #[test]
+    fn test_extract_titles_first_with_layouts_and_notes() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("deck.pptx");
+
+        let slide = |title: &str, body: &str| {
+            format!(
+                r#"<p:sld><p:cSld><p:spTree>
+                    <p:sp><p:nvSpPr><p:nvPr><p:ph type="title"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>{}</a:t></a:r></a:p></p:txBody></p:sp>
+                    <p:sp><p:nvSpPr><p:nvPr><p:ph idx="1"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>{}</a:t></a:r></a:p></p:txBody></p:sp>
+                    <p:sp><p:nvSpPr><p:nvPr><p:ph type="sldNum"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:fld><a:t>7</a:t></a:fld></a:p></p:txBody></p:sp>
+                </p:spTree></p:cSld></p:sld>"#,
+                title, body
+            )
+        };
+        let rels = |layout: u32, notes: Option<u32>| {
+            let notes = notes.map_or(String::new(), |n| {
+                format!(r#"<Relationship Id="rId2" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/notesSlide" Target="../notesSlides/notesSlide{}.xml"/>"#, n)
+            });
+            format!(
+                r#"<Relationships><Relationship Id="rId1" Type="http://schemas.openxmlformats.org/officeDocument/2006/relationships/slideLayout" Target="../slideLayouts/slideLayout{}.xml"/>{}</Relationships>"#,
+                layout, notes
+            )
+        };
+        let parts = [
+            ("ppt/slides/slide1.xml", slide("Q3 Review", "Acme Corp")),
+            ("ppt/slides/_rels/slide1.xml.rels", rels(1, None)),
+            ("ppt/slides/slide2.xml", slide("Revenue", "Up 12%")),
+            ("ppt/slides/_rels/slide2.xml.rels", rels(2, Some(1))),
+            ("ppt/slideLayouts/slideLayout1.xml", r#"<p:sldLayout><p:cSld name="Title Slide"/></p:sldLayout>"#.to_string()),
+            ("ppt/slideLayouts/slideLayout2.xml", r#"<p:sldLayout><p:cSld name="Title and Content"><p:spTree/></p:cSld></p:sldLayout>"#.to_string()),
+            (
+                "ppt/notesSlides/notesSlide1.xml",
+                r#"<p:notes><p:cSld><p:spTree>
+                    <p:sp><p:nvSpPr><p:nvPr><p:ph type="sldImg"/></p:nvPr></p:nvSpPr></p:sp>
+                    <p:sp><p:nvSpPr><p:nvPr><p:ph type="body" idx="1"/></p:nvPr></p:nvSpPr><p:txBody><a:p><a:r><a:t>Mention the Globex deal</a:t></a:r></a:p></p:txBody></p:sp>
+                </p:spTree></p:cSld></p:notes>"#
+                    .to_string(),
+            ),
+        ];
+
+        let mut zip = ZipWriter::new(File::create(&path).unwrap());
+        for (name, xml) in &parts {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(xml.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+
+        let result = extract_pptx(&path, 2000).unwrap();
+        assert_eq!(
+            result.content,
+            "[Slide titles]\nQ3 Review\nRevenue\n\n[Slide 1 - Title Slide]\nAcme Corp\n\n\
+             [Slide 2 - Title and Content]\nUp 12%\n[Notes]\nMention the Globex deal"
+        );
+        assert_eq!(result.metadata.page_count, Some(2));
+
+        // A small budget still holds every title
+        let result = extract_pptx(&path, 30).unwrap();
+        assert_eq!(result.content, "[Slide titles]\nQ3 Review\nReven");
+    }
 }
@@ -0,0 +1,285 @@
+//! Parser registry
+//!
+//! Every format is a `DocumentParser`: the extensions it handles and the extraction
+//! itself. Content sniffing lives in `detect`, which names the extension to use. The built-in
+//! parsers are registered first; user-configured external extractors come after
+//! and take over any extension they list.
+
+use super::{docx, email, epub, html, image, media, ole, odf, pdf, pptx, rtf, text, xlsx};
+use super::{calculate_confidence, truncate, DocumentMetadata, ParseError, ParsedDocument};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// A document format the extraction pipeline can read
+pub trait DocumentParser: Send + Sync {
+    /// Stored as content_snippets.extraction_method
+    fn name(&self) -> &str;
+
+    /// Lowercase extensions without the dot
+    fn extensions(&self) -> Vec<String>;
+
+    /// Extract text (up to `max_chars`) and metadata
+    fn extract(&self, path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError>;
+}
+
+/// Detected types recognised from leading text rather than a signature
+const TEXT_TYPES: &[&str] = &["txt", "html", "eml", "mhtml", "mbox"];
+
+/// A parser compiled into the app
+#[derive(Clone, Copy)]
+struct BuiltinParser {
+    name: &'static str,
+    extensions: &'static [&'static str],
+    extract: fn(&Path, usize) -> Result<ParsedDocument, ParseError>,
+}
+
+impl DocumentParser for BuiltinParser {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn extensions(&self) -> Vec<String> {
+        self.extensions.iter().map(|e| e.to_string()).collect()
+    }
+
+    fn extract(&self, path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+        (self.extract)(path, max_chars)
+    }
+}
+
+/// Built-in parsers
+const BUILTIN_PARSERS: &[BuiltinParser] = &[
+    // Group 1: Plain Text & Rich Text
+    BuiltinParser { name: "text", extensions: &["txt", "md", "markdown", "log", "csv", "tsv"], extract: text::extract_text },
+    BuiltinParser { name: "rtf", extensions: &["rtf"], extract: rtf::extract_rtf },
+    // Group 2: Office Open XML
+    BuiltinParser { name: "docx", extensions: &["docx"], extract: docx::extract_docx },
+    BuiltinParser { name: "pptx", extensions: &["pptx"], extract: pptx::extract_pptx },
+    BuiltinParser { name: "xlsx", extensions: &["xlsx"], extract: xlsx::extract_xlsx },
+    // Group 2: OpenDocument
+    BuiltinParser { name: "odt", extensions: &["odt"], extract: odf::extract_odt },
+    BuiltinParser { name: "ods", extensions: &["ods"], extract: odf::extract_ods },
+    BuiltinParser { name: "odp", extensions: &["odp"], extract: odf::extract_odp },
+    // Group 3: PDF
+    BuiltinParser { name: "pdf", extensions: &["pdf"], extract: pdf::extract_pdf },
+    // Group 4: Legacy Office
+    BuiltinParser { name: "doc", extensions: &["doc"], extract: ole::extract_doc },
+    BuiltinParser { name: "xls", extensions: &["xls"], extract: ole::extract_xls },
+    BuiltinParser { name: "ppt", extensions: &["ppt"], extract: ole::extract_ppt },
+    // Group 5: Email
+    BuiltinParser { name: "eml", extensions: &["eml"], extract: email::extract_eml },
+    BuiltinParser { name: "mbox", extensions: &["mbox"], extract: email::extract_mbox },
+    BuiltinParser { name: "msg", extensions: &["msg"], extract: email::extract_msg },
+    // Group 6: Web pages & e-books
+    BuiltinParser { name: "html", extensions: &["html", "htm"], extract: html::extract_html },
+    BuiltinParser { name: "mhtml", extensions: &["mhtml", "mht"], extract: html::extract_mhtml },
+    BuiltinParser { name: "epub", extensions: &["epub"], extract: epub::extract_epub },
+    // Group 7: Images
+    BuiltinParser {
+        name: "image",
+        extensions: &["jpg", "jpeg", "png", "tif", "tiff", "heic", "heif"],
+        extract: image::extract_image,
+    },
+    // Group 8: Audio & video
+    BuiltinParser {
+        name: "media",
+        extensions: &["mp3", "flac", "m4a", "m4b", "mp4", "m4v", "mov"],
+        extract: media::extract_media,
+    },
+];
+
+/// A user-configured program that prints a file's text on stdout
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ExternalExtractor {
+    /// Stored as the extraction method
+    pub name: String,
+    /// Extensions it handles, without the dot; these take over from the built-in parsers
+    pub extensions: Vec<String>,
+    /// Program to run
+    pub command: String,
+    /// Arguments; "{path}" is replaced with the file path, which is appended when no argument has it
+    #[serde(default)]
+    pub args: Vec<String>,
+}
+
+/// How long an external extractor may run before it is killed
+const EXTERNAL_TIMEOUT: Duration = Duration::from_secs(30);
+
+impl DocumentParser for ExternalExtractor {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn extensions(&self) -> Vec<String> {
+        self.extensions
+            .iter()
+            .map(|e| e.trim().trim_start_matches('.').to_lowercase())
+            .filter(|e| !e.is_empty())
+            .collect()
+    }
+
+    fn extract(&self, path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+        let path_arg = path.to_string_lossy();
+        let mut args: Vec<String> = self.args.iter().map(|arg| arg.replace("{path}", &path_arg)).collect();
+        if !self.args.iter().any(|arg| arg.contains("{path}")) {
+            args.push(path_arg.to_string());
+        }
+
+        let mut child = Command::new(&self.command)
+            .args(&args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| ParseError::ReadError(format!("Failed to run {}: {}", self.command, e)))?;
+
+        // Keep what fits the snippet (4 bytes covers any UTF-8 char) and drain the rest
+        // so the program never blocks on a full pipe
+        let mut stdout = child.stdout.take().ok_or_else(|| ParseError::ReadError("No stdout".to_string()))?;
+        let limit = (max_chars * 4) as u64;
+        let reader = thread::spawn(move || {
+            let mut output = Vec::new();
+            let _ = (&mut stdout).take(limit).read_to_end(&mut output);
+            let _ = io::copy(&mut stdout, &mut io::sink());
+            output
+        });
+
+        let deadline = Instant::now() + EXTERNAL_TIMEOUT;
+        let status = loop {
+            if let Some(status) = child.try_wait().map_err(|e| ParseError::ReadError(e.to_string()))? {
+                break status;
+            }
+            if Instant::now() >= deadline {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(ParseError::ParseError(format!("{} timed out", self.name)));
+            }
+            thread::sleep(Duration::from_millis(20));
+        };
+        if !status.success() {
+            return Err(ParseError::ParseError(format!("{} failed ({})", self.name, status)));
+        }
+
+        let output = reader.join().unwrap_or_default();
+        let content = truncate(String::from_utf8_lossy(&output).trim().to_string(), max_chars);
+        let word_count = content.split_whitespace().count() as u32;
+
+        Ok(ParsedDocument {
+            content,
+            metadata: DocumentMetadata {
+                word_count: Some(word_count),
+                ..Default::default()
+            },
+            extraction_confidence: calculate_confidence(word_count),
+        })
+    }
+}
+
+/// The parsers available to a scan, looked up by extension or by detected type
+#[derive(Default)]
+pub struct ParserRegistry {
+    parsers: Vec<Box<dyn DocumentParser>>,
+    /// Lowercase extension → index into `parsers`; later registrations win
+    by_extension: HashMap<String, usize>,
+}
+
+impl ParserRegistry {
+    /// The parsers compiled into the app
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        for parser in BUILTIN_PARSERS {
+            registry.register(Box::new(*parser));
+        }
+        registry
+    }
+
+    /// The built-in parsers plus the user's external extractors
+    pub fn with_external(extractors: &[ExternalExtractor]) -> Self {
+        let mut registry = Self::builtin();
+        for extractor in extractors.iter().filter(|e| !e.command.trim().is_empty()) {
+            registry.register(Box::new(extractor.clone()));
+        }
+        registry
+    }
+
+    /// Add a parser; it takes over the extensions it lists from earlier ones
+    pub fn register(&mut self, parser: Box<dyn DocumentParser>) {
+        let index = self.parsers.len();
+        for extension in parser.extensions() {
+            self.by_extension.insert(extension.to_lowercase(), index);
+        }
+        self.parsers.push(parser);
+    }
+
+    /// The parser for a file extension (case-insensitive, without the dot)
+    pub fn for_extension(&self, extension: &str) -> Option<&dyn DocumentParser> {
+        self.by_extension
+            .get(&extension.to_lowercase())
+            .map(|&index| self.parsers[index].as_ref())
+    }
+
+    /// The parser for a file. A type detected from a binary signature wins over a
+    /// misleading extension; text-based detections (plain, HTML, email) are heuristics,
+    /// so they only apply when the extension has no parser of its own.
+    pub fn for_file(&self, extension: Option<&str>, detected_type: Option<&str>) -> Option<&dyn DocumentParser> {
+        let by_extension = extension.and_then(|extension| self.for_extension(extension));
+        let by_content = detected_type.and_then(|detected| self.for_extension(detected));
+        match detected_type {
+            Some(detected) if !TEXT_TYPES.contains(&detected) => by_content.or(by_extension),
+            _ => by_extension.or(by_content),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parser_for_file() {
+        let registry = ParserRegistry::builtin();
+        let parser = |extension, detected| registry.for_file(extension, detected).map(|p| p.name().to_string());
+
+        assert_eq!(parser(Some("txt"), Some("pdf")), Some("pdf".to_string()));
+        assert_eq!(parser(None, Some("docx")), Some("docx".to_string()));
+        assert_eq!(parser(Some("md"), Some("txt")), Some("text".to_string()));
+        assert_eq!(parser(Some("mhtml"), Some("eml")), Some("mhtml".to_string()));
+        assert_eq!(parser(Some("docx"), Some("zip")), Some("docx".to_string()));
+        assert_eq!(parser(Some("dat"), Some("html")), Some("html".to_string()));
+        assert_eq!(parser(Some("exe"), None), None);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_external_extractor_takes_over_extension() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.TXT");
+        std::fs::write(&path, "converted by an external tool").unwrap();
+
+        let registry = ParserRegistry::with_external(&[ExternalExtractor {
+            name: "cat".to_string(),
+            extensions: vec![".txt".to_string(), "Pages".to_string()],
+            command: "cat".to_string(),
+            args: vec![],
+        }]);
+        assert_eq!(registry.for_extension("md").map(|p| p.name()), Some("text"));
+        assert_eq!(registry.for_extension("pages").map(|p| p.name()), Some("cat"));
+
+        let parser = registry.for_extension("txt").unwrap();
+        assert_eq!(parser.name(), "cat");
+        let parsed = parser.extract(&path, 9).unwrap();
+        assert_eq!(parsed.content, "converted");
+
+        let failing = ExternalExtractor {
+            name: "false".to_string(),
+            command: "false".to_string(),
+            ..Default::default()
+        };
+        assert!(failing.extract(&path, 100).is_err());
+    }
+}
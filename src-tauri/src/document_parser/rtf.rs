@@ -0,0 +1,400 @@
+//! RTF Parser (Group 1 - Rich Text)
+//!
+//! Handles: .rtf files
+//! Strategy: Tokenize control words and groups → keep body text, skip font/color/style
+//! tables, pictures and other ignorable destinations → read \info (title, author)
+
+use super::ole::decode_cp1252;
+use super::{DocumentMetadata, ParseError, ParsedDocument};
+use std::fs;
+use std::path::Path;
+
+/// Destinations whose text is never part of the document body
+const SKIPPED_DESTINATIONS: &[&str] = &[
+    "fonttbl",
+    "colortbl",
+    "stylesheet",
+    "listtable",
+    "listoverridetable",
+    "revtbl",
+    "rsidtbl",
+    "generator",
+    "xmlnstbl",
+    "latentstyles",
+    "themedata",
+    "colorschememapping",
+    "datastore",
+    "pict",
+    "shppict",
+    "nonshppict",
+    "object",
+    "fldinst",
+    "header",
+    "headerl",
+    "headerr",
+    "headerf",
+    "footer",
+    "footerl",
+    "footerr",
+    "footerf",
+    "footnote",
+    "annotation",
+    "bkmkstart",
+    "bkmkend",
+    "pgdsctbl",
+    "mmathPr",
+];
+
+/// Where the text of the current group goes
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Destination {
+    Body,
+    Skip,
+    /// Inside \info, but not in a field we keep
+    Info,
+    Title,
+    Author,
+    Subject,
+    Keywords,
+}
+
+/// Per-group state, restored when the group closes
+#[derive(Debug, Clone, Copy)]
+struct GroupState {
+    destination: Destination,
+    /// Fallback characters that follow each \uN (\ucN)
+    unicode_skip: usize,
+}
+
+/// Extract text content from an RTF file
+pub fn extract_rtf(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    let data = fs::read(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ParseError::NotFound(path.to_string_lossy().to_string())
+        } else {
+            ParseError::ReadError(e.to_string())
+        }
+    })?;
+
+    if !data.starts_with(b"{\\rtf") {
+        return Err(ParseError::ParseError("Not an RTF file (missing {\\rtf header)".to_string()));
+    }
+
+    let (text, metadata) = parse_rtf(&data, max_chars);
+    let content = clean_text(&text, max_chars);
+
+    let word_count = content.split_whitespace().count() as u32;
+    let confidence = calculate_extraction_confidence(&content, word_count);
+
+    Ok(ParsedDocument {
+        content,
+        metadata: DocumentMetadata {
+            word_count: Some(word_count),
+            ..metadata
+        },
+        extraction_confidence: confidence,
+    })
+}
+
+/// Walk the RTF token stream, collecting body text (stopping once it passes
+/// `max_chars`) and \info metadata
+fn parse_rtf(data: &[u8], max_chars: usize) -> (String, DocumentMetadata) {
+    let mut body = String::new();
+    let mut body_chars = 0usize;
+    let mut metadata = DocumentMetadata::default();
+    let mut info_text = String::new();
+
+    let mut state = GroupState {
+        destination: Destination::Body,
+        unicode_skip: 1,
+    };
+    let mut stack: Vec<GroupState> = Vec::new();
+    // Fallback characters still to drop after a \uN
+    let mut pending_skip = 0usize;
+    // Set by \* : the next control word starts a destination to skip if unknown
+    let mut ignorable = false;
+    let mut at = 0;
+
+    while at < data.len() {
+        // Characters for the current destination produced by this token
+        let mut emitted: Option<char> = None;
+
+        match data[at] {
+            b'{' => {
+                stack.push(state);
+                at += 1;
+            }
+            b'}' => {
+                let closing = state.destination;
+                state = stack.pop().unwrap_or(state);
+                if closing != state.destination {
+                    store_info_field(closing, &mut info_text, &mut metadata);
+                }
+                ignorable = false;
+                at += 1;
+            }
+            b'\\' => {
+                let Some(&next) = data.get(at + 1) else {
+                    break;
+                };
+                if next.is_ascii_alphabetic() {
+                    let (word, parameter, end) = read_control_word(data, at + 1);
+                    at = end;
+
+                    if word == "bin" {
+                        // Raw binary data follows
+                        at = at.saturating_add(parameter.unwrap_or(0).max(0) as usize);
+                        continue;
+                    }
+
+                    if let Some(destination) = destination_for(word, state.destination) {
+                        state.destination = destination;
+                        ignorable = false;
+                        continue;
+                    }
+                    if std::mem::take(&mut ignorable) {
+                        // Unknown \* destination
+                        state.destination = Destination::Skip;
+                        continue;
+                    }
+
+                    match word {
+                        "uc" => state.unicode_skip = parameter.unwrap_or(1).max(0) as usize,
+                        "u" => {
+                            // Negative values are code units above 0x7FFF written as signed 16-bit
+                            let code = parameter.unwrap_or(0);
+                            let code = if code < 0 { code + 0x10000 } else { code };
+                            emitted = Some(char::from_u32(code as u32).unwrap_or('\u{fffd}'));
+                            pending_skip = state.unicode_skip;
+                        }
+                        "par" | "line" | "sect" | "page" | "row" => emitted = Some('\n'),
+                        "tab" | "cell" => emitted = Some('\t'),
+                        "emdash" => emitted = Some('—'),
+                        "endash" => emitted = Some('–'),
+                        "lquote" => emitted = Some('‘'),
+                        "rquote" => emitted = Some('’'),
+                        "ldblquote" => emitted = Some('“'),
+                        "rdblquote" => emitted = Some('”'),
+                        "bullet" => emitted = Some('•'),
+                        "nofpages" if state.destination == Destination::Info => {
+                            metadata.page_count = parameter.and_then(|n| u32::try_from(n).ok());
+                        }
+                        _ => {}
+                    }
+                } else {
+                    at += 2;
+                    match next {
+                        b'\'' => {
+                            let hex = data.get(at..at + 2).and_then(|h| std::str::from_utf8(h).ok());
+                            if let Some(byte) = hex.and_then(|h| u8::from_str_radix(h, 16).ok()) {
+                                at += 2;
+                                if pending_skip > 0 {
+                                    pending_skip -= 1;
+                                } else {
+                                    emitted = decode_cp1252(&[byte]).chars().next();
+                                }
+                            }
+                        }
+                        b'*' => ignorable = true,
+                        b'~' => emitted = Some(' '),
+                        b'_' => emitted = Some('-'),
+                        b'\\' | b'{' | b'}' => emitted = Some(next as char),
+                        // Escaped line ends are paragraph breaks
+                        b'\r' | b'\n' => emitted = Some('\n'),
+                        // Optional hyphen, formula character, etc.
+                        _ => {}
+                    }
+                }
+            }
+            // Raw line ends are formatting only
+            b'\r' | b'\n' => at += 1,
+            _ => {
+                // Plain text runs up to the next special character
+                let end = data[at..]
+                    .iter()
+                    .position(|b| matches!(b, b'{' | b'}' | b'\\' | b'\r' | b'\n'))
+                    .map_or(data.len(), |p| at + p);
+                let mut run = decode_cp1252(&data[at..end]);
+                at = end;
+                if pending_skip > 0 {
+                    let skip = pending_skip.min(run.chars().count());
+                    pending_skip -= skip;
+                    run = run.chars().skip(skip).collect();
+                }
+                push_text(state.destination, &run, &mut body, &mut body_chars, &mut info_text);
+            }
+        }
+
+        if let Some(c) = emitted {
+            let mut utf8 = [0u8; 4];
+            push_text(state.destination, c.encode_utf8(&mut utf8), &mut body, &mut body_chars, &mut info_text);
+        }
+
+        if body_chars > max_chars && state.destination == Destination::Body {
+            break;
+        }
+    }
+
+    (body, metadata)
+}
+
+/// Control word name, numeric parameter and the index after it (and its delimiter space)
+fn read_control_word(data: &[u8], start: usize) -> (&str, Option<i64>, usize) {
+    let mut at = start;
+    while at < data.len() && data[at].is_ascii_alphabetic() {
+        at += 1;
+    }
+    let word = std::str::from_utf8(&data[start..at]).unwrap_or_default();
+
+    let number_start = at;
+    if data.get(at) == Some(&b'-') {
+        at += 1;
+    }
+    while at < data.len() && data[at].is_ascii_digit() {
+        at += 1;
+    }
+    let parameter = std::str::from_utf8(&data[number_start..at])
+        .ok()
+        .and_then(|n| n.parse().ok());
+
+    if data.get(at) == Some(&b' ') {
+        at += 1;
+    }
+    (word, parameter, at)
+}
+
+/// Destination a control word switches the current group to, if it is one
+fn destination_for(word: &str, current: Destination) -> Option<Destination> {
+    if current == Destination::Skip {
+        return None;
+    }
+    if SKIPPED_DESTINATIONS.contains(&word) {
+        return Some(Destination::Skip);
+    }
+    match (word, current) {
+        ("info", _) => Some(Destination::Info),
+        ("title", Destination::Info) => Some(Destination::Title),
+        ("author", Destination::Info) => Some(Destination::Author),
+        ("subject", Destination::Info) => Some(Destination::Subject),
+        ("keywords", Destination::Info) => Some(Destination::Keywords),
+        // Dates, operator, company and the like
+        (_, Destination::Info) if word != "nofpages" && word != "nofwords" => Some(Destination::Skip),
+        _ => None,
+    }
+}
+
+fn push_text(destination: Destination, text: &str, body: &mut String, body_chars: &mut usize, info_text: &mut String) {
+    match destination {
+        Destination::Body => {
+            body.push_str(text);
+            *body_chars += text.chars().count();
+        }
+        Destination::Title | Destination::Author | Destination::Subject | Destination::Keywords => {
+            info_text.push_str(text);
+        }
+        Destination::Skip | Destination::Info => {}
+    }
+}
+
+/// Move the text collected for an \info field into the metadata
+fn store_info_field(field: Destination, info_text: &mut String, metadata: &mut DocumentMetadata) {
+    let text = std::mem::take(info_text).trim().to_string();
+    if text.is_empty() {
+        return;
+    }
+    match field {
+        Destination::Title => metadata.title = Some(text),
+        Destination::Author => metadata.author = Some(text),
+        Destination::Subject => metadata.subject = Some(text),
+        Destination::Keywords => {
+            metadata.keywords = text.split(',').map(|s| s.trim().to_string()).collect();
+        }
+        _ => {}
+    }
+}
+
+/// Trim lines, drop empty ones and truncate (char-safe for multi-byte UTF-8)
+fn clean_text(text: &str, max_chars: usize) -> String {
+    let content = text
+        .lines()
+        .map(|l| l.split_whitespace().collect::<Vec<_>>().join(" "))
+        .filter(|l| !l.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    if content.chars().count() > max_chars {
+        content.chars().take(max_chars).collect::<String>()
+    } else {
+        content
+    }
+}
+
+/// Calculate confidence from the amount of text and how much of it is letters
+/// Clean RTF text is reliable; a low letter ratio means leftover control data.
+fn calculate_extraction_confidence(content: &str, word_count: u32) -> f64 {
+    let base: f64 = if word_count > 100 {
+        0.95
+    } else if word_count > 20 {
+        0.85
+    } else if word_count > 5 {
+        0.70
+    } else if word_count > 0 {
+        0.50
+    } else {
+        return 0.10;
+    };
+
+    let total_chars = content.chars().filter(|c| !c.is_whitespace()).count();
+    let alpha_chars = content.chars().filter(|c| c.is_alphabetic()).count();
+    let alpha_ratio = alpha_chars as f64 / total_chars.max(1) as f64;
+
+    // Prose is mostly letters; below half, scale confidence down
+    let penalty = if alpha_ratio >= 0.5 { 1.0 } else { 0.5 + alpha_ratio };
+    (base * penalty).clamp(0.10, 0.95)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(rtf: &str) -> (String, DocumentMetadata) {
+        let (text, metadata) = parse_rtf(rtf.as_bytes(), 2000);
+        (clean_text(&text, 2000), metadata)
+    }
+
+    #[test]
+    fn test_strips_tables_and_decodes_escapes() {
+        let (text, metadata) = parse(
+            r#"{\rtf1\ansi\ansicpg1252\deff0{\fonttbl{\f0\froman Times New Roman;}}{\colortbl;\red0\green0\blue0;}
+{\info{\title Settlement Agreement}{\author J. Smith}{\creatim\yr2001\mo3}}
+{\*\generator Riched20;}{\*\unknowndest hidden}\pard\f0\fs24 Caf\'e9 \b Agreement\b0\par
+Clause 1 \endash  Na\u239?ve parties pay \u8364?100.\par
+{\field{\*\fldinst HYPERLINK "x"}{\fldrslt Exhibit A}}\tab done\par
+{\pict\pngblip 89504e47}}"#,
+        );
+
+        assert_eq!(
+            text,
+            "Café Agreement\nClause 1 – Naïve parties pay €100.\nExhibit A done"
+        );
+        assert_eq!(metadata.title.as_deref(), Some("Settlement Agreement"));
+        assert_eq!(metadata.author.as_deref(), Some("J. Smith"));
+    }
+
+    #[test]
+    fn test_unicode_skip_count_and_binary() {
+        let (text, _) = parse("{\\rtf1{\\uc2\\u26085\\'93\\'fa\\u26412\\'96\\'7b} text\\bin3 {}x\\par end}");
+        assert_eq!(text, "日本 text\nend");
+    }
+
+    #[test]
+    fn test_confidence_calibration() {
+        assert!(calculate_extraction_confidence("", 0) < 0.2);
+        let prose = "The parties agree that the tenant shall pay rent on the first day of each month. ".repeat(2);
+        let prose_confidence = calculate_extraction_confidence(&prose, prose.split_whitespace().count() as u32);
+        let junk = "0x1f 0x2e 0x33 12;34;56 78;90;12 ;;; ### 1 2 3 4 5 6";
+        let junk_confidence = calculate_extraction_confidence(junk, junk.split_whitespace().count() as u32);
+        assert!(prose_confidence > 0.8);
+        assert!(junk_confidence < prose_confidence);
+    }
+}
@@ -5,7 +5,7 @@
 
 use super::{DocumentMetadata, ParseError, ParsedDocument};
 use std::fs::File;
-use std::io::{BufRead, BufReader, Read};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom};
 use std::path::Path;
 
 /// Extract text content from a plain text file
@@ -77,14 +77,10 @@ fn read_with_encoding_detection(
         ));
     }
 
-    // Reset reader and skip BOM if present
-    let file = reader.get_ref().try_clone().map_err(|e| ParseError::ReadError(e.to_string()))?;
-    let mut reader = BufReader::new(file);
-
-    if skip_bytes > 0 {
-        let mut skip = vec![0u8; skip_bytes];
-        reader.read_exact(&mut skip).ok();
-    }
+    // Rewind to just past the BOM (if any) before reading content
+    reader
+        .seek(SeekFrom::Start(skip_bytes))
+        .map_err(|e| ParseError::ReadError(e.to_string()))?;
 
     // Read content line by line, respecting max_chars
     let mut content = String::new();
@@ -93,16 +89,18 @@ fn read_with_encoding_detection(
     for line in reader.lines() {
         let line = line.map_err(|e| ParseError::EncodingError(e.to_string()))?;
 
-        if total_chars + line.len() > max_chars {
-            // Take partial line to reach max_chars
+        let line_chars = line.chars().count();
+
+        if total_chars + line_chars > max_chars {
+            // Take partial line to reach max_chars (char-safe for multi-byte UTF-8)
             let remaining = max_chars - total_chars;
-            content.push_str(&line[..remaining.min(line.len())]);
+            content.extend(line.chars().take(remaining));
             break;
         }
 
         content.push_str(&line);
         content.push('\n');
-        total_chars += line.len() + 1;
+        total_chars += line_chars + 1;
 
         if total_chars >= max_chars {
             break;
@@ -0,0 +1,464 @@
+//! XLSX Parser (Group 2 - Office Open XML)
+//!
+//! Handles: .xlsx files
+//! Strategy: Unzip → read xl/sharedStrings.xml + xl/workbook.xml → sample the
+//! first rows of each xl/worksheets/sheet*.xml
+
+use super::{spreadsheet_confidence, DocumentMetadata, ParseError, ParsedDocument, MAX_COLUMNS, SAMPLE_ROWS_PER_SHEET};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::{BufReader, Read};
+use std::path::Path;
+use zip::ZipArchive;
+
+/// Shared strings loaded; cells referring past this are skipped
+const MAX_SHARED_STRINGS: usize = 100_000;
+
+/// A worksheet in workbook order
+struct SheetEntry {
+    name: String,
+    /// Path of the sheet XML inside the archive
+    part: String,
+}
+
+/// Extract text content from an XLSX file
+pub fn extract_xlsx(path: &Path, max_chars: usize) -> Result<ParsedDocument, ParseError> {
+    let file = File::open(path).map_err(|e| {
+        if e.kind() == std::io::ErrorKind::NotFound {
+            ParseError::NotFound(path.to_string_lossy().to_string())
+        } else {
+            ParseError::ReadError(e.to_string())
+        }
+    })?;
+
+    let mut archive = ZipArchive::new(file)
+        .map_err(|e| ParseError::ParseError(format!("Invalid XLSX file (not a valid ZIP): {}", e)))?;
+
+    // Extract metadata from docProps/core.xml
+    let metadata = extract_metadata(&mut archive).unwrap_or_default();
+
+    let sheets = list_sheets(&mut archive);
+    if sheets.is_empty() {
+        return Err(ParseError::ParseError("XLSX file has no worksheets".to_string()));
+    }
+
+    // Sample rows from every sheet
+    let shared_strings = read_shared_strings(&mut archive);
+    let content = extract_sheets_text(&mut archive, &sheets, &shared_strings, max_chars);
+
+    let word_count = content.split_whitespace().count() as u32;
+    let confidence = spreadsheet_confidence(word_count);
+
+    Ok(ParsedDocument {
+        content,
+        metadata: DocumentMetadata {
+            word_count: Some(word_count),
+            page_count: Some(sheets.len() as u32),
+            ..metadata
+        },
+        extraction_confidence: confidence,
+    })
+}
+
+/// Read a whole XML part from the archive
+fn read_part(archive: &mut ZipArchive<File>, name: &str) -> Option<String> {
+    let mut part = archive.by_name(name).ok()?;
+    let mut xml_content = String::new();
+    part.read_to_string(&mut xml_content).ok()?;
+    Some(xml_content)
+}
+
+/// Extract metadata from docProps/core.xml (same structure as DOCX)
+fn extract_metadata(archive: &mut ZipArchive<File>) -> Result<DocumentMetadata, ParseError> {
+    let mut metadata = DocumentMetadata::default();
+
+    if let Some(xml_content) = read_part(archive, "docProps/core.xml") {
+        let mut reader = Reader::from_str(&xml_content);
+        reader.config_mut().trim_text(true);
+
+        let mut current_tag = String::new();
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) => {
+                    current_tag = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                }
+                Ok(Event::Text(e)) => {
+                    let text = e.unescape().unwrap_or_default().to_string();
+                    match current_tag.as_str() {
+                        "dc:title" | "title" => metadata.title = Some(text),
+                        "dc:creator" | "creator" => metadata.author = Some(text),
+                        "dc:subject" | "subject" => metadata.subject = Some(text),
+                        "cp:keywords" | "keywords" => {
+                            metadata.keywords = text.split(',').map(|s| s.trim().to_string()).collect();
+                        }
+                        _ => {}
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(_) => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+
+    Ok(metadata)
+}
+
+/// Sheets in workbook order, resolved through xl/_rels/workbook.xml.rels
+/// Falls back to the xl/worksheets/sheetN.xml parts when the workbook can't be read.
+fn list_sheets(archive: &mut ZipArchive<File>) -> Vec<SheetEntry> {
+    let targets = read_part(archive, "xl/_rels/workbook.xml.rels")
+        .map(|xml| read_relationships(&xml))
+        .unwrap_or_default();
+
+    let mut sheets = Vec::new();
+    if let Some(xml_content) = read_part(archive, "xl/workbook.xml") {
+        let mut reader = Reader::from_str(&xml_content);
+        let mut buf = Vec::new();
+
+        loop {
+            match reader.read_event_into(&mut buf) {
+                Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"sheet" => {
+                    let mut name = None;
+                    let mut rel_id = None;
+                    for attr in e.attributes().flatten() {
+                        let value = attr.unescape_value().unwrap_or_default().to_string();
+                        match attr.key.local_name().as_ref() {
+                            b"name" => name = Some(value),
+                            // r:id (the unprefixed sheetId is a different attribute)
+                            b"id" => rel_id = Some(value),
+                            _ => {}
+                        }
+                    }
+                    let part = rel_id.and_then(|id| targets.get(&id)).map(|target| resolve_target(target));
+                    if let (Some(name), Some(part)) = (name, part) {
+                        sheets.push(SheetEntry { name, part });
+                    }
+                }
+                Ok(Event::Eof) => break,
+                Err(_) => break,
+                _ => {}
+            }
+            buf.clear();
+        }
+    }
+
+    if sheets.is_empty() {
+        let mut parts: Vec<String> = archive
+            .file_names()
+            .filter(|name| name.starts_with("xl/worksheets/sheet") && name.ends_with(".xml"))
+            .map(|name| name.to_string())
+            .collect();
+        parts.sort_by_key(|name| extract_sheet_number(name));
+        sheets = parts
+            .into_iter()
+            .map(|part| SheetEntry {
+                name: format!("Sheet{}", extract_sheet_number(&part)),
+                part,
+            })
+            .collect();
+    }
+
+    sheets
+}
+
+/// Relationship id → target from a .rels part
+fn read_relationships(xml_content: &str) -> HashMap<String, String> {
+    let mut reader = Reader::from_str(xml_content);
+    let mut targets = HashMap::new();
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) | Ok(Event::Empty(e)) if e.local_name().as_ref() == b"Relationship" => {
+                let mut id = None;
+                let mut target = None;
+                for attr in e.attributes().flatten() {
+                    let value = attr.unescape_value().unwrap_or_default().to_string();
+                    match attr.key.as_ref() {
+                        b"Id" => id = Some(value),
+                        b"Target" => target = Some(value),
+                        _ => {}
+                    }
+                }
+                if let (Some(id), Some(target)) = (id, target) {
+                    targets.insert(id, target);
+                }
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    targets
+}
+
+/// Archive path of a workbook relationship target ("worksheets/sheet1.xml" or "/xl/worksheets/sheet1.xml")
+fn resolve_target(target: &str) -> String {
+    match target.strip_prefix('/') {
+        Some(absolute) => absolute.to_string(),
+        None => format!("xl/{}", target),
+    }
+}
+
+/// Extract sheet number from part name (e.g., "xl/worksheets/sheet3.xml" -> 3)
+fn extract_sheet_number(name: &str) -> u32 {
+    name.trim_start_matches("xl/worksheets/sheet")
+        .trim_end_matches(".xml")
+        .parse()
+        .unwrap_or(0)
+}
+
+/// Read xl/sharedStrings.xml, the table most text cells point into
+/// Rich-text runs are joined; phonetic hints (<rPh>) are skipped.
+fn read_shared_strings(archive: &mut ZipArchive<File>) -> Vec<String> {
+    let Some(xml_content) = read_part(archive, "xl/sharedStrings.xml") else {
+        return Vec::new();
+    };
+
+    // No trimming: runs like "Total " + "due" carry their own spaces
+    let mut reader = Reader::from_str(&xml_content);
+    let mut strings = Vec::new();
+    let mut current = String::new();
+    let mut in_text_element = false;
+    let mut in_phonetic = false;
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                b"si" => current.clear(),
+                b"t" => in_text_element = !in_phonetic,
+                b"rPh" => in_phonetic = true,
+                _ => {}
+            },
+            Ok(Event::Empty(e)) if e.local_name().as_ref() == b"si" => strings.push(String::new()),
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"si" => {
+                    strings.push(current.trim().to_string());
+                    if strings.len() >= MAX_SHARED_STRINGS {
+                        break;
+                    }
+                }
+                b"t" => in_text_element = false,
+                b"rPh" => in_phonetic = false,
+                _ => {}
+            },
+            Ok(Event::Text(e)) if in_text_element => {
+                current.push_str(&e.unescape().unwrap_or_default());
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    strings
+}
+
+/// Extract the header and sample rows of every sheet
+fn extract_sheets_text(
+    archive: &mut ZipArchive<File>,
+    sheets: &[SheetEntry],
+    shared_strings: &[String],
+    max_chars: usize,
+) -> String {
+    let mut content = String::new();
+
+    for sheet in sheets {
+        if content.chars().count() >= max_chars {
+            break;
+        }
+
+        if !content.is_empty() {
+            content.push_str("\n\n");
+        }
+        content.push_str(&format!("[Sheet: {}]", sheet.name));
+
+        let rows = sample_rows(archive, &sheet.part, shared_strings);
+        for (i, row) in rows.iter().enumerate() {
+            content.push('\n');
+            if i == 0 {
+                content.push_str("Header: ");
+            }
+            content.push_str(&row.join(" | "));
+        }
+    }
+
+    // Truncate if needed (char-safe for multi-byte UTF-8)
+    if content.chars().count() > max_chars {
+        content = content.chars().take(max_chars).collect::<String>();
+    }
+
+    content
+}
+
+/// Read the first non-empty rows of a sheet
+/// The sheet is streamed, so only the sampled rows of large sheets are parsed.
+fn sample_rows(archive: &mut ZipArchive<File>, part: &str, shared_strings: &[String]) -> Vec<Vec<String>> {
+    let Ok(sheet_file) = archive.by_name(part) else {
+        return Vec::new();
+    };
+
+    let mut reader = Reader::from_reader(BufReader::new(sheet_file));
+    let mut rows = Vec::new();
+    let mut row: Vec<String> = Vec::new();
+    let mut cell_type = String::new();
+    let mut cell_text = String::new();
+    let mut in_value = false;
+    let mut buf = Vec::new();
+
+    loop {
+        match reader.read_event_into(&mut buf) {
+            Ok(Event::Start(e)) => match e.local_name().as_ref() {
+                b"row" => row.clear(),
+                b"c" => {
+                    cell_type = e
+                        .attributes()
+                        .flatten()
+                        .find(|attr| attr.key.as_ref() == b"t")
+                        .map(|attr| String::from_utf8_lossy(&attr.value).to_string())
+                        .unwrap_or_default();
+                    cell_text.clear();
+                }
+                // <v> holds the value; inline strings keep theirs in <is><t>
+                b"v" | b"t" => in_value = true,
+                _ => {}
+            },
+            Ok(Event::End(e)) => match e.local_name().as_ref() {
+                b"v" | b"t" => in_value = false,
+                b"c" => {
+                    let value = match cell_type.as_str() {
+                        "s" => cell_text
+                            .trim()
+                            .parse::<usize>()
+                            .ok()
+                            .and_then(|i| shared_strings.get(i))
+                            .cloned()
+                            .unwrap_or_default(),
+                        "b" => if cell_text.trim() == "1" { "TRUE" } else { "FALSE" }.to_string(),
+                        // Error values (#DIV/0! etc.) say nothing about the content
+                        "e" => String::new(),
+                        _ => cell_text.trim().to_string(),
+                    };
+                    if !value.is_empty() && row.len() < MAX_COLUMNS {
+                        row.push(value);
+                    }
+                }
+                b"row" if !row.is_empty() => {
+                    rows.push(std::mem::take(&mut row));
+                    if rows.len() >= SAMPLE_ROWS_PER_SHEET {
+                        break;
+                    }
+                }
+                _ => {}
+            },
+            Ok(Event::Text(e)) if in_value => {
+                cell_text.push_str(&e.unescape().unwrap_or_default());
+            }
+            Ok(Event::Eof) => break,
+            Err(_) => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    rows
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+    use zip::ZipWriter;
+
+    fn write_xlsx(path: &Path, parts: &[(&str, &str)]) {
+        let mut zip = ZipWriter::new(File::create(path).unwrap());
+        for (name, xml) in parts {
+            zip.start_file(*name, SimpleFileOptions::default()).unwrap();
+            zip.write_all(xml.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn test_extract_sheets_headers_and_cells() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("budget.xlsx");
+        write_xlsx(
+            &path,
+            &[
+                (
+                    "xl/workbook.xml",
+                    r#"<workbook xmlns:r="http://schemas.openxmlformats.org/officeDocument/2006/relationships"><sheets>
+                        <sheet name="Budget 2024" sheetId="1" r:id="rId2"/>
+                        <sheet name="Notes" sheetId="2" r:id="rId1"/>
+                    </sheets></workbook>"#,
+                ),
+                (
+                    "xl/_rels/workbook.xml.rels",
+                    r#"<Relationships>
+                        <Relationship Id="rId1" Target="worksheets/sheet2.xml"/>
+                        <Relationship Id="rId2" Target="/xl/worksheets/sheet1.xml"/>
+                    </Relationships>"#,
+                ),
+                (
+                    "xl/sharedStrings.xml",
+                    r#"<sst><si><t>Category</t></si><si><t>Amount</t></si>
+                        <si><r><t>Rent </t></r><r><t>&amp; utilities</t></r><rPh><t>x</t></rPh></si></sst>"#,
+                ),
+                (
+                    "xl/worksheets/sheet1.xml",
+                    r#"<worksheet><sheetData>
+                        <row r="1"><c r="A1" t="s"><v>0</v></c><c r="B1" t="s"><v>1</v></c></row>
+                        <row r="2"/>
+                        <row r="3"><c r="A3" t="s"><v>2</v></c><c r="B3"><v>1200</v></c><c r="C3" t="e"><v>#DIV/0!</v></c></row>
+                    </sheetData></worksheet>"#,
+                ),
+                (
+                    "xl/worksheets/sheet2.xml",
+                    r#"<worksheet><sheetData>
+                        <row r="1"><c r="A1" t="inlineStr"><is><t>Paid on time</t></is></c><c r="B1" t="b"><v>1</v></c></row>
+                    </sheetData></worksheet>"#,
+                ),
+                (
+                    "docProps/core.xml",
+                    r#"<cp:coreProperties xmlns:cp="cp" xmlns:dc="dc"><dc:title>Household budget</dc:title></cp:coreProperties>"#,
+                ),
+            ],
+        );
+
+        let result = extract_xlsx(&path, 2000).unwrap();
+        assert_eq!(
+            result.content,
+            "[Sheet: Budget 2024]\nHeader: Category | Amount\nRent & utilities | 1200\n\n[Sheet: Notes]\nHeader: Paid on time | TRUE"
+        );
+        assert_eq!(result.metadata.title.as_deref(), Some("Household budget"));
+        assert_eq!(result.metadata.page_count, Some(2));
+    }
+
+    #[test]
+    fn test_sheets_found_without_workbook() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("export.xlsx");
+        write_xlsx(
+            &path,
+            &[(
+                "xl/worksheets/sheet1.xml",
+                r#"<worksheet><sheetData><row><c t="str"><v>Invoice 42</v></c></row></sheetData></worksheet>"#,
+            )],
+        );
+
+        let result = extract_xlsx(&path, 2000).unwrap();
+        assert_eq!(result.content, "[Sheet: Sheet1]\nHeader: Invoice 42");
+    }
+}
@@ -0,0 +1,120 @@
+//! XMP packet reader (shared by the image and PDF parsers)
+//!
+//! XMP is RDF/XML; simple properties may be written as attributes of
+//! rdf:Description or as child elements, and lists as rdf:Alt/Bag/Seq items.
+
+use quick_xml::events::{BytesStart, Event};
+use quick_xml::Reader;
+
+/// Dublin Core and date properties of an XMP packet
+#[derive(Debug, Default, PartialEq)]
+pub(super) struct XmpMetadata {
+    pub title: Option<String>,
+    pub creators: Vec<String>,
+    pub description: Option<String>,
+    pub keywords: Vec<String>,
+    /// When the content was created (photoshop:DateCreated, exif:DateTimeOriginal or xmp:CreateDate)
+    pub created: Option<String>,
+}
+
+/// Date properties, most specific first
+const DATE_PROPERTIES: &[&str] = &["photoshop:DateCreated", "exif:DateTimeOriginal", "xmp:CreateDate"];
+
+/// Read the properties we index from an XMP packet; unknown or malformed XML yields what was read so far
+pub(super) fn parse_xmp(xml: &str) -> XmpMetadata {
+    let mut xmp = XmpMetadata::default();
+    let mut dates: [Option<String>; 3] = Default::default();
+    let mut reader = Reader::from_str(xml);
+    reader.config_mut().trim_text(true);
+
+    // The property element the current text belongs to
+    let mut property: Option<String> = None;
+
+    loop {
+        match reader.read_event() {
+            Ok(Event::Start(e)) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).to_string();
+                if name == "rdf:Description" {
+                    read_attribute_properties(&e, &mut xmp, &mut dates);
+                } else if !name.starts_with("rdf:") {
+                    property = Some(name);
+                }
+            }
+            Ok(Event::Empty(e)) if e.name().as_ref() == b"rdf:Description" => {
+                read_attribute_properties(&e, &mut xmp, &mut dates);
+            }
+            Ok(Event::Text(e)) => {
+                let text = e.unescape().map(|t| t.trim().to_string()).unwrap_or_default();
+                if let (Some(property), false) = (&property, text.is_empty()) {
+                    set_property(property, text, &mut xmp, &mut dates);
+                }
+            }
+            Ok(Event::End(e)) if property.as_deref().is_some_and(|p| p.as_bytes() == e.name().as_ref()) => {
+                property = None;
+            }
+            Ok(Event::Eof) | Err(_) => break,
+            _ => {}
+        }
+    }
+
+    xmp.created = dates.into_iter().flatten().next();
+    xmp
+}
+
+/// Properties written as attributes: <rdf:Description xmp:CreateDate="...">
+fn read_attribute_properties(e: &BytesStart, xmp: &mut XmpMetadata, dates: &mut [Option<String>; 3]) {
+    for attr in e.attributes().flatten() {
+        let name = String::from_utf8_lossy(attr.key.as_ref()).to_string();
+        if let Ok(value) = attr.unescape_value() {
+            let value = value.trim();
+            if !value.is_empty() {
+                set_property(&name, value.to_string(), xmp, dates);
+            }
+        }
+    }
+}
+
+fn set_property(name: &str, value: String, xmp: &mut XmpMetadata, dates: &mut [Option<String>; 3]) {
+    match name {
+        // rdf:Alt lists hold one entry per language; the first is the default
+        "dc:title" => {
+            xmp.title.get_or_insert(value);
+        }
+        "dc:description" => {
+            xmp.description.get_or_insert(value);
+        }
+        "dc:creator" => xmp.creators.push(value),
+        "dc:subject" => xmp.keywords.push(value),
+        _ => {
+            if let Some(i) = DATE_PROPERTIES.iter().position(|p| *p == name) {
+                dates[i].get_or_insert(value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_xmp_elements_and_attributes() {
+        let xml = r#"<?xpacket begin="" id="W5M0MpCehiHzreSzNTczkc9d"?>
+            <x:xmpmeta xmlns:x="adobe:ns:meta/"><rdf:RDF xmlns:rdf="http://www.w3.org/1999/02/22-rdf-syntax-ns#">
+            <rdf:Description rdf:about="" xmp:CreateDate="2023-07-14T18:32:05+01:00" xmp:CreatorTool="Lightroom"/>
+            <rdf:Description rdf:about="">
+                <dc:title><rdf:Alt><rdf:li xml:lang="x-default">Harbour at dusk</rdf:li><rdf:li xml:lang="pt">Porto</rdf:li></rdf:Alt></dc:title>
+                <dc:subject><rdf:Bag><rdf:li>travel</rdf:li><rdf:li>lisbon</rdf:li></rdf:Bag></dc:subject>
+                <dc:creator><rdf:Seq><rdf:li>Ana Reis</rdf:li></rdf:Seq></dc:creator>
+                <photoshop:DateCreated>2023-07-14</photoshop:DateCreated>
+            </rdf:Description>
+            </rdf:RDF></x:xmpmeta><?xpacket end="w"?>"#;
+
+        let xmp = parse_xmp(xml);
+        assert_eq!(xmp.title.as_deref(), Some("Harbour at dusk"));
+        assert_eq!(xmp.keywords, vec!["travel", "lisbon"]);
+        assert_eq!(xmp.creators, vec!["Ana Reis"]);
+        // photoshop:DateCreated is preferred over xmp:CreateDate
+        assert_eq!(xmp.created.as_deref(), Some("2023-07-14"));
+    }
+}
@@ -0,0 +1,458 @@
+//! Duplicates Module
+//!
+//! Content-based duplicate detection and resolution.
+//! Candidates share a size and the scan-time quick_hash (which only covers
+//! the ends of the file), so every candidate is confirmed with a full-file
+//! hash before it is grouped. Full hashes are computed lazily and cached in
+//! files.full_hash until the next scan sees the file change. A keep-policy picks one copy per group; the rest
+//! are planned as moves into `Review/Duplicates` and saved as a regular
+//! organization plan, so execution goes through `execute_plan` and the
+//! activity log and can be undone like any other move.
+
+use crate::scanner;
+use rusqlite::{params, Connection, Result as SqlResult};
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+/// organization_style value stored for dedupe plans
+pub const DEDUPE_PLAN_STYLE: &str = "dedupe";
+
+/// Which copy of a duplicate group stays in place
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum KeepPolicy {
+    Oldest,
+    Newest,
+    ShortestPath,
+    InOrganizedFiles,
+}
+
+impl KeepPolicy {
+    pub fn label(&self) -> &'static str {
+        match self {
+            KeepPolicy::Oldest => "oldest copy",
+            KeepPolicy::Newest => "newest copy",
+            KeepPolicy::ShortestPath => "shortest path",
+            KeepPolicy::InOrganizedFiles => "copy in Organized Files",
+        }
+    }
+}
+
+/// A file that belongs to a duplicate group
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateFile {
+    pub id: i64,
+    pub path: String,
+    pub filename: String,
+    pub created_at: Option<String>,
+    pub modified_at: Option<String>,
+}
+
+/// Files with identical size and full content hash
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateGroup {
+    pub full_hash: String,
+    pub size: i64,
+    pub files: Vec<DuplicateFile>,
+}
+
+/// A planned move of a redundant copy into the duplicates folder
+#[derive(Debug, Clone, Serialize)]
+pub struct DuplicateMove {
+    pub file: DuplicateFile,
+    pub destination_path: String,
+}
+
+/// Resolution of one duplicate group under a keep-policy
+#[derive(Debug, Clone, Serialize)]
+pub struct DedupeGroupPlan {
+    pub full_hash: String,
+    pub size: i64,
+    pub keep: DuplicateFile,
+    pub moves: Vec<DuplicateMove>,
+}
+
+/// Summary statistics for a dedupe plan
+#[derive(Debug, Clone, Serialize)]
+pub struct DedupeSummary {
+    pub duplicate_groups: usize,
+    pub files_to_move: usize,
+    pub bytes_reclaimable: i64,
+}
+
+/// Reviewable dedupe plan, executed with `execute_plan`
+#[derive(Debug, Clone, Serialize)]
+pub struct DedupePlan {
+    pub id: String,
+    pub name: String,
+    pub policy: KeepPolicy,
+    pub destination_folder: String,
+    pub groups: Vec<DedupeGroupPlan>,
+    pub summary: DedupeSummary,
+}
+
+/// A candidate file with its cached full hash (if any)
+type CandidateFile = (DuplicateFile, Option<String>);
+
+/// Folder that redundant copies are moved into (per ORGANIZATION_SYSTEM.md)
+pub fn duplicates_folder(organize_base: &Path) -> PathBuf {
+    organize_base.join("Review").join("Duplicates")
+}
+
+/// Find duplicate groups, confirming size + quick hash candidates with a full hash.
+/// Empty files all share one hash, so they are never reported.
+/// Files that can no longer be read are left out.
+pub fn find_duplicate_groups(conn: &Connection) -> SqlResult<Vec<DuplicateGroup>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, path, filename, size, quick_hash, created_at, modified_at, full_hash
+         FROM files
+         WHERE quick_hash IS NOT NULL
+           AND size > 0
+           AND (size, quick_hash) IN (
+               SELECT size, quick_hash FROM files
+               WHERE quick_hash IS NOT NULL AND size > 0
+               GROUP BY size, quick_hash
+               HAVING COUNT(*) > 1
+           )
+         ORDER BY size DESC, quick_hash, path",
+    )?;
+
+    let rows = stmt.query_map([], |row| {
+        Ok((
+            row.get::<_, i64>(3)?,
+            row.get::<_, String>(4)?,
+            row.get::<_, Option<String>>(7)?,
+            DuplicateFile {
+                id: row.get(0)?,
+                path: row.get(1)?,
+                filename: row.get(2)?,
+                created_at: row.get(5)?,
+                modified_at: row.get(6)?,
+            },
+        ))
+    })?;
+
+    // Candidates arrive ordered, so consecutive rows with the same key form a group
+    let mut candidates: Vec<(i64, Vec<CandidateFile>)> = Vec::new();
+    let mut current_key: Option<(i64, String)> = None;
+    for row in rows {
+        let (size, quick_hash, cached_full_hash, file) = row?;
+        let key = (size, quick_hash);
+        if current_key.as_ref() != Some(&key) {
+            candidates.push((size, Vec::new()));
+            current_key = Some(key);
+        }
+        if let Some((_, files)) = candidates.last_mut() {
+            files.push((file, cached_full_hash));
+        }
+    }
+
+    let mut groups = Vec::new();
+    for (size, files) in candidates {
+        let mut by_full_hash: HashMap<String, Vec<DuplicateFile>> = HashMap::new();
+        for (file, cached_full_hash) in files {
+            if let Some(full_hash) = full_hash_for(conn, &file, cached_full_hash)? {
+                by_full_hash.entry(full_hash).or_default().push(file);
+            }
+        }
+
+        let mut confirmed: Vec<DuplicateGroup> = by_full_hash
+            .into_iter()
+            .filter(|(_, files)| files.len() > 1)
+            .map(|(full_hash, files)| DuplicateGroup { full_hash, size, files })
+            .collect();
+        confirmed.sort_by(|a, b| a.files[0].path.cmp(&b.files[0].path));
+        groups.extend(confirmed);
+    }
+
+    Ok(groups)
+}
+
+/// Return the cached full hash, or stream the file and cache the result.
+/// None means the file could not be read.
+fn full_hash_for(conn: &Connection, file: &DuplicateFile, cached: Option<String>) -> SqlResult<Option<String>> {
+    if cached.is_some() {
+        return Ok(cached);
+    }
+
+    let full_hash = scanner::compute_full_hash(Path::new(&file.path));
+    if let Some(hash) = &full_hash {
+        conn.execute("UPDATE files SET full_hash = ?1 WHERE id = ?2", params![hash, file.id])?;
+    }
+    Ok(full_hash)
+}
+
+/// Timestamp used for age comparisons: creation time, falling back to modification time
+fn file_time(file: &DuplicateFile) -> Option<&str> {
+    file.created_at.as_deref().or(file.modified_at.as_deref())
+}
+
+/// Compare timestamps (ascending, or descending when `newest_first`) with missing values sorted last
+fn compare_times(a: Option<&str>, b: Option<&str>, newest_first: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if newest_first => b.cmp(a),
+        (Some(a), Some(b)) => a.cmp(b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn is_in_organized_files(path: &str) -> bool {
+    path.to_lowercase().contains("organized files")
+}
+
+/// Pick the copy to keep. Copies already parked in the duplicates folder are
+/// only kept when nothing else is left; ties fall back to shortest path, then path order.
+pub fn choose_keeper(files: &[DuplicateFile], policy: KeepPolicy, duplicates_dir: &Path) -> usize {
+    let by_length = |a: &DuplicateFile, b: &DuplicateFile| {
+        a.path.chars().count().cmp(&b.path.chars().count()).then_with(|| a.path.cmp(&b.path))
+    };
+
+    (0..files.len())
+        .min_by(|&i, &j| {
+            let (a, b) = (&files[i], &files[j]);
+            let parked_a = Path::new(&a.path).starts_with(duplicates_dir);
+            let parked_b = Path::new(&b.path).starts_with(duplicates_dir);
+
+            parked_a.cmp(&parked_b).then_with(|| match policy {
+                KeepPolicy::Oldest => compare_times(file_time(a), file_time(b), false)
+                    .then_with(|| by_length(a, b)),
+                KeepPolicy::Newest => compare_times(file_time(a), file_time(b), true)
+                    .then_with(|| by_length(a, b)),
+                KeepPolicy::ShortestPath => by_length(a, b)
+                    .then_with(|| compare_times(file_time(a), file_time(b), false)),
+                KeepPolicy::InOrganizedFiles => is_in_organized_files(&b.path)
+                    .cmp(&is_in_organized_files(&a.path))
+                    .then_with(|| compare_times(file_time(a), file_time(b), false))
+                    .then_with(|| by_length(a, b)),
+            })
+        })
+        .unwrap_or(0)
+}
+
+/// Resolve one group: keep a single copy and move the others into the duplicates folder.
+/// Copies that are already in the duplicates folder stay where they are.
+pub fn plan_group(group: &DuplicateGroup, policy: KeepPolicy, organize_base: &Path) -> DedupeGroupPlan {
+    let duplicates_dir = duplicates_folder(organize_base);
+    let keep_index = choose_keeper(&group.files, policy, &duplicates_dir);
+
+    let moves = group
+        .files
+        .iter()
+        .enumerate()
+        .filter(|(i, file)| *i != keep_index && !Path::new(&file.path).starts_with(&duplicates_dir))
+        .map(|(_, file)| DuplicateMove {
+            file: file.clone(),
+            destination_path: duplicates_dir.join(&file.filename).to_string_lossy().to_string(),
+        })
+        .collect();
+
+    DedupeGroupPlan {
+        full_hash: group.full_hash.clone(),
+        size: group.size,
+        keep: group.files[keep_index].clone(),
+        moves,
+    }
+}
+
+/// Build a dedupe plan and save it to organization_plans / plan_items
+pub fn create_dedupe_plan(conn: &Connection, policy: KeepPolicy, organize_base: &Path) -> SqlResult<DedupePlan> {
+    let groups: Vec<DedupeGroupPlan> = find_duplicate_groups(conn)?
+        .iter()
+        .map(|group| plan_group(group, policy, organize_base))
+        .filter(|plan| !plan.moves.is_empty())
+        .collect();
+
+    let plan_id = uuid::Uuid::new_v4().to_string();
+    let name = format!("Duplicate Cleanup (keep {})", policy.label());
+
+    conn.execute(
+        "INSERT INTO organization_plans (id, name, organization_style, status) VALUES (?1, ?2, ?3, 'pending')",
+        params![&plan_id, &name, DEDUPE_PLAN_STYLE],
+    )?;
+
+    for group in &groups {
+        let reason = format!("Duplicate of {}", group.keep.path);
+        for item in &group.moves {
+            conn.execute(
+                "INSERT INTO plan_items (plan_id, file_id, source_path, destination_path, confidence, reason, requires_review)
+                 VALUES (?1, ?2, ?3, ?4, 1.0, ?5, 0)",
+                params![&plan_id, item.file.id, &item.file.path, &item.destination_path, &reason],
+            )?;
+        }
+    }
+
+    let files_to_move = groups.iter().map(|g| g.moves.len()).sum();
+    let bytes_reclaimable = groups.iter().map(|g| g.size * g.moves.len() as i64).sum();
+
+    Ok(DedupePlan {
+        id: plan_id,
+        name,
+        policy,
+        destination_folder: duplicates_folder(organize_base).to_string_lossy().to_string(),
+        summary: DedupeSummary {
+            duplicate_groups: groups.len(),
+            files_to_move,
+            bytes_reclaimable,
+        },
+        groups,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    fn setup_test_db() -> (tempfile::TempDir, Connection) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.db");
+        db::init_database(&path).unwrap();
+        let conn = db::open_connection(&path).unwrap();
+        (dir, conn)
+    }
+
+    fn insert_file(conn: &Connection, path: &Path, quick_hash: &str, created_at: &str) -> i64 {
+        let size = std::fs::metadata(path).unwrap().len() as i64;
+        conn.execute(
+            "INSERT INTO files (path, filename, extension, size, quick_hash, created_at)
+             VALUES (?1, ?2, 'bin', ?3, ?4, ?5)",
+            params![
+                path.to_string_lossy(),
+                path.file_name().unwrap().to_string_lossy(),
+                size,
+                quick_hash,
+                created_at
+            ],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    fn dup(path: &str, created_at: Option<&str>) -> DuplicateFile {
+        DuplicateFile {
+            id: 0,
+            path: path.to_string(),
+            filename: Path::new(path).file_name().unwrap().to_string_lossy().to_string(),
+            created_at: created_at.map(String::from),
+            modified_at: None,
+        }
+    }
+
+    #[test]
+    fn test_groups_require_matching_full_hash() {
+        let (dir, conn) = setup_test_db();
+
+        // Same size and same quick hash, different middle: not duplicates
+        let mut data = vec![7u8; 512 * 1024];
+        let a = dir.path().join("a.bin");
+        std::fs::write(&a, &data).unwrap();
+        let b = dir.path().join("renamed copy.bin");
+        std::fs::write(&b, &data).unwrap();
+        data[256 * 1024] = 8;
+        let c = dir.path().join("c.bin");
+        std::fs::write(&c, &data).unwrap();
+
+        let a_id = insert_file(&conn, &a, "quick", "2024-01-01T00:00:00+00:00");
+        let b_id = insert_file(&conn, &b, "quick", "2024-02-01T00:00:00+00:00");
+        insert_file(&conn, &c, "quick", "2024-03-01T00:00:00+00:00");
+
+        let groups = find_duplicate_groups(&conn).unwrap();
+        assert_eq!(groups.len(), 1);
+        let ids: Vec<i64> = groups[0].files.iter().map(|f| f.id).collect();
+        assert_eq!(ids, vec![a_id, b_id]);
+        assert_eq!(groups[0].full_hash, scanner::compute_full_hash(&a).unwrap());
+
+        // Full hashes are cached for every candidate that was read
+        let cached: i64 = conn
+            .query_row("SELECT COUNT(*) FROM files WHERE full_hash IS NOT NULL", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(cached, 3);
+    }
+
+    #[test]
+    fn test_empty_files_are_not_duplicates() {
+        let (dir, conn) = setup_test_db();
+        let a = dir.path().join("empty.txt");
+        std::fs::write(&a, "").unwrap();
+        let b = dir.path().join("placeholder.log");
+        std::fs::write(&b, "").unwrap();
+        let hash = scanner::compute_quick_hash(&a, 0).unwrap();
+        insert_file(&conn, &a, &hash, "2024-01-01T00:00:00+00:00");
+        insert_file(&conn, &b, &hash, "2024-02-01T00:00:00+00:00");
+
+        assert!(find_duplicate_groups(&conn).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_keep_policies() {
+        let dup_dir = Path::new("/home/u/Documents/Organized Files/Review/Duplicates");
+        let files = vec![
+            dup("/home/u/Downloads/nested/deeper/report.pdf", Some("2023-01-01T00:00:00+00:00")),
+            dup("/home/u/Desktop/report.pdf", Some("2024-06-01T00:00:00+00:00")),
+            dup("/home/u/Documents/Organized Files/Work/report.pdf", Some("2024-01-01T00:00:00+00:00")),
+            dup("/home/u/Documents/Organized Files/Review/Duplicates/r.pdf", Some("2020-01-01T00:00:00+00:00")),
+        ];
+
+        assert_eq!(choose_keeper(&files, KeepPolicy::Oldest, dup_dir), 0);
+        assert_eq!(choose_keeper(&files, KeepPolicy::Newest, dup_dir), 1);
+        assert_eq!(choose_keeper(&files, KeepPolicy::ShortestPath, dup_dir), 1);
+        assert_eq!(choose_keeper(&files, KeepPolicy::InOrganizedFiles, dup_dir), 2);
+    }
+
+    #[test]
+    fn test_plan_moves_extra_copies_to_review() {
+        let base = Path::new("/home/u/Documents/Organized Files");
+        let group = DuplicateGroup {
+            full_hash: "abc".to_string(),
+            size: 10,
+            files: vec![
+                dup("/home/u/Desktop/scan.png", Some("2024-01-01T00:00:00+00:00")),
+                dup("/home/u/Downloads/scan (1).png", Some("2024-02-01T00:00:00+00:00")),
+                dup("/home/u/Documents/Organized Files/Review/Duplicates/scan.png", None),
+            ],
+        };
+
+        let plan = plan_group(&group, KeepPolicy::Oldest, base);
+        assert_eq!(plan.keep.path, "/home/u/Desktop/scan.png");
+        assert_eq!(plan.moves.len(), 1);
+        assert_eq!(plan.moves[0].file.path, "/home/u/Downloads/scan (1).png");
+        assert_eq!(
+            Path::new(&plan.moves[0].destination_path),
+            duplicates_folder(base).join("scan (1).png")
+        );
+    }
+
+    #[test]
+    fn test_create_dedupe_plan_saves_pending_items() {
+        let (dir, conn) = setup_test_db();
+        let a = dir.path().join("a.txt");
+        let b = dir.path().join("b.txt");
+        std::fs::write(&a, "same content").unwrap();
+        std::fs::write(&b, "same content").unwrap();
+        insert_file(&conn, &a, "h", "2024-01-01T00:00:00+00:00");
+        let b_id = insert_file(&conn, &b, "h", "2024-02-01T00:00:00+00:00");
+
+        let base = dir.path().join("Organized Files");
+        let plan = create_dedupe_plan(&conn, KeepPolicy::Oldest, &base).unwrap();
+        assert_eq!(plan.summary.duplicate_groups, 1);
+        assert_eq!(plan.summary.files_to_move, 1);
+        assert_eq!(plan.summary.bytes_reclaimable, 12);
+
+        let (file_id, status, style): (i64, String, String) = conn
+            .query_row(
+                "SELECT pi.file_id, pi.status, p.organization_style
+                 FROM plan_items pi JOIN organization_plans p ON p.id = pi.plan_id
+                 WHERE pi.plan_id = ?1",
+                params![&plan.id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .unwrap();
+        assert_eq!(file_id, b_id);
+        assert_eq!(status, "pending");
+        assert_eq!(style, DEDUPE_PLAN_STYLE);
+    }
+}
@@ -0,0 +1,199 @@
+//! Ignore Rules Module
+//!
+//! Gitignore-style scan exclusions.
+//! - Global rules apply under every scan root. They start from `DEFAULT_IGNORE_PATTERNS`
+//!   and can be edited by the user.
+//! - A `.filesenseignore` file in any folder adds rules for that folder and
+//!   below. Its patterns are relative to that folder, and deeper files can
+//!   re-include paths with `!pattern`.
+//!
+//! Used by both the scanner and the quick file count, so estimates match what
+//! is actually indexed.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use ignore::Match;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+/// Per-folder ignore file name
+pub const IGNORE_FILE_NAME: &str = ".filesenseignore";
+
+/// Global rules used until the user saves their own
+/// VCS metadata, dependency folders, build outputs and temporary files
+pub const DEFAULT_IGNORE_PATTERNS: &[&str] = &[
+    ".git/",
+    ".svn/",
+    ".hg/",
+    "node_modules/",
+    "__pycache__/",
+    ".venv/",
+    "target/",
+    "build/",
+    "dist/",
+    "obj/",
+    "*.tmp",
+    "*.temp",
+    "*.crdownload",
+    "*.part",
+    "~$*",
+];
+
+pub fn default_ignore_patterns() -> Vec<String> {
+    DEFAULT_IGNORE_PATTERNS.iter().map(|p| p.to_string()).collect()
+}
+
+/// Compiled ignore rules for a set of scan roots
+/// Per-folder files are read on first use and cached, so build a fresh
+/// `IgnoreRules` for each scan to pick up edits.
+pub struct IgnoreRules {
+    roots: Vec<PathBuf>,
+    global: Gitignore,
+    folders: RwLock<HashMap<PathBuf, Option<Arc<Gitignore>>>>,
+}
+
+impl IgnoreRules {
+    /// Compile the global `patterns` (gitignore syntax, one rule per entry)
+    /// Invalid patterns are skipped.
+    pub fn new(roots: &[PathBuf], patterns: &[String]) -> Self {
+        let mut builder = GitignoreBuilder::new("");
+        for pattern in patterns {
+            let _ = builder.add_line(None, pattern);
+        }
+
+        Self {
+            roots: roots.to_vec(),
+            global: builder.build().unwrap_or_else(|_| Gitignore::empty()),
+            folders: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Rules from `dir/.filesenseignore`, if present
+    /// The file is read without holding the cache lock, so parallel walkers don't wait on each other.
+    fn folder_rules(&self, dir: &Path) -> Option<Arc<Gitignore>> {
+        if let Some(cached) = self.folders.read().ok()?.get(dir) {
+            return cached.clone();
+        }
+
+        let file = dir.join(IGNORE_FILE_NAME);
+        let rules = file.is_file().then(|| Gitignore::new(&file).0).filter(|rules| !rules.is_empty()).map(Arc::new);
+        self.folders.write().ok()?.entry(dir.to_path_buf()).or_insert(rules).clone()
+    }
+
+    /// Scan root containing `path` (the deepest one if roots are nested)
+    fn root_for(&self, path: &Path) -> Option<&Path> {
+        self.roots
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .map(PathBuf::as_path)
+    }
+
+    /// Whether `path` itself matches a rule, assuming its parent folders
+    /// have already been checked (as during a directory walk)
+    pub fn matches_entry(&self, path: &Path, is_dir: bool) -> bool {
+        // Folder files apply from the scan root down
+        let Some(parent) = path.parent() else {
+            return self.matched(path, is_dir, &[]);
+        };
+        let root = self.root_for(path).unwrap_or(parent);
+        let mut dirs: Vec<&Path> = parent.ancestors().take_while(|d| d.starts_with(root)).collect();
+        dirs.reverse();
+
+        let folder_rules: Vec<Arc<Gitignore>> = dirs.into_iter().filter_map(|dir| self.folder_rules(dir)).collect();
+        self.matched(path, is_dir, &folder_rules)
+    }
+
+    /// Whether `path` or any folder between it and its scan root is ignored
+    /// For single paths (watcher events); a walk only needs `matches_entry`.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        let Some(root) = self.root_for(path) else {
+            return self.matches_entry(path, is_dir);
+        };
+        if path == root {
+            return self.matched(path, is_dir, &[]);
+        }
+
+        // Each level from the root down, with the folder files above it collected on the way
+        let mut entries: Vec<&Path> = path.ancestors().take_while(|p| *p != root && p.starts_with(root)).collect();
+        entries.reverse();
+        let mut folder_rules: Vec<Arc<Gitignore>> = self.folder_rules(root).into_iter().collect();
+        for entry in entries {
+            let entry_is_dir = entry != path || is_dir;
+            if self.matched(entry, entry_is_dir, &folder_rules) {
+                return true;
+            }
+            if entry != path {
+                folder_rules.extend(self.folder_rules(entry));
+            }
+        }
+        false
+    }
+
+    /// Global rules, then folder rules from the outermost folder in; the deepest match wins
+    fn matched(&self, path: &Path, is_dir: bool, folder_rules: &[Arc<Gitignore>]) -> bool {
+        let mut ignored = self.global.matched(path, is_dir).is_ignore();
+        for rules in folder_rules {
+            match rules.matched(path, is_dir) {
+                Match::Ignore(_) => ignored = true,
+                Match::Whitelist(_) => ignored = false,
+                Match::None => {}
+            }
+        }
+        ignored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    #[test]
+    fn test_default_patterns() {
+        let root = PathBuf::from("/scan");
+        let rules = IgnoreRules::new(std::slice::from_ref(&root), &default_ignore_patterns());
+
+        assert!(rules.is_ignored(&root.join("app/node_modules/pkg/readme.md"), false));
+        assert!(rules.is_ignored(&root.join("repo/.git"), true));
+        assert!(rules.is_ignored(&root.join("report.docx.tmp"), false));
+        assert!(rules.is_ignored(&root.join("~$report.docx"), false));
+        assert!(!rules.is_ignored(&root.join("taxes/2024.pdf"), false));
+        // Directory patterns don't match files of the same name
+        assert!(!rules.is_ignored(&root.join("notes/build"), false));
+    }
+
+    #[test]
+    fn test_folder_ignore_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path().to_path_buf();
+        let project = root.join("project");
+        fs::create_dir_all(project.join("drafts")).unwrap();
+        fs::write(root.join(IGNORE_FILE_NAME), "*.log\n/scratch/\n").unwrap();
+        fs::write(project.join(IGNORE_FILE_NAME), "drafts/\n!keep.log\n").unwrap();
+
+        let rules = IgnoreRules::new(std::slice::from_ref(&root), &[]);
+
+        assert!(rules.is_ignored(&root.join("debug.log"), false));
+        assert!(rules.is_ignored(&root.join("scratch/a.txt"), false));
+        // Anchored patterns are relative to the folder holding the file
+        assert!(!rules.is_ignored(&project.join("scratch/a.txt"), false));
+        assert!(rules.is_ignored(&project.join("drafts/v1.docx"), false));
+        // A deeper file can re-include what a parent ignores
+        assert!(rules.is_ignored(&project.join("other.log"), false));
+        assert!(!rules.is_ignored(&project.join("keep.log"), false));
+        assert!(!rules.is_ignored(&project.join("final.docx"), false));
+    }
+
+    #[test]
+    fn test_entry_match_leaves_folders_to_the_walk() {
+        let root = PathBuf::from("/scan");
+        let rules = IgnoreRules::new(std::slice::from_ref(&root), &default_ignore_patterns());
+        let file = root.join("app/node_modules/pkg/readme.md");
+
+        // A walk prunes node_modules before reaching the file, so only the file itself is checked
+        assert!(!rules.matches_entry(&file, false));
+        assert!(rules.matches_entry(&root.join("app/node_modules"), true));
+        assert!(rules.is_ignored(&file, false));
+    }
+}
@@ -0,0 +1,757 @@
+//! Indexer Module
+//!
+//! Writes to the `files` index shared by full scans (`scan_directories`)
+//! and the filesystem watcher, so both paths keep AI metadata for unchanged
+//! files, clear it for changed ones and clean up related rows on delete.
+
+use crate::document_parser::{DocumentMetadata, ParserRegistry};
+use crate::path_identity::{self, PathIdentity};
+use crate::scanner::{self, CancelToken, ExtractedContent, KnownFile, KnownFiles, ProgressTracker, ScannedFile};
+use rayon::prelude::*;
+use rusqlite::{params, Connection, Result as SqlResult};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// What happened to a file when it was written to the index
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum IndexOutcome {
+    New,
+    Updated,
+    Unchanged,
+}
+
+/// Files written per transaction when indexing a scan
+pub const INDEX_BATCH_SIZE: usize = 500;
+
+/// Per-outcome counts from `index_files`
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct IndexCounts {
+    pub new_files: usize,
+    pub updated_files: usize,
+    pub unchanged_files: usize,
+}
+
+impl IndexCounts {
+    fn record(&mut self, outcome: IndexOutcome) {
+        match outcome {
+            IndexOutcome::New => self.new_files += 1,
+            IndexOutcome::Updated => self.updated_files += 1,
+            IndexOutcome::Unchanged => self.unchanged_files += 1,
+        }
+    }
+
+    /// Files that were new or changed
+    pub fn changed(&self) -> usize {
+        self.new_files + self.updated_files
+    }
+
+    pub fn total(&self) -> usize {
+        self.new_files + self.updated_files + self.unchanged_files
+    }
+}
+
+/// Run `f` inside a transaction, or inside the caller's transaction if one is already open
+/// Batching writes this way avoids an fsync per statement.
+fn in_transaction<T>(conn: &Connection, f: impl FnOnce() -> SqlResult<T>) -> SqlResult<T> {
+    if !conn.is_autocommit() {
+        return f();
+    }
+    let tx = conn.unchecked_transaction()?;
+    let result = f()?;
+    tx.commit()?;
+    Ok(result)
+}
+
+/// Upsert a scanned file (incremental - preserves AI metadata for unchanged files)
+/// A file is unchanged when its quick hash and modified date match the indexed row.
+/// A row spelled differently but naming the same file (per `identity`) is reused
+/// and takes the new spelling.
+pub fn index_file(conn: &Connection, file: &ScannedFile, identity: &PathIdentity) -> SqlResult<IndexOutcome> {
+    let path_str = file.path.to_string_lossy().to_string();
+
+    let lookup = |id: i64| -> SqlResult<(i64, Option<String>, Option<String>)> {
+        conn.prepare_cached("SELECT id, quick_hash, modified_at FROM files WHERE id = ?1")?
+            .query_row([id], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+    };
+    let ids = file_ids_at(conn, &path_str, identity)?;
+    // Extra rows naming the same file are leftovers from other spellings
+    for &duplicate_id in ids.iter().skip(1) {
+        remove_file(conn, duplicate_id);
+    }
+    let existing = match ids.first() {
+        Some(&id) => Some(lookup(id)?),
+        None => None,
+    };
+
+    match existing {
+        None => {
+            conn.prepare_cached(
+                "INSERT INTO files (path, filename, extension, size, created_at, modified_at, quick_hash, detected_type, path_key, last_scanned_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, CURRENT_TIMESTAMP)",
+            )?
+            .execute(params![
+                &path_str,
+                &file.filename,
+                &file.extension,
+                file.size as i64,
+                &file.created_at,
+                &file.modified_at,
+                &file.quick_hash,
+                &file.detected_type,
+                path_identity::index_key(&path_str),
+            ])?;
+            Ok(IndexOutcome::New)
+        }
+        Some((file_id, old_hash, old_modified)) if old_hash == file.quick_hash && old_modified == file.modified_at => {
+            // File unchanged - just update last_scanned_at timestamp (and spelling, and the
+            // detected type, which databases from before detection lack)
+            conn.prepare_cached(
+                "UPDATE files SET path = ?2, path_key = ?4, detected_type = ?3, last_scanned_at = CURRENT_TIMESTAMP
+                 WHERE id = ?1",
+            )?
+            .execute(params![file_id, &path_str, &file.detected_type, path_identity::index_key(&path_str)])?;
+            Ok(IndexOutcome::Unchanged)
+        }
+        Some((file_id, _, _)) => {
+            conn.prepare_cached(
+                "UPDATE files SET
+                     path = ?8,
+                     path_key = ?10,
+                     filename = ?2,
+                     extension = ?3,
+                     size = ?4,
+                     created_at = ?5,
+                     modified_at = ?6,
+                     quick_hash = ?7,
+                     detected_type = ?9,
+                     full_hash = NULL,
+                     last_scanned_at = CURRENT_TIMESTAMP
+                 WHERE id = ?1",
+            )?
+            .execute(params![
+                file_id,
+                &file.filename,
+                &file.extension,
+                file.size as i64,
+                &file.created_at,
+                &file.modified_at,
+                &file.quick_hash,
+                &path_str,
+                &file.detected_type,
+                path_identity::index_key(&path_str),
+            ])?;
+
+            // Clear stale AI metadata for changed files only (not new files)
+            conn.prepare_cached("DELETE FROM ai_metadata WHERE file_id = ?1")?.execute([file_id])?;
+            conn.prepare_cached("DELETE FROM content_snippets WHERE file_id = ?1")?.execute([file_id])?;
+            conn.prepare_cached("DELETE FROM document_metadata WHERE file_id = ?1")?.execute([file_id])?;
+            Ok(IndexOutcome::Updated)
+        }
+    }
+}
+
+/// Index scanned files in transactions of `INDEX_BATCH_SIZE`
+/// Stops between batches when cancelled; batches already written stay committed.
+pub fn index_files(
+    conn: &Connection,
+    files: &[ScannedFile],
+    identity: &PathIdentity,
+    cancel: &CancelToken,
+    progress: &ProgressTracker,
+) -> SqlResult<IndexCounts> {
+    let mut counts = IndexCounts::default();
+    for batch in files.chunks(INDEX_BATCH_SIZE) {
+        if cancel.is_cancelled() {
+            break;
+        }
+        in_transaction(conn, || {
+            for file in batch {
+                counts.record(index_file(conn, file, identity)?);
+            }
+            Ok(())
+        })?;
+        progress.add_processed(batch.len() as u64);
+    }
+    Ok(counts)
+}
+
+/// Store (or replace) the extracted content snippet for a file
+pub fn store_content_snippet(
+    conn: &Connection,
+    file_id: i64,
+    extracted: &ExtractedContent,
+) -> SqlResult<()> {
+    conn.prepare_cached(
+        "INSERT INTO content_snippets (file_id, snippet, extraction_method, extraction_confidence, extracted_at)
+         VALUES (?1, ?2, ?3, ?4, CURRENT_TIMESTAMP)
+         ON CONFLICT(file_id) DO UPDATE SET
+             snippet = excluded.snippet,
+             extraction_method = excluded.extraction_method,
+             extraction_confidence = excluded.extraction_confidence,
+             extracted_at = CURRENT_TIMESTAMP",
+    )?
+    .execute(params![
+        file_id,
+        &extracted.snippet,
+        &extracted.extraction_method,
+        extracted.extraction_confidence,
+    ])?;
+    Ok(())
+}
+
+/// Store (or replace) the metadata a document parser read for a file
+pub fn store_document_metadata(conn: &Connection, file_id: i64, metadata: &DocumentMetadata) -> SqlResult<()> {
+    let keywords = (!metadata.keywords.is_empty()).then(|| metadata.keywords.join(", "));
+    conn.prepare_cached(
+        "INSERT INTO document_metadata (file_id, title, author, subject, keywords, page_count, taken_at,
+                                        camera_make, camera_model, gps_latitude, gps_longitude, width, height, album, duration_secs,
+                                        created_at, extracted_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, CURRENT_TIMESTAMP)
+         ON CONFLICT(file_id) DO UPDATE SET
+             title = excluded.title,
+             author = excluded.author,
+             subject = excluded.subject,
+             keywords = excluded.keywords,
+             page_count = excluded.page_count,
+             taken_at = excluded.taken_at,
+             camera_make = excluded.camera_make,
+             camera_model = excluded.camera_model,
+             gps_latitude = excluded.gps_latitude,
+             gps_longitude = excluded.gps_longitude,
+             width = excluded.width,
+             height = excluded.height,
+             album = excluded.album,
+             duration_secs = excluded.duration_secs,
+             created_at = excluded.created_at,
+             extracted_at = CURRENT_TIMESTAMP",
+    )?
+    .execute(params![
+        file_id,
+        &metadata.title,
+        &metadata.author,
+        &metadata.subject,
+        keywords,
+        metadata.page_count,
+        &metadata.taken_at,
+        &metadata.camera_make,
+        &metadata.camera_model,
+        metadata.gps_latitude,
+        metadata.gps_longitude,
+        metadata.width,
+        metadata.height,
+        &metadata.album,
+        metadata.duration_secs,
+        &metadata.created_at,
+    ])?;
+    Ok(())
+}
+
+/// Extraction stage: new and changed files have no content snippet yet (changed files
+/// had theirs cleared by `index_file`), so extract document text for AI classification.
+/// Only files whose identity key (see `PathIdentity::key`) is in `keys` are processed.
+/// Returns how many snippets came from a document parser rather than the filename fallback.
+pub fn extract_pending_snippets(
+    conn: &Connection,
+    keys: &HashSet<String>,
+    identity: &PathIdentity,
+    registry: &ParserRegistry,
+) -> SqlResult<usize> {
+    extract_pending_snippets_with_progress(conn, keys, identity, registry, &CancelToken::default(), &ProgressTracker::new(&|_| {}))
+}
+
+/// Files extracted in parallel between cancellation checks and database writes
+const EXTRACT_CHUNK_SIZE: usize = 64;
+
+/// `extract_pending_snippets`, extracting on the worker pool with progress and cancellation.
+/// Files left unextracted by a cancel stay pending for the next scan.
+pub fn extract_pending_snippets_with_progress(
+    conn: &Connection,
+    keys: &HashSet<String>,
+    identity: &PathIdentity,
+    registry: &ParserRegistry,
+    cancel: &CancelToken,
+    progress: &ProgressTracker,
+) -> SqlResult<usize> {
+    let pending_extraction: Vec<(i64, String, Option<String>)> = {
+        let mut stmt = conn.prepare(
+            "SELECT f.id, f.path, f.detected_type FROM files f
+             LEFT JOIN content_snippets cs ON f.id = cs.file_id
+             WHERE cs.file_id IS NULL",
+        )?;
+
+        let rows = stmt.query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get(2)?)))?;
+
+        rows.filter_map(|r| r.ok())
+            .filter(|(_, path, _)| keys.contains(&identity.key(path)))
+            .collect()
+    };
+
+    let mut snippets_extracted = 0;
+    for chunk in pending_extraction.chunks(EXTRACT_CHUNK_SIZE) {
+        if cancel.is_cancelled() {
+            break;
+        }
+
+        let extracted: Vec<(i64, ExtractedContent)> = chunk
+            .par_iter()
+            .map(|(file_id, path, detected_type)| {
+                let extracted = scanner::extract_content(
+                    registry,
+                    Path::new(path),
+                    detected_type.as_deref(),
+                    scanner::SNIPPET_MAX_CHARS,
+                );
+                (*file_id, extracted)
+            })
+            .collect();
+
+        in_transaction(conn, || {
+            for (file_id, extracted) in &extracted {
+                store_content_snippet(conn, *file_id, extracted)?;
+                if let Some(metadata) = &extracted.metadata {
+                    store_document_metadata(conn, *file_id, metadata)?;
+                }
+                if extracted.extraction_method != "filename" && extracted.extraction_method != "none" {
+                    snippets_extracted += 1;
+                }
+            }
+            Ok(())
+        })?;
+        progress.add_processed(chunk.len() as u64);
+    }
+
+    Ok(snippets_extracted)
+}
+
+/// Delete file rows and their associated data in bulk (foreign key order)
+/// The ids go through a temp table so each table is cleared with one statement.
+pub fn remove_files(conn: &Connection, file_ids: &[i64]) -> SqlResult<usize> {
+    if file_ids.is_empty() {
+        return Ok(0);
+    }
+
+    in_transaction(conn, || {
+        conn.execute_batch(
+            "CREATE TEMP TABLE IF NOT EXISTS removed_file_ids (id INTEGER PRIMARY KEY);
+             DELETE FROM temp.removed_file_ids;",
+        )?;
+        {
+            let mut insert = conn.prepare_cached("INSERT OR IGNORE INTO temp.removed_file_ids (id) VALUES (?1)")?;
+            for file_id in file_ids {
+                insert.execute([file_id])?;
+            }
+        }
+
+        conn.execute_batch(
+            "DELETE FROM ai_metadata WHERE file_id IN (SELECT id FROM temp.removed_file_ids);
+             DELETE FROM content_snippets WHERE file_id IN (SELECT id FROM temp.removed_file_ids);
+             DELETE FROM document_metadata WHERE file_id IN (SELECT id FROM temp.removed_file_ids);
+             DELETE FROM plan_items WHERE file_id IN (SELECT id FROM temp.removed_file_ids);
+             DELETE FROM move_history WHERE file_id IN (SELECT id FROM temp.removed_file_ids);",
+        )?;
+        let removed = conn.execute("DELETE FROM files WHERE id IN (SELECT id FROM temp.removed_file_ids)", [])?;
+        conn.execute("DELETE FROM temp.removed_file_ids", [])?;
+        Ok(removed)
+    })
+}
+
+/// Delete a file row and its associated data (foreign key order)
+pub fn remove_file(conn: &Connection, file_id: i64) {
+    conn.execute("DELETE FROM ai_metadata WHERE file_id = ?1", [file_id]).ok();
+    conn.execute("DELETE FROM content_snippets WHERE file_id = ?1", [file_id]).ok();
+    conn.execute("DELETE FROM document_metadata WHERE file_id = ?1", [file_id]).ok();
+    conn.execute("DELETE FROM plan_items WHERE file_id = ?1", [file_id]).ok();
+    conn.execute("DELETE FROM move_history WHERE file_id = ?1", [file_id]).ok();
+    conn.execute("DELETE FROM files WHERE id = ?1", [file_id]).ok();
+}
+
+/// `dir` with exactly one trailing platform separator, for matching the paths under it
+/// (so `/home/me/Downloads` never matches `/home/me/Downloads2/report.pdf`)
+pub fn dir_prefix(dir: &str) -> String {
+    format!("{}{}", dir.trim_end_matches(std::path::is_separator), std::path::MAIN_SEPARATOR)
+}
+
+/// Upper bound for strings under a `dir_prefix`: they all sort between the prefix
+/// and the same string with its trailing separator bumped by one
+fn prefix_end(prefix: &str) -> String {
+    format!("{}{}", &prefix[..prefix.len() - 1], (std::path::MAIN_SEPARATOR as u8 + 1) as char)
+}
+
+/// Indexed size, modification time and detected type of the file at `path`, or of
+/// every file under it (as spelled in the index), for `scanner::scan_file` to reuse
+pub fn known_files(conn: &Connection, path: &str) -> SqlResult<KnownFiles> {
+    let prefix = dir_prefix(path);
+    conn.prepare_cached(
+        "SELECT path, size, modified_at, detected_type FROM files
+         WHERE path = ?1 OR (path > ?2 AND path < ?3)",
+    )?
+    .query_map(params![path, &prefix, prefix_end(&prefix)], |row| {
+        Ok((
+            PathBuf::from(row.get::<_, String>(0)?),
+            KnownFile {
+                size: row.get::<_, i64>(1)? as u64,
+                modified_at: row.get(2)?,
+                detected_type: row.get(3)?,
+            },
+        ))
+    })?
+    .collect()
+}
+
+/// Rows that may name `path` or something under it: any case, NFC or NFD
+/// Looked up by the indexed files.path_key (see `path_identity::index_key`);
+/// callers narrow these down with `PathIdentity`.
+fn candidate_rows(conn: &Connection, path: &str, include_children: bool) -> SqlResult<Vec<(i64, String)>> {
+    let key = path_identity::index_key(path);
+    let mut rows: Vec<(i64, String)> = conn
+        .prepare_cached("SELECT id, path FROM files WHERE path_key = ?1")?
+        .query_map([&key], |row| Ok((row.get(0)?, row.get(1)?)))?
+        .collect::<SqlResult<_>>()?;
+
+    if include_children {
+        let prefix = dir_prefix(&key);
+        let end = prefix_end(&prefix);
+        let children = conn
+            .prepare_cached("SELECT id, path FROM files WHERE path_key > ?1 AND path_key < ?2")?
+            .query_map([&prefix, &end], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<SqlResult<Vec<_>>>()?;
+        rows.extend(children);
+    }
+    Ok(rows)
+}
+
+/// Ids of the rows naming the file at `path`, an exact spelling match first
+pub fn file_ids_at(conn: &Connection, path: &str, identity: &PathIdentity) -> SqlResult<Vec<i64>> {
+    let mut rows: Vec<(i64, String)> = candidate_rows(conn, path, false)?
+        .into_iter()
+        .filter(|(_, row_path)| identity.same(row_path, path))
+        .collect();
+    rows.sort_by_key(|(_, row_path)| row_path != path);
+    Ok(rows.into_iter().map(|(id, _)| id).collect())
+}
+
+/// Ids of the file at `path`, or of every file under it when `path` is a directory
+fn file_ids_at_or_under(conn: &Connection, path: &str, identity: &PathIdentity) -> SqlResult<Vec<(i64, String)>> {
+    let mut rows: Vec<(i64, String)> = candidate_rows(conn, path, true)?
+        .into_iter()
+        .filter(|(_, row_path)| identity.is_at_or_under(row_path, path))
+        .collect();
+    rows.sort();
+    rows.dedup();
+    Ok(rows)
+}
+
+/// Remove a deleted file (or every file under a deleted directory) from the index
+pub fn remove_path(conn: &Connection, path: &str, identity: &PathIdentity) -> SqlResult<usize> {
+    let ids: Vec<i64> = file_ids_at_or_under(conn, path, identity)?.into_iter().map(|(id, _)| id).collect();
+    remove_files(conn, &ids)
+}
+
+/// Follow a rename of a file or directory, keeping AI metadata and snippets.
+/// Returns how many rows moved; 0 means the source was not indexed.
+pub fn rename_path(conn: &Connection, from: &str, to: &str, identity: &PathIdentity) -> SqlResult<usize> {
+    let from_components = Path::new(from).components().count();
+    let rows = file_ids_at_or_under(conn, from, identity)?;
+
+    for (file_id, old_path) in &rows {
+        // Re-root the part below `from`, whatever its spelling in the index
+        let new_file: PathBuf = Path::new(to)
+            .components()
+            .chain(Path::new(old_path).components().skip(from_components))
+            .collect();
+        let new_path = new_file.to_string_lossy().to_string();
+
+        // A row already at the destination is stale (the file there was replaced)
+        for other_id in file_ids_at(conn, &new_path, identity)? {
+            if other_id != *file_id {
+                remove_file(conn, other_id);
+            }
+        }
+
+        let filename = new_file
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let extension = new_file.extension().map(|e| e.to_string_lossy().to_lowercase());
+
+        conn.execute(
+            "UPDATE files SET path = ?1, path_key = ?5, filename = ?2, extension = ?3, last_scanned_at = CURRENT_TIMESTAMP
+             WHERE id = ?4",
+            params![&new_path, &filename, &extension, file_id, path_identity::index_key(&new_path)],
+        )?;
+    }
+
+    Ok(rows.len())
+}
+
+/// Remove files that no longer exist in the scanned directories.
+/// Only files within `directories` whose identity key is not in `seen_keys` are deleted.
+pub fn remove_stale_files(
+    conn: &Connection,
+    directories: &[String],
+    identity: &PathIdentity,
+    seen_keys: &HashSet<String>,
+) -> SqlResult<usize> {
+    let mut stale_file_ids = Vec::new();
+    for dir in directories {
+        // Find files under this directory that weren't seen in this scan
+        stale_file_ids.extend(
+            file_ids_at_or_under(conn, dir, identity)?
+                .into_iter()
+                .filter(|(_, path)| !seen_keys.contains(&identity.key(path)))
+                .map(|(id, _)| id),
+        );
+    }
+
+    // One bulk delete for every root
+    remove_files(conn, &stale_file_ids)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+
+    fn setup_test_db() -> (tempfile::TempDir, Connection) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.db");
+        db::init_database(&path).unwrap();
+        let conn = db::open_connection(&path).unwrap();
+        (dir, conn)
+    }
+
+    fn scanned(path: &Path, hash: &str) -> ScannedFile {
+        ScannedFile {
+            path: path.to_path_buf(),
+            filename: path.file_name().unwrap().to_string_lossy().to_string(),
+            extension: path.extension().map(|e| e.to_string_lossy().to_string()),
+            size: 10,
+            created_at: None,
+            modified_at: Some("2024-01-01T00:00:00+00:00".to_string()),
+            quick_hash: Some(hash.to_string()),
+            detected_type: None,
+        }
+    }
+
+    fn classify(conn: &Connection, path: &Path) -> i64 {
+        let id: i64 = conn
+            .query_row("SELECT id FROM files WHERE path = ?1", [path.to_string_lossy()], |row| row.get(0))
+            .unwrap();
+        conn.execute("INSERT INTO ai_metadata (file_id, category) VALUES (?1, 'Money')", [id])
+            .unwrap();
+        id
+    }
+
+    #[test]
+    fn test_index_file_outcomes() {
+        let (dir, conn) = setup_test_db();
+        let identity = PathIdentity::new(&[dir.path().to_path_buf()]);
+        let path = dir.path().join("invoice.pdf");
+
+        assert_eq!(index_file(&conn, &scanned(&path, "a"), &identity).unwrap(), IndexOutcome::New);
+        let id = classify(&conn, &path);
+        assert_eq!(index_file(&conn, &scanned(&path, "a"), &identity).unwrap(), IndexOutcome::Unchanged);
+        let kept: i64 = conn
+            .query_row("SELECT COUNT(*) FROM ai_metadata WHERE file_id = ?1", [id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(kept, 1);
+
+        assert_eq!(index_file(&conn, &scanned(&path, "b"), &identity).unwrap(), IndexOutcome::Updated);
+        let cleared: i64 = conn
+            .query_row("SELECT COUNT(*) FROM ai_metadata WHERE file_id = ?1", [id], |row| row.get(0))
+            .unwrap();
+        assert_eq!(cleared, 0);
+    }
+
+    #[test]
+    fn test_extraction_stores_document_metadata() {
+        let (dir, conn) = setup_test_db();
+        let identity = PathIdentity::new(&[dir.path().to_path_buf()]);
+        let path = dir.path().join("scan.png");
+        // 640x480 PNG header with an Author text chunk
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        for (kind, body) in [(&b"IHDR"[..], &[0, 0, 2, 128, 0, 0, 1, 224, 8, 2, 0, 0, 0][..]), (b"tEXt", b"Author\0Sam")] {
+            png.extend_from_slice(&(body.len() as u32).to_be_bytes());
+            png.extend_from_slice(kind);
+            png.extend_from_slice(body);
+            png.extend_from_slice(&[0; 4]);
+        }
+        std::fs::write(&path, png).unwrap();
+
+        index_file(&conn, &scanned(&path, "a"), &identity).unwrap();
+        let keys = HashSet::from([identity.key(&path.to_string_lossy())]);
+        assert_eq!(extract_pending_snippets(&conn, &keys, &identity, &ParserRegistry::builtin()).unwrap(), 1);
+
+        let (author, width, height): (String, u32, u32) = conn
+            .query_row("SELECT author, width, height FROM document_metadata", [], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?))
+            })
+            .unwrap();
+        assert_eq!((author.as_str(), width, height), ("Sam", 640, 480));
+
+        // A changed file loses its stale metadata until it is extracted again
+        index_file(&conn, &scanned(&path, "b"), &identity).unwrap();
+        let remaining: i64 = conn.query_row("SELECT COUNT(*) FROM document_metadata", [], |row| row.get(0)).unwrap();
+        assert_eq!(remaining, 0);
+    }
+
+    #[test]
+    fn test_index_files_in_batches_and_remove_in_bulk() {
+        let (dir, conn) = setup_test_db();
+        let identity = PathIdentity::new(&[dir.path().to_path_buf()]);
+        let files: Vec<ScannedFile> = (0..INDEX_BATCH_SIZE + 10)
+            .map(|i| scanned(&dir.path().join(format!("doc{}.txt", i)), "a"))
+            .collect();
+        let cancel = CancelToken::default();
+        let progress = ProgressTracker::new(&|_| {});
+
+        let counts = index_files(&conn, &files, &identity, &cancel, &progress).unwrap();
+        assert_eq!(counts.new_files, files.len());
+        assert!(conn.is_autocommit());
+
+        let first = classify(&conn, &files[0].path);
+        let mut changed = files.clone();
+        changed[0].quick_hash = Some("b".to_string());
+        let counts = index_files(&conn, &changed, &identity, &cancel, &progress).unwrap();
+        assert_eq!((counts.updated_files, counts.unchanged_files), (1, files.len() - 1));
+        let cleared: i64 = conn
+            .query_row("SELECT COUNT(*) FROM ai_metadata WHERE file_id = ?1", [first], |row| row.get(0))
+            .unwrap();
+        assert_eq!(cleared, 0);
+
+        let second = classify(&conn, &files[1].path);
+        assert_eq!(remove_files(&conn, &[first, second, second]).unwrap(), 2);
+        let remaining: i64 = conn.query_row("SELECT COUNT(*) FROM files", [], |row| row.get(0)).unwrap();
+        assert_eq!(remaining as usize, files.len() - 2);
+        let orphaned: i64 = conn.query_row("SELECT COUNT(*) FROM ai_metadata", [], |row| row.get(0)).unwrap();
+        assert_eq!(orphaned, 0);
+    }
+
+    #[test]
+    fn test_remove_stale_files_matches_whole_directories() {
+        let (dir, conn) = setup_test_db();
+        let identity = PathIdentity::new(&[dir.path().to_path_buf()]);
+        let root = dir.path().join("Downloads");
+        let kept = root.join("kept.pdf");
+        let gone = root.join("nested").join("gone.pdf");
+        let sibling = dir.path().join("Downloads2").join("other.pdf");
+        for path in [&kept, &gone, &sibling] {
+            index_file(&conn, &scanned(path, "a"), &identity).unwrap();
+        }
+
+        let seen: HashSet<String> = [identity.path_key(&kept)].into_iter().collect();
+        let roots = vec![format!("{}{}", root.to_string_lossy(), std::path::MAIN_SEPARATOR)];
+        assert_eq!(remove_stale_files(&conn, &roots, &identity, &seen).unwrap(), 1);
+
+        let remaining: Vec<String> = {
+            let mut stmt = conn.prepare("SELECT path FROM files ORDER BY path").unwrap();
+            stmt.query_map([], |row| row.get(0)).unwrap().map(|r| r.unwrap()).collect()
+        };
+        assert_eq!(remaining, vec![kept.to_string_lossy().to_string(), sibling.to_string_lossy().to_string()]);
+    }
+
+    #[test]
+    fn test_rename_directory_keeps_metadata() {
+        let (dir, conn) = setup_test_db();
+        let identity = PathIdentity::new(&[dir.path().to_path_buf()]);
+        let old_dir = dir.path().join("Taxes");
+        let file = old_dir.join("w2.pdf");
+        let sibling = dir.path().join("Taxes 2023").join("other.pdf");
+        index_file(&conn, &scanned(&file, "a"), &identity).unwrap();
+        index_file(&conn, &scanned(&sibling, "b"), &identity).unwrap();
+        let id = classify(&conn, &file);
+
+        let new_dir = dir.path().join("Money");
+        let moved = rename_path(&conn, &old_dir.to_string_lossy(), &new_dir.to_string_lossy(), &identity).unwrap();
+        assert_eq!(moved, 1);
+
+        let (path, category): (String, String) = conn
+            .query_row(
+                "SELECT f.path, m.category FROM files f JOIN ai_metadata m ON m.file_id = f.id WHERE f.id = ?1",
+                [id],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .unwrap();
+        assert_eq!(Path::new(&path), new_dir.join("w2.pdf"));
+        assert_eq!(category, "Money");
+
+        // A directory with a shared name prefix is untouched
+        assert_eq!(remove_path(&conn, &new_dir.to_string_lossy(), &identity).unwrap(), 1);
+        let remaining: i64 = conn.query_row("SELECT COUNT(*) FROM files", [], |row| row.get(0)).unwrap();
+        assert_eq!(remaining, 1);
+    }
+
+    #[test]
+    fn test_same_file_in_another_spelling_reuses_row() {
+        let (dir, conn) = setup_test_db();
+        let identity = PathIdentity::new(&[dir.path().to_path_buf()]);
+
+        // NFD (as macOS may report it) and NFC spellings are one file on every volume
+        let nfd = dir.path().join("Re\u{301}sume\u{301}.pdf");
+        let nfc = dir.path().join("R\u{e9}sum\u{e9}.pdf");
+        assert_eq!(index_file(&conn, &scanned(&nfd, "a"), &identity).unwrap(), IndexOutcome::New);
+        assert_eq!(index_file(&conn, &scanned(&nfc, "a"), &identity).unwrap(), IndexOutcome::Unchanged);
+
+        // Names differing only by case are separate files only on case-sensitive volumes
+        let upper = dir.path().join("Report.pdf");
+        let lower = dir.path().join("report.pdf");
+        index_file(&conn, &scanned(&upper, "a"), &identity).unwrap();
+        index_file(&conn, &scanned(&lower, "a"), &identity).unwrap();
+
+        let rows: i64 = conn.query_row("SELECT COUNT(*) FROM files", [], |row| row.get(0)).unwrap();
+        let case_sensitive = crate::path_identity::is_case_sensitive(dir.path());
+        assert_eq!(rows, if case_sensitive { 3 } else { 2 });
+    }
+
+    #[test]
+    fn test_unicode_case_variants_found_on_case_insensitive_roots() {
+        let (_dir, conn) = setup_test_db();
+        let identity = PathIdentity::with_roots(&[("/data", false)]);
+        let path = "/data/\u{c4}rzte/\u{c4}.pdf";
+        conn.execute(
+            "INSERT INTO files (path, filename, size, path_key) VALUES (?1, '\u{c4}.pdf', 1, ?2)",
+            params![path, path_identity::index_key(path)],
+        )
+        .unwrap();
+
+        assert_eq!(file_ids_at(&conn, "/data/\u{e4}rzte/\u{e4}.pdf", &identity).unwrap().len(), 1);
+        assert_eq!(file_ids_at(&conn, "/DATA/A\u{308}rzte/a\u{308}.pdf", &identity).unwrap().len(), 1);
+        assert_eq!(remove_path(&conn, "/data/\u{e4}rzte", &identity).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_path_lookups_use_the_path_key_index() {
+        let (_dir, conn) = setup_test_db();
+        for (sql, params) in [
+            ("SELECT id, path FROM files WHERE path_key = ?1", vec!["/data/\u{e4}rzte"]),
+            ("SELECT id, path FROM files WHERE path_key > ?1 AND path_key < ?2", vec!["/data/\u{e4}rzte/", "/data/\u{e4}rzte0"]),
+        ] {
+            let plan: Vec<String> = conn
+                .prepare(&format!("EXPLAIN QUERY PLAN {}", sql))
+                .unwrap()
+                .query_map(rusqlite::params_from_iter(params), |row| row.get(3))
+                .unwrap()
+                .collect::<SqlResult<_>>()
+                .unwrap();
+            assert!(plan.iter().any(|step| step.contains("idx_files_path_key")), "{:?}", plan);
+        }
+    }
+
+    #[test]
+    fn test_known_files_at_or_under_path() {
+        let (dir, conn) = setup_test_db();
+        let identity = PathIdentity::new(&[dir.path().to_path_buf()]);
+        let inside = dir.path().join("docs").join("a.pdf");
+        let sibling = dir.path().join("docs2").join("b.pdf");
+        let mut file = scanned(&inside, "a");
+        file.detected_type = Some("pdf".to_string());
+        index_file(&conn, &file, &identity).unwrap();
+        index_file(&conn, &scanned(&sibling, "b"), &identity).unwrap();
+
+        let known = known_files(&conn, &dir.path().join("docs").to_string_lossy()).unwrap();
+        assert_eq!(known.len(), 1);
+        assert_eq!(known[&inside].detected_type.as_deref(), Some("pdf"));
+        assert_eq!(known[&inside].size, 10);
+        assert_eq!(known_files(&conn, &inside.to_string_lossy()).unwrap().len(), 1);
+    }
+}
@@ -1,12 +1,19 @@
 mod activity_log;
 mod ai;
+mod auto_organize;
 pub mod category;
 mod commands;
 mod db;
 mod document_parser;
 pub mod document_type;
+mod duplicates;
+mod ignore_rules;
+mod indexer;
+mod path_identity;
 mod recovery;
 mod scanner;
+mod search;
+mod watcher;
 
 // Re-export key types for external use
 pub use category::{Category, normalize_folder};
@@ -139,6 +146,15 @@ pub fn run() {
 
             // Store db path in app state
             app.manage(db::DbPath(db_path));
+            app.manage(scanner::ScanState::default());
+            app.manage(watcher::WatcherState::default());
+            app.manage(auto_organize::AutoOrganizeState::default());
+
+            // Resume auto-organize if it was left enabled; a failure leaves it stopped
+            let auto_config = commands::get_auto_organize_config(app.handle().clone());
+            if auto_config.enabled {
+                commands::start_auto_organize_service(app.handle(), auto_config).ok();
+            }
 
             Ok(())
         })
@@ -146,11 +162,25 @@ pub fn run() {
             commands::get_known_folders,
             commands::count_files_in_directories,
             commands::count_duplicates,
+            commands::find_duplicates,
+            commands::generate_dedupe_plan,
             commands::check_ai_config,
             commands::classify_files,
             commands::get_classification_estimate,
             commands::scan_directories,
+            commands::cancel_scan,
             commands::get_scan_status,
+            commands::start_watcher,
+            commands::stop_watcher,
+            commands::get_watcher_status,
+            commands::get_auto_organize_config,
+            commands::save_auto_organize_config,
+            commands::start_auto_organize,
+            commands::stop_auto_organize,
+            commands::get_auto_organize_status,
+            commands::get_auto_organize_suggestions,
+            commands::approve_auto_organize_suggestions,
+            commands::dismiss_auto_organize_suggestions,
             commands::search_files,
             commands::get_file_details,
             commands::generate_organization_plan,
@@ -158,6 +188,8 @@ pub fn run() {
             commands::undo_last_operation,
             commands::get_settings,
             commands::save_settings,
+            commands::get_ignore_patterns,
+            commands::save_ignore_patterns,
             commands::test_api_connection,
             commands::increment_scan_count,
             commands::get_scan_count,
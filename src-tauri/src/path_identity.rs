@@ -0,0 +1,195 @@
+//! Path Identity Module
+//!
+//! Decides when two path strings name the same file. Paths are compared by
+//! key:
+//! - The key is NFC-normalized, so the NFD names macOS can report match the
+//!   NFC spelling used elsewhere.
+//! - On case-insensitive volumes the key is also lowercased.
+//!
+//! Case sensitivity is detected per scan root rather than assumed per
+//! platform, e.g. a case-sensitive APFS volume or a mounted NTFS drive on Linux.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use unicode_normalization::UnicodeNormalization;
+
+/// Directory entries inspected when looking for a name to probe case sensitivity with
+const PROBE_ENTRIES: usize = 32;
+
+/// Case sensitivity assumed when a volume can't be probed
+pub fn platform_case_sensitive() -> bool {
+    !cfg!(any(target_os = "windows", target_os = "macos"))
+}
+
+/// NFC form of a path string
+pub fn nfc(path: &str) -> String {
+    path.nfc().collect()
+}
+
+fn swap_case(name: &str) -> String {
+    name.chars()
+        .flat_map(|c| {
+            if c.is_lowercase() {
+                c.to_uppercase().collect::<Vec<_>>()
+            } else {
+                c.to_lowercase().collect::<Vec<_>>()
+            }
+        })
+        .collect()
+}
+
+/// Whether two paths resolve to the same file
+#[cfg(unix)]
+fn same_file(a: &Path, b: &Path) -> Option<bool> {
+    use std::os::unix::fs::MetadataExt;
+    let (a, b) = (fs::metadata(a).ok()?, fs::metadata(b).ok()?);
+    Some(a.dev() == b.dev() && a.ino() == b.ino())
+}
+
+#[cfg(not(unix))]
+fn same_file(a: &Path, b: &Path) -> Option<bool> {
+    fs::metadata(a).ok()?;
+    Some(fs::metadata(b).is_ok())
+}
+
+/// Detect whether the volume holding `root` distinguishes names by case
+/// Looks up the case-swapped spelling of an entry inside the root (or of the root
+/// itself); nothing is written to disk.
+pub fn is_case_sensitive(root: &Path) -> bool {
+    let entries = fs::read_dir(root)
+        .into_iter()
+        .flatten()
+        .filter_map(|e| e.ok())
+        .take(PROBE_ENTRIES)
+        .map(|e| e.path());
+
+    // Entries first: the root's own name lives on its parent's volume, which may differ
+    let probe = entries
+        .chain(std::iter::once(root.to_path_buf()))
+        .find_map(|path| {
+            let name = path.file_name()?.to_string_lossy().to_string();
+            let swapped = swap_case(&name);
+            (swapped != name).then(|| (path.clone(), path.with_file_name(swapped)))
+        });
+
+    match probe {
+        Some((path, swapped)) => match fs::symlink_metadata(&swapped) {
+            Ok(_) => !same_file(&path, &swapped).unwrap_or(false),
+            Err(_) => true,
+        },
+        None => platform_case_sensitive(),
+    }
+}
+
+/// Path comparison rules for a set of scan roots
+#[derive(Debug, Clone)]
+pub struct PathIdentity {
+    /// (root's index key, case sensitive)
+    roots: Vec<(String, bool)>,
+}
+
+impl PathIdentity {
+    /// Probe each root's volume for case sensitivity
+    pub fn new(roots: &[PathBuf]) -> Self {
+        Self {
+            roots: roots
+                .iter()
+                .map(|root| (index_key(&root.to_string_lossy()), is_case_sensitive(root)))
+                .collect(),
+        }
+    }
+
+    /// Identity with known case sensitivity per root, without probing
+    #[cfg(test)]
+    pub fn with_roots(roots: &[(&str, bool)]) -> Self {
+        Self {
+            roots: roots.iter().map(|(root, sensitive)| (index_key(root), *sensitive)).collect(),
+        }
+    }
+
+    /// Case sensitivity of the deepest root containing `path`
+    fn case_sensitive_for(&self, path: &str) -> bool {
+        let loose = index_key(path);
+        self.roots
+            .iter()
+            .filter(|(root, _)| is_prefix_dir(&loose, root))
+            .max_by_key(|(root, _)| root.len())
+            .map(|(_, case_sensitive)| *case_sensitive)
+            .unwrap_or_else(platform_case_sensitive)
+    }
+
+    /// Comparison key: equal keys mean the same file
+    pub fn key(&self, path: &str) -> String {
+        let normalized = nfc(path);
+        if self.case_sensitive_for(path) {
+            normalized
+        } else {
+            normalized.to_lowercase()
+        }
+    }
+
+    pub fn path_key(&self, path: &Path) -> String {
+        self.key(&path.to_string_lossy())
+    }
+
+    pub fn same(&self, a: &str, b: &str) -> bool {
+        a == b || self.key(a) == self.key(b)
+    }
+
+    /// Whether `path` is `dir` itself or inside it
+    pub fn is_at_or_under(&self, path: &str, dir: &str) -> bool {
+        is_prefix_dir(&self.key(path), &self.key(dir))
+    }
+}
+
+/// Case- and normalization-insensitive key: the `PathIdentity::key` of a case-insensitive
+/// volume. Stored as files.path_key so every spelling of a path is found by one indexed
+/// lookup, whatever the volume; matches are then compared with the root's own rules.
+pub fn index_key(path: &str) -> String {
+    nfc(path).to_lowercase()
+}
+
+/// Whether `path` equals `dir` or continues it with a separator
+fn is_prefix_dir(path: &str, dir: &str) -> bool {
+    let dir = dir.trim_end_matches(std::path::is_separator);
+    match path.strip_prefix(dir) {
+        Some(rest) => rest.is_empty() || rest.starts_with(std::path::is_separator),
+        None => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detects_case_sensitivity_of_temp_volume() {
+        let dir = tempfile::tempdir().unwrap();
+        fs::write(dir.path().join("Report.pdf"), "a").unwrap();
+        let detected = is_case_sensitive(dir.path());
+        assert_eq!(detected, !dir.path().join("REPORT.PDF").exists());
+    }
+
+    #[test]
+    fn test_keys_follow_root_sensitivity() {
+        let sensitive = PathIdentity { roots: vec![("/data".to_string(), true)] };
+        let insensitive = PathIdentity { roots: vec![("/data".to_string(), false)] };
+
+        assert!(!sensitive.same("/data/Report.pdf", "/data/report.pdf"));
+        assert!(insensitive.same("/data/Report.pdf", "/data/report.pdf"));
+
+        // NFD "é" (e + combining acute) is the same name as NFC "é" either way
+        let nfd_name = "/data/Re\u{301}sume\u{301}.pdf";
+        let nfc_name = "/data/R\u{e9}sum\u{e9}.pdf";
+        assert!(sensitive.same(nfd_name, nfc_name));
+        assert!(insensitive.same(nfd_name, &nfc_name.to_uppercase()));
+    }
+
+    #[test]
+    fn test_at_or_under_matches_whole_components() {
+        let identity = PathIdentity { roots: vec![("/data".to_string(), false)] };
+        assert!(identity.is_at_or_under("/Data/Downloads/a.pdf", "/data/downloads"));
+        assert!(identity.is_at_or_under("/data/downloads", "/data/downloads/"));
+        assert!(!identity.is_at_or_under("/data/Downloads2/a.pdf", "/data/downloads"));
+    }
+}
@@ -1,10 +1,16 @@
-use crate::document_parser;
+use crate::document_parser::{self, ExternalExtractor, ParserRegistry};
+use crate::ignore_rules::{self, IgnoreRules};
+use crate::path_identity::PathIdentity;
+use ignore::{WalkBuilder, WalkState};
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::fs::{self, File};
-use std::io::Read;
+use std::io::{Read, Seek, SeekFrom};
 use std::path::{Path, PathBuf};
-use walkdir::WalkDir;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::time::{Duration, Instant};
 
 /// Represents a discovered file with its metadata
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -15,9 +21,26 @@ pub struct ScannedFile {
     pub size: u64,
     pub created_at: Option<String>,
     pub modified_at: Option<String>,
-    pub content_hash: Option<String>,
+    pub quick_hash: Option<String>,
+    /// Type detected from the file's content, as a canonical extension (see `document_parser::detect_type`)
+    pub detected_type: Option<String>,
 }
 
+/// What the index already holds for a file, so an unchanged file isn't sniffed again
+#[derive(Debug, Clone, PartialEq)]
+pub struct KnownFile {
+    pub size: u64,
+    pub modified_at: Option<String>,
+    pub detected_type: Option<String>,
+}
+
+/// Indexed files by path (see `indexer::known_files`)
+pub type KnownFiles = HashMap<PathBuf, KnownFile>;
+
+/// Folder levels walked below each scan root; the watcher uses the same limit
+/// so it never indexes files a rescan would not visit
+pub const MAX_SCAN_DEPTH: usize = 10;
+
 /// Scan configuration
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ScanConfig {
@@ -26,6 +49,12 @@ pub struct ScanConfig {
     pub max_depth: Option<usize>,
     pub compute_hashes: bool,
     pub extensions_filter: Option<Vec<String>>,
+    /// Global gitignore-style rules; per-folder `.filesenseignore` files add to these
+    #[serde(default = "ignore_rules::default_ignore_patterns")]
+    pub ignore_patterns: Vec<String>,
+    /// User-configured commands that extract text for extra extensions (see `ParserRegistry`)
+    #[serde(default)]
+    pub external_extractors: Vec<ExternalExtractor>,
 }
 
 impl Default for ScanConfig {
@@ -33,74 +62,348 @@ impl Default for ScanConfig {
         Self {
             directories: vec![],
             include_hidden: false,
-            max_depth: Some(10),
+            max_depth: Some(MAX_SCAN_DEPTH),
             compute_hashes: false,
             extensions_filter: None,
+            ignore_patterns: ignore_rules::default_ignore_patterns(),
+            external_extractors: Vec::new(),
+        }
+    }
+}
+
+impl ScanConfig {
+    /// Compile this config's ignore rules for its scan roots
+    pub fn ignore_rules(&self) -> IgnoreRules {
+        IgnoreRules::new(&self.directories, &self.ignore_patterns)
+    }
+
+    /// Path comparison rules for this config's scan roots (probes each volume)
+    pub fn path_identity(&self) -> PathIdentity {
+        PathIdentity::new(&self.directories)
+    }
+
+    /// Scan root containing `path` (the deepest one if roots are nested)
+    pub fn root_for(&self, path: &Path) -> Option<&Path> {
+        self.directories
+            .iter()
+            .filter(|root| path.starts_with(root))
+            .max_by_key(|root| root.components().count())
+            .map(PathBuf::as_path)
+    }
+
+    /// Whether a walk from the scan roots reaches `path` within `max_depth`
+    /// (a root's direct children are at depth 1)
+    pub fn within_max_depth(&self, path: &Path) -> bool {
+        match (self.max_depth, self.root_for(path)) {
+            (Some(max), Some(root)) => path.components().count() - root.components().count() <= max,
+            _ => true,
         }
     }
+
+    /// The built-in document parsers plus this config's external extractors
+    pub fn parser_registry(&self) -> ParserRegistry {
+        ParserRegistry::with_external(&self.external_extractors)
+    }
+}
+
+/// Minimum time between progress reports
+pub const PROGRESS_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Cancellation flag shared between a running scan and whoever may stop it
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Tauri-managed token of the scan in progress (None when idle)
+#[derive(Default)]
+pub struct ScanState(pub Mutex<Option<CancelToken>>);
+
+/// Stage of a scan
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScanPhase {
+    #[default]
+    Discovering,
+    Indexing,
+    Extracting,
+    Done,
+}
+
+/// Snapshot of a running scan, sent to the frontend as "scan-progress" events
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScanProgress {
+    pub phase: ScanPhase,
+    pub files_seen: u64,
+    pub bytes_hashed: u64,
+    /// Files indexed or extracted so far in the current phase
+    pub files_processed: u64,
+    pub current_directory: Option<String>,
+}
+
+/// Shared scan counters, reported through `on_progress` at most every `PROGRESS_INTERVAL`
+pub struct ProgressTracker<'a> {
+    files_seen: AtomicU64,
+    bytes_hashed: AtomicU64,
+    files_processed: AtomicU64,
+    phase: Mutex<ScanPhase>,
+    current_directory: Mutex<Option<String>>,
+    last_report: Mutex<Option<Instant>>,
+    on_progress: &'a (dyn Fn(&ScanProgress) + Sync),
+}
+
+impl<'a> ProgressTracker<'a> {
+    pub fn new(on_progress: &'a (dyn Fn(&ScanProgress) + Sync)) -> Self {
+        Self {
+            files_seen: AtomicU64::new(0),
+            bytes_hashed: AtomicU64::new(0),
+            files_processed: AtomicU64::new(0),
+            phase: Mutex::new(ScanPhase::default()),
+            current_directory: Mutex::new(None),
+            last_report: Mutex::new(None),
+            on_progress,
+        }
+    }
+
+    /// Start a new phase (resets `files_processed` and always reports)
+    pub fn set_phase(&self, phase: ScanPhase) {
+        if let Ok(mut current) = self.phase.lock() {
+            *current = phase;
+        }
+        self.files_processed.store(0, Ordering::Relaxed);
+        self.report(true);
+    }
+
+    pub fn add_processed(&self, count: u64) {
+        self.files_processed.fetch_add(count, Ordering::Relaxed);
+        self.report(false);
+    }
+
+    fn enter_directory(&self, dir: &Path) {
+        if let Ok(mut current) = self.current_directory.lock() {
+            *current = Some(dir.to_string_lossy().to_string());
+        }
+        self.report(false);
+    }
+
+    fn file_seen(&self, bytes_hashed: u64) {
+        self.files_seen.fetch_add(1, Ordering::Relaxed);
+        self.bytes_hashed.fetch_add(bytes_hashed, Ordering::Relaxed);
+        self.report(false);
+    }
+
+    pub fn snapshot(&self) -> ScanProgress {
+        ScanProgress {
+            phase: self.phase.lock().map(|p| *p).unwrap_or_default(),
+            files_seen: self.files_seen.load(Ordering::Relaxed),
+            bytes_hashed: self.bytes_hashed.load(Ordering::Relaxed),
+            files_processed: self.files_processed.load(Ordering::Relaxed),
+            current_directory: self.current_directory.lock().ok().and_then(|d| d.clone()),
+        }
+    }
+
+    /// Report a snapshot if forced or the interval has passed
+    /// Worker threads skip reporting rather than wait while another thread reports.
+    fn report(&self, force: bool) {
+        let guard = if force {
+            self.last_report.lock().ok()
+        } else {
+            self.last_report.try_lock().ok()
+        };
+        let Some(mut last_report) = guard else {
+            return;
+        };
+        if !force && last_report.is_some_and(|at| at.elapsed() < PROGRESS_INTERVAL) {
+            return;
+        }
+        *last_report = Some(Instant::now());
+        (self.on_progress)(&self.snapshot());
+    }
 }
 
 /// Scan directories and collect file metadata
 pub fn scan_directories(config: &ScanConfig) -> Vec<ScannedFile> {
-    let mut files = Vec::new();
+    scan_directories_with_progress(config, &KnownFiles::new(), &CancelToken::default(), &ProgressTracker::new(&|_| {}))
+        .unwrap_or_default()
+}
 
-    for dir in &config.directories {
-        if !dir.exists() {
-            continue;
+/// Scan directories on a thread pool, hashing files as they are found
+/// Returns None if cancelled; results are sorted by path.
+pub fn scan_directories_with_progress(
+    config: &ScanConfig,
+    known: &KnownFiles,
+    cancel: &CancelToken,
+    progress: &ProgressTracker,
+) -> Option<Vec<ScannedFile>> {
+    let roots: Vec<&Path> = config.directories.iter().map(PathBuf::as_path).collect();
+    collect_files(config, &roots, config.max_depth, known, cancel, progress)
+}
+
+/// Scan a folder inside the scan roots, e.g. one the watcher saw appear.
+/// Ignore rules (including `.filesenseignore` files above it) and `max_depth`
+/// apply as if the folder had been reached by walking from its scan root.
+pub fn scan_subtree(config: &ScanConfig, known: &KnownFiles, dir: &Path) -> Vec<ScannedFile> {
+    let Some(root) = config.root_for(dir) else {
+        return Vec::new();
+    };
+    let depth = dir.components().count() - root.components().count();
+    if depth > 0 && config.ignore_rules().is_ignored(dir, true) {
+        return Vec::new();
+    }
+    let max_depth = match config.max_depth {
+        Some(max) if depth >= max => return Vec::new(),
+        Some(max) => Some(max - depth),
+        None => None,
+    };
+
+    collect_files(config, &[dir], max_depth, known, &CancelToken::default(), &ProgressTracker::new(&|_| {}))
+        .unwrap_or_default()
+}
+
+/// Scan the files under `starts`, walking at most `max_depth` levels below them
+fn collect_files(
+    config: &ScanConfig,
+    starts: &[&Path],
+    max_depth: Option<usize>,
+    known: &KnownFiles,
+    cancel: &CancelToken,
+    progress: &ProgressTracker,
+) -> Option<Vec<ScannedFile>> {
+    let (tx, rx) = mpsc::channel();
+    let completed = walk_included_files(config, starts, max_depth, cancel, progress, &|path| {
+        if let Some(scanned) = scan_file(path, config.compute_hashes, known.get(path)) {
+            let hashed = if config.compute_hashes {
+                scanned.size.min(2 * QUICK_HASH_CHUNK)
+            } else {
+                0
+            };
+            progress.file_seen(hashed);
+            let _ = tx.send(scanned);
         }
+    });
+    drop(tx);
 
-        let walker = WalkDir::new(dir)
-            .max_depth(config.max_depth.unwrap_or(usize::MAX))
-            .follow_links(false);
+    if !completed {
+        return None;
+    }
 
-        for entry in walker.into_iter().filter_map(|e| e.ok()) {
-            let path = entry.path();
+    let mut files: Vec<ScannedFile> = rx.into_iter().collect();
+    files.sort_by(|a, b| a.path.cmp(&b.path));
+    Some(files)
+}
 
-            // Skip directories
-            if path.is_dir() {
-                continue;
-            }
+/// Count the files `scan_directories` would index, without reading them
+pub fn count_files(config: &ScanConfig) -> usize {
+    let count = AtomicU64::new(0);
+    let roots: Vec<&Path> = config.directories.iter().map(PathBuf::as_path).collect();
+    walk_included_files(config, &roots, config.max_depth, &CancelToken::default(), &ProgressTracker::new(&|_| {}), &|_| {
+        count.fetch_add(1, Ordering::Relaxed);
+    });
+    count.into_inner() as usize
+}
 
-            // Skip files in "Organized Files" folders - these are already organized
-            let path_str = path.to_string_lossy().to_lowercase();
-            if path_str.contains("organized files") {
-                continue;
-            }
+/// Walk `starts` in parallel, calling `visit` for each file that is not excluded
+/// Ignored directories are pruned instead of walked (e.g. node_modules).
+/// Ignore rules are always those of the config's scan roots, even when
+/// `starts` are folders below them. Returns false if the walk was cancelled.
+fn walk_included_files(
+    config: &ScanConfig,
+    starts: &[&Path],
+    max_depth: Option<usize>,
+    cancel: &CancelToken,
+    progress: &ProgressTracker,
+    visit: &(dyn Fn(&Path) + Sync),
+) -> bool {
+    let starts: Vec<&Path> = starts.iter().copied().filter(|dir| dir.exists()).collect();
+    let Some((first, rest)) = starts.split_first() else {
+        return true;
+    };
 
-            // Skip hidden files unless configured
-            if !config.include_hidden {
-                if let Some(name) = path.file_name() {
-                    if name.to_string_lossy().starts_with('.') {
-                        continue;
-                    }
-                }
+    let rules = Arc::new(config.ignore_rules());
+    let mut builder = WalkBuilder::new(first);
+    for start in rest {
+        builder.add(start);
+    }
+
+    let prune_rules = Arc::clone(&rules);
+    builder
+        .standard_filters(false)
+        .follow_links(false)
+        .max_depth(max_depth)
+        .filter_entry(move |e| {
+            e.depth() == 0 || !e.file_type().is_some_and(|t| t.is_dir()) || !prune_rules.matches_entry(e.path(), true)
+        });
+
+    builder.build_parallel().run(|| {
+        let rules = Arc::clone(&rules);
+        Box::new(move |entry| {
+            if cancel.is_cancelled() {
+                return WalkState::Quit;
             }
+            let Ok(entry) = entry else {
+                return WalkState::Continue;
+            };
 
-            // Filter by extension if configured
-            if let Some(ref allowed) = config.extensions_filter {
-                let ext = path
-                    .extension()
-                    .map(|e| e.to_string_lossy().to_lowercase());
-                if let Some(ref ext) = ext {
-                    if !allowed.iter().any(|a| a.to_lowercase() == *ext) {
-                        continue;
-                    }
-                } else {
-                    continue; // Skip files without extensions
-                }
+            if entry.file_type().is_some_and(|t| t.is_dir()) {
+                progress.enter_directory(entry.path());
+            } else if !entry.path().is_dir() && !is_excluded(entry.path(), config, &rules) {
+                visit(entry.path());
             }
+            WalkState::Continue
+        })
+    });
 
-            if let Some(scanned) = scan_file(path, config.compute_hashes) {
-                files.push(scanned);
+    !cancel.is_cancelled()
+}
+
+/// Whether a file should be left out of the index (already organized, hidden,
+/// filtered out by extension, or matched by an ignore rule)
+/// Its folders are not checked: the walk has already pruned ignored ones.
+pub fn is_excluded(path: &Path, config: &ScanConfig, rules: &IgnoreRules) -> bool {
+    // Skip files in "Organized Files" folders - these are already organized
+    let path_str = path.to_string_lossy().to_lowercase();
+    if path_str.contains("organized files") {
+        return true;
+    }
+
+    // Skip hidden files unless configured
+    if !config.include_hidden {
+        if let Some(name) = path.file_name() {
+            if name.to_string_lossy().starts_with('.') {
+                return true;
             }
         }
     }
 
-    files
+    // Filter by extension if configured
+    if let Some(ref allowed) = config.extensions_filter {
+        let ext = path
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase());
+        // Files without an extension are filtered by their content type; only they are
+        // sniffed here, so filtering doesn't read every file in the tree
+        let ext = ext.or_else(|| document_parser::detect_type(path));
+        match ext {
+            Some(ref ext) if allowed.iter().any(|a| a.to_lowercase() == *ext) => {}
+            _ => return true,
+        }
+    }
+
+    rules.matches_entry(path, false)
 }
 
 /// Scan a single file and extract metadata
-fn scan_file(path: &Path, compute_hash: bool) -> Option<ScannedFile> {
+/// `known` is the file's indexed row, if any: when size and modification time still
+/// match, its detected type is reused instead of reading the file again.
+pub fn scan_file(path: &Path, compute_hash: bool, known: Option<&KnownFile>) -> Option<ScannedFile> {
     let metadata = fs::metadata(path).ok()?;
 
     let filename = path.file_name()?.to_string_lossy().to_string();
@@ -119,12 +422,20 @@ fn scan_file(path: &Path, compute_hash: bool) -> Option<ScannedFile> {
         .ok()
         .and_then(|t| chrono::DateTime::<chrono::Utc>::from(t).to_rfc3339().into());
 
-    let content_hash = if compute_hash {
-        compute_file_hash(path)
+    let quick_hash = if compute_hash {
+        compute_quick_hash(path, metadata.len())
     } else {
         None
     };
 
+    // Files with no recognised type are sniffed again, as are rows from before detection
+    let detected_type = match known {
+        Some(known) if known.size == metadata.len() && known.modified_at == modified_at && known.detected_type.is_some() => {
+            known.detected_type.clone()
+        }
+        _ => document_parser::detect_type(path),
+    };
+
     Some(ScannedFile {
         path: path.to_path_buf(),
         filename,
@@ -132,68 +443,133 @@ fn scan_file(path: &Path, compute_hash: bool) -> Option<ScannedFile> {
         size: metadata.len(),
         created_at,
         modified_at,
-        content_hash,
+        quick_hash,
+        detected_type,
     })
 }
 
-/// Compute SHA-256 hash of file contents (first 1MB only for speed)
-fn compute_file_hash(path: &Path) -> Option<String> {
+/// Bytes read from each end of a file for the quick hash
+pub const QUICK_HASH_CHUNK: u64 = 64 * 1024;
+
+/// Compute a quick change-detection hash: SHA-256 of the size plus the first and last 64KB.
+/// Cheap enough for every scan, but not proof of equality - use `compute_full_hash` for that.
+pub fn compute_quick_hash(path: &Path, size: u64) -> Option<String> {
+    let mut file = File::open(path).ok()?;
+    let mut hasher = Sha256::new();
+    hasher.update(size.to_le_bytes());
+
+    let mut buffer = Vec::with_capacity(QUICK_HASH_CHUNK as usize);
+    (&mut file).take(QUICK_HASH_CHUNK).read_to_end(&mut buffer).ok()?;
+    hasher.update(&buffer);
+
+    if size > QUICK_HASH_CHUNK {
+        // Tail chunk, without re-reading bytes already covered by the head
+        let tail_start = size.saturating_sub(QUICK_HASH_CHUNK).max(QUICK_HASH_CHUNK);
+        file.seek(SeekFrom::Start(tail_start)).ok()?;
+        buffer.clear();
+        file.take(QUICK_HASH_CHUNK).read_to_end(&mut buffer).ok()?;
+        hasher.update(&buffer);
+    }
+
+    Some(hex::encode(hasher.finalize()))
+}
+
+/// Compute SHA-256 hash of the entire file, streamed in 64KB chunks
+/// Expensive on large files, so callers compute it lazily and cache it in files.full_hash
+pub fn compute_full_hash(path: &Path) -> Option<String> {
     let mut file = File::open(path).ok()?;
     let mut hasher = Sha256::new();
-    let mut buffer = vec![0u8; 1024 * 1024]; // 1MB buffer
+    let mut buffer = vec![0u8; 64 * 1024];
 
-    let bytes_read = file.read(&mut buffer).ok()?;
-    hasher.update(&buffer[..bytes_read]);
+    loop {
+        let bytes_read = file.read(&mut buffer).ok()?;
+        if bytes_read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..bytes_read]);
+    }
 
     Some(hex::encode(hasher.finalize()))
 }
 
-/// Extract text snippet from a file for AI classification
-/// Uses the document_parser module for proper content extraction (Phase 1 & 2)
-#[allow(dead_code)]
-pub fn extract_snippet(path: &Path, max_chars: usize) -> Option<String> {
-    let extension = path.extension()?.to_string_lossy().to_lowercase();
+/// Maximum characters of extracted text stored per file (per doc 04: first ~2000 chars)
+pub const SNIPPET_MAX_CHARS: usize = 2000;
+
+/// Result of the extraction stage for a single file, stored in content_snippets
+#[derive(Debug, Clone)]
+pub struct ExtractedContent {
+    pub snippet: Option<String>,
+    pub extraction_method: String,
+    pub extraction_confidence: f64,
+    /// Fields the document parser read (stored in document_metadata)
+    pub metadata: Option<document_parser::DocumentMetadata>,
+}
+
+/// Extract a text snippet from a file for AI classification
+/// Uses the parser the registry has for the file's extension and detected type (Phase 1 & 2)
+pub fn extract_content(
+    registry: &ParserRegistry,
+    path: &Path,
+    detected_type: Option<&str>,
+    max_chars: usize,
+) -> ExtractedContent {
+    let filename = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let extension = path.extension().map(|e| e.to_string_lossy().to_lowercase());
+    let parser = registry.for_file(extension.as_deref(), detected_type);
+    // Label fallback snippets by the extension, or the content type for extensionless files
+    let extension = extension.or(detected_type.map(str::to_string)).unwrap_or_default();
 
     // Check if this file type is supported by our document parsers
-    if document_parser::is_supported_type(&extension) {
-        match document_parser::extract_document_content(path, max_chars) {
-            Ok(parsed) => {
-                // Return extracted content with confidence indicator
-                if parsed.content.is_empty() {
-                    // Fallback to filename if extraction returned empty
-                    Some(format!(
-                        "[{} - no text extracted] Filename: {}",
-                        extension.to_uppercase(),
-                        path.file_name()?.to_string_lossy()
-                    ))
-                } else {
-                    Some(parsed.content)
-                }
-            }
-            Err(_) => {
-                // Extraction failed, use filename fallback
-                Some(format!(
+    if let Some(parser) = parser {
+        match parser.extract(path, max_chars) {
+            Ok(parsed) if !parsed.content.is_empty() => ExtractedContent {
+                snippet: Some(parsed.content),
+                extraction_method: parser.name().to_string(),
+                extraction_confidence: parsed.extraction_confidence,
+                metadata: Some(parsed.metadata),
+            },
+            // Fallback to filename if extraction returned empty
+            Ok(_) => ExtractedContent {
+                snippet: Some(format!(
+                    "[{} - no text extracted] Filename: {}",
+                    extension.to_uppercase(),
+                    filename
+                )),
+                extraction_method: "filename".to_string(),
+                extraction_confidence: 0.1,
+                metadata: None,
+            },
+            // Extraction failed, use filename fallback
+            Err(_) => ExtractedContent {
+                snippet: Some(format!(
                     "[{} Document] Filename: {}",
                     extension.to_uppercase(),
-                    path.file_name()?.to_string_lossy()
-                ))
-            }
+                    filename
+                )),
+                extraction_method: "filename".to_string(),
+                extraction_confidence: 0.0,
+                metadata: None,
+            },
         }
     } else {
         // Unsupported file types - return metadata only
-        match extension.as_str() {
-            // Legacy Office formats (not yet supported - Phase 5)
-            "doc" | "xls" | "ppt" => Some(format!(
-                "[Legacy Office Document] Filename: {}",
-                path.file_name()?.to_string_lossy()
-            )),
-            // Images - return metadata only
-            "jpg" | "jpeg" | "png" | "gif" | "webp" | "bmp" | "tiff" => Some(format!(
-                "[Image] Filename: {}",
-                path.file_name()?.to_string_lossy()
-            )),
+        let snippet = match extension.as_str() {
+            // Images without EXIF/XMP support - filename only
+            "gif" | "webp" | "bmp" => {
+                Some(format!("[Image] Filename: {}", filename))
+            }
             // Unknown types
             _ => None,
+        };
+
+        ExtractedContent {
+            extraction_method: if snippet.is_some() { "filename" } else { "none" }.to_string(),
+            snippet,
+            extraction_confidence: 0.0,
+            metadata: None,
         }
     }
 }
@@ -208,4 +584,145 @@ mod tests {
         assert!(!config.include_hidden);
         assert_eq!(config.max_depth, Some(10));
     }
+
+    #[test]
+    fn test_scan_and_count_apply_ignore_rules() {
+        let dir = tempfile::tempdir().unwrap();
+        let root = dir.path();
+        fs::create_dir_all(root.join("site/node_modules/pkg")).unwrap();
+        fs::create_dir_all(root.join("archive")).unwrap();
+        fs::write(root.join("site/node_modules/pkg/readme.txt"), "x").unwrap();
+        fs::write(root.join("site/notes.txt"), "x").unwrap();
+        fs::write(root.join("draft.tmp"), "x").unwrap();
+        fs::write(root.join("archive/old.txt"), "x").unwrap();
+        fs::write(root.join(ignore_rules::IGNORE_FILE_NAME), "archive/\n").unwrap();
+
+        let config = ScanConfig {
+            directories: vec![root.to_path_buf()],
+            ..ScanConfig::default()
+        };
+
+        let scanned: Vec<String> = scan_directories(&config).into_iter().map(|f| f.filename).collect();
+        assert_eq!(scanned, vec!["notes.txt"]);
+        assert_eq!(count_files(&config), 1);
+    }
+
+    #[test]
+    fn test_scan_reports_progress_and_honours_cancel() {
+        let dir = tempfile::tempdir().unwrap();
+        for i in 0..20 {
+            let sub = dir.path().join(format!("folder{}", i % 4));
+            fs::create_dir_all(&sub).unwrap();
+            fs::write(sub.join(format!("file{}.txt", i)), "hello").unwrap();
+        }
+        let config = ScanConfig {
+            directories: vec![dir.path().to_path_buf()],
+            compute_hashes: true,
+            ..ScanConfig::default()
+        };
+
+        let last = Mutex::new(ScanProgress::default());
+        let on_progress = |p: &ScanProgress| *last.lock().unwrap() = p.clone();
+        let progress = ProgressTracker::new(&on_progress);
+        let files = scan_directories_with_progress(&config, &KnownFiles::new(), &CancelToken::default(), &progress).unwrap();
+        assert_eq!(files.len(), 20);
+        assert!(files.windows(2).all(|w| w[0].path < w[1].path));
+
+        progress.set_phase(ScanPhase::Done);
+        let last = last.lock().unwrap().clone();
+        assert_eq!(last.files_seen, 20);
+        assert_eq!(last.bytes_hashed, 100);
+        assert!(last.current_directory.is_some());
+
+        let cancel = CancelToken::default();
+        cancel.cancel();
+        assert!(scan_directories_with_progress(&config, &KnownFiles::new(), &cancel, &ProgressTracker::new(&|_| {})).is_none());
+    }
+
+    #[test]
+    fn test_extract_content_text_file() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("notes.txt");
+        fs::write(&path, "Quarterly budget review for the marketing team.").unwrap();
+
+        let extracted = extract_content(&ParserRegistry::builtin(), &path, None, SNIPPET_MAX_CHARS);
+        assert_eq!(extracted.extraction_method, "text");
+        assert!(extracted.snippet.unwrap().contains("Quarterly budget"));
+        assert!(extracted.extraction_confidence > 0.0);
+    }
+
+    #[test]
+    fn test_unchanged_file_keeps_known_type() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("minutes");
+        fs::write(&path, r"{\rtf1\ansi Board meeting minutes}").unwrap();
+        let first = scan_file(&path, false, None).unwrap();
+        assert_eq!(first.detected_type.as_deref(), Some("rtf"));
+
+        // Same size and modification time: the stored type is trusted without reading the file
+        let known = KnownFile { size: first.size, modified_at: first.modified_at.clone(), detected_type: Some("pdf".to_string()) };
+        assert_eq!(scan_file(&path, false, Some(&known)).unwrap().detected_type.as_deref(), Some("pdf"));
+
+        let changed = KnownFile { size: first.size + 1, ..known };
+        assert_eq!(scan_file(&path, false, Some(&changed)).unwrap().detected_type.as_deref(), Some("rtf"));
+    }
+
+    #[test]
+    fn test_extensionless_file_filtered_and_parsed_by_content() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("minutes");
+        fs::write(&path, r"{\rtf1\ansi Board meeting minutes}").unwrap();
+        fs::write(dir.path().join("blob"), b"\0\x01\x02").unwrap();
+
+        let config = ScanConfig {
+            directories: vec![dir.path().to_path_buf()],
+            extensions_filter: Some(vec!["rtf".to_string()]),
+            ..Default::default()
+        };
+        let rules = config.ignore_rules();
+        assert!(!is_excluded(&path, &config, &rules));
+        assert!(is_excluded(&dir.path().join("blob"), &config, &rules));
+
+        let scanned = scan_file(&path, false, None).unwrap();
+        assert_eq!(scanned.detected_type.as_deref(), Some("rtf"));
+        let extracted = extract_content(&ParserRegistry::builtin(), &path, scanned.detected_type.as_deref(), SNIPPET_MAX_CHARS);
+        assert_eq!(extracted.extraction_method, "rtf");
+        assert!(extracted.snippet.unwrap().contains("Board meeting"));
+    }
+
+    #[test]
+    fn test_extract_content_unknown_type() {
+        let extracted = extract_content(&ParserRegistry::builtin(), Path::new("archive.bin"), None, SNIPPET_MAX_CHARS);
+        assert_eq!(extracted.extraction_method, "none");
+        assert!(extracted.snippet.is_none());
+    }
+
+    #[test]
+    fn test_quick_hash_covers_size_and_ends() {
+        let dir = tempfile::tempdir().unwrap();
+        let write = |name: &str, data: &[u8]| {
+            let path = dir.path().join(name);
+            fs::write(&path, data).unwrap();
+            let hash = compute_quick_hash(&path, data.len() as u64).unwrap();
+            (path, hash)
+        };
+
+        let data = vec![1u8; 300 * 1024];
+        let (base, base_hash) = write("base.bin", &data);
+
+        let mut tail_changed = data.clone();
+        *tail_changed.last_mut().unwrap() = 2;
+        assert_ne!(write("tail.bin", &tail_changed).1, base_hash);
+
+        let mut appended = data.clone();
+        appended.push(1);
+        assert_ne!(write("appended.bin", &appended).1, base_hash);
+
+        // A change in the middle slips past the quick hash but not the full hash
+        let mut middle_changed = data.clone();
+        middle_changed[150 * 1024] = 2;
+        let (middle, middle_hash) = write("middle.bin", &middle_changed);
+        assert_eq!(middle_hash, base_hash);
+        assert_ne!(compute_full_hash(&middle), compute_full_hash(&base));
+    }
 }
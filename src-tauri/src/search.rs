@@ -0,0 +1,625 @@
+//! Search Module
+//!
+//! Ranked full-text search over the file index.
+//! The files_fts table is kept in sync by triggers (see db.rs); this module
+//! parses user queries (free text plus qualifiers such as `ext:pdf`, `type:pdf`
+//! or `size:>5MB`), turns the text into FTS5 MATCH expressions and runs
+//! bm25-ranked lookups narrowed by the qualifiers.
+
+use crate::category::{normalize_folder, Category};
+use chrono::NaiveDate;
+use rusqlite::types::Value;
+use rusqlite::{params_from_iter, Connection, Result as SqlResult};
+use serde::Serialize;
+
+/// Default number of results returned by a search
+pub const DEFAULT_SEARCH_LIMIT: usize = 50;
+
+/// Markers wrapped around matched terms in highlighted fields
+/// (plain text, so the UI must split on them rather than render as HTML)
+pub const MATCH_START: &str = "<mark>";
+pub const MATCH_END: &str = "</mark>";
+
+/// bm25 column weights: filename, path, category, tags, summary, snippet
+const BM25_WEIGHTS: &str = "10.0, 2.0, 4.0, 6.0, 3.0, 1.0";
+
+/// Comparison operator for numeric qualifiers (`size:>5MB`, `confidence:<0.5`)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Comparison {
+    Less,
+    LessOrEqual,
+    Greater,
+    GreaterOrEqual,
+    Equal,
+}
+
+impl Comparison {
+    fn as_sql(&self) -> &'static str {
+        match self {
+            Comparison::Less => "<",
+            Comparison::LessOrEqual => "<=",
+            Comparison::Greater => ">",
+            Comparison::GreaterOrEqual => ">=",
+            Comparison::Equal => "=",
+        }
+    }
+
+    /// Split a leading operator off a qualifier value; a bare value means ">="
+    fn split(value: &str) -> (Self, &str) {
+        for (prefix, op) in [
+            ("<=", Comparison::LessOrEqual),
+            (">=", Comparison::GreaterOrEqual),
+            ("<", Comparison::Less),
+            (">", Comparison::Greater),
+            ("=", Comparison::Equal),
+        ] {
+            if let Some(rest) = value.strip_prefix(prefix) {
+                return (op, rest);
+            }
+        }
+        (Comparison::GreaterOrEqual, value)
+    }
+}
+
+/// A qualifier that narrows search results, mapped onto files / ai_metadata columns
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchFilter {
+    /// `category:money` (comma-separated values match any)
+    Category(Vec<String>),
+    /// `ext:pdf` (comma-separated values match any)
+    Extension(Vec<String>),
+    /// `type:pdf` - the extension or the type detected from the content (files.detected_type)
+    Type(Vec<String>),
+    /// `tag:tax`
+    Tag(String),
+    /// `after:2023-01-01` - modified on or after the date
+    After(String),
+    /// `before:2024` - modified before the date
+    Before(String),
+    /// `size:>5MB`
+    Size(Comparison, i64),
+    /// `confidence:<0.5`
+    Confidence(Comparison, f64),
+    /// `path:Downloads`
+    Path(String),
+}
+
+/// A parsed search query: free text plus qualifiers
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchQuery {
+    pub text: String,
+    pub filters: Vec<SearchFilter>,
+}
+
+/// Parse a search string such as `tax ext:pdf after:2023 size:>5MB path:"My Docs"`
+/// Unknown qualifiers and values that don't parse are kept as free text.
+pub fn parse_query(input: &str) -> SearchQuery {
+    let mut text_terms = Vec::new();
+    let mut filters = Vec::new();
+
+    for token in tokenize_query(input) {
+        match token.split_once(':').and_then(|(key, value)| parse_filter(key, value)) {
+            Some(filter) => filters.push(filter),
+            None => text_terms.push(token),
+        }
+    }
+
+    SearchQuery {
+        text: text_terms.join(" "),
+        filters,
+    }
+}
+
+/// Split on whitespace, keeping double-quoted sections (`path:"My Documents"`) together
+fn tokenize_query(input: &str) -> Vec<String> {
+    let mut tokens = Vec::new();
+    let mut current = String::new();
+    let mut in_quotes = false;
+
+    for c in input.chars() {
+        match c {
+            '"' => in_quotes = !in_quotes,
+            c if c.is_whitespace() && !in_quotes => {
+                if !current.is_empty() {
+                    tokens.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        tokens.push(current);
+    }
+
+    tokens
+}
+
+/// Parse a single `key:value` qualifier
+fn parse_filter(key: &str, value: &str) -> Option<SearchFilter> {
+    if value.is_empty() {
+        return None;
+    }
+
+    let list = |v: &str| -> Vec<String> {
+        v.split(',').map(|s| s.trim().to_string()).filter(|s| !s.is_empty()).collect()
+    };
+
+    match key.to_lowercase().as_str() {
+        "category" | "cat" => Some(SearchFilter::Category(
+            list(value).iter().map(|c| canonical_category(c)).collect(),
+        )),
+        "ext" | "extension" => Some(SearchFilter::Extension(
+            list(value)
+                .iter()
+                .map(|e| e.trim_start_matches('.').to_lowercase())
+                .collect(),
+        )),
+        "type" => Some(SearchFilter::Type(
+            list(value)
+                .iter()
+                .map(|e| e.trim_start_matches('.').to_lowercase())
+                .collect(),
+        )),
+        "tag" => Some(SearchFilter::Tag(value.to_string())),
+        "after" | "since" => parse_date(value).map(SearchFilter::After),
+        "before" | "until" => parse_date(value).map(SearchFilter::Before),
+        "size" => {
+            let (op, rest) = Comparison::split(value);
+            parse_size(rest).map(|bytes| SearchFilter::Size(op, bytes))
+        }
+        "confidence" | "conf" => {
+            let (op, rest) = Comparison::split(value);
+            rest.parse::<f64>().ok().map(|c| SearchFilter::Confidence(op, c))
+        }
+        "path" | "in" => Some(SearchFilter::Path(value.to_string())),
+        _ => None,
+    }
+}
+
+/// Map a category qualifier onto the canonical category name (accepts synonyms like "dinero")
+/// Unrecognised names are kept as typed rather than collapsing to Review
+fn canonical_category(value: &str) -> String {
+    let category = normalize_folder(value);
+    if category != Category::Review || value.eq_ignore_ascii_case("review") {
+        category.as_str().to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+/// Parse `YYYY`, `YYYY-MM` or `YYYY-MM-DD` into the start of that period (`YYYY-MM-DD`)
+/// so it compares correctly against RFC 3339 timestamps in files.modified_at
+fn parse_date(value: &str) -> Option<String> {
+    let full = match value.len() {
+        4 => format!("{}-01-01", value),
+        7 => format!("{}-01", value),
+        _ => value.to_string(),
+    };
+    NaiveDate::parse_from_str(&full, "%Y-%m-%d")
+        .ok()
+        .map(|d| d.format("%Y-%m-%d").to_string())
+}
+
+/// Parse a human-readable size (`500`, `200KB`, `1.5MB`, `2GB`) into bytes
+fn parse_size(value: &str) -> Option<i64> {
+    let value = value.trim().to_uppercase();
+    let split = value
+        .find(|c: char| !(c.is_ascii_digit() || c == '.'))
+        .unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: f64 = number.parse().ok()?;
+
+    let multiplier: f64 = match unit.trim() {
+        "" | "B" => 1.0,
+        "K" | "KB" => 1024.0,
+        "M" | "MB" => 1024.0 * 1024.0,
+        "G" | "GB" => 1024.0 * 1024.0 * 1024.0,
+        "T" | "TB" => 1024.0 * 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+
+    Some((number * multiplier) as i64)
+}
+
+/// Escape `%`, `_` and `\` so a value matches literally inside a LIKE pattern
+fn like_contains(value: &str) -> String {
+    let escaped = value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
+
+/// Build the SQL conditions for a set of filters
+/// Returns ` AND ...` clauses using numbered parameters starting at `first_param`
+fn filters_sql(filters: &[SearchFilter], first_param: usize) -> (String, Vec<Value>) {
+    let mut sql = String::new();
+    let mut values: Vec<Value> = Vec::new();
+
+    let next = |v: Value, values: &mut Vec<Value>| -> String {
+        values.push(v);
+        format!("?{}", first_param + values.len() - 1)
+    };
+
+    for filter in filters {
+        let clause = match filter {
+            SearchFilter::Category(names) => {
+                let placeholders: Vec<String> = names
+                    .iter()
+                    .map(|n| next(Value::Text(n.clone()), &mut values))
+                    .collect();
+                format!(
+                    "COALESCE(m.category, 'Review') COLLATE NOCASE IN ({})",
+                    placeholders.join(", ")
+                )
+            }
+            SearchFilter::Extension(exts) => {
+                let placeholders: Vec<String> = exts
+                    .iter()
+                    .map(|e| next(Value::Text(e.clone()), &mut values))
+                    .collect();
+                format!("LOWER(f.extension) IN ({})", placeholders.join(", "))
+            }
+            SearchFilter::Type(types) => {
+                let placeholders: Vec<String> = types
+                    .iter()
+                    .map(|t| next(Value::Text(t.clone()), &mut values))
+                    .collect();
+                let placeholders = placeholders.join(", ");
+                format!("(LOWER(f.extension) IN ({0}) OR f.detected_type IN ({0}))", placeholders)
+            }
+            SearchFilter::Tag(tag) => format!(
+                "m.tags LIKE {} ESCAPE '\\'",
+                next(Value::Text(like_contains(tag)), &mut values)
+            ),
+            SearchFilter::After(date) => format!(
+                "f.modified_at >= {}",
+                next(Value::Text(date.clone()), &mut values)
+            ),
+            SearchFilter::Before(date) => format!(
+                "f.modified_at < {}",
+                next(Value::Text(date.clone()), &mut values)
+            ),
+            SearchFilter::Size(op, bytes) => format!(
+                "f.size {} {}",
+                op.as_sql(),
+                next(Value::Integer(*bytes), &mut values)
+            ),
+            SearchFilter::Confidence(op, confidence) => format!(
+                "m.confidence {} {}",
+                op.as_sql(),
+                next(Value::Real(*confidence), &mut values)
+            ),
+            SearchFilter::Path(fragment) => format!(
+                "f.path LIKE {} ESCAPE '\\'",
+                next(Value::Text(like_contains(fragment)), &mut values)
+            ),
+        };
+        sql.push_str(" AND ");
+        sql.push_str(&clause);
+    }
+
+    (sql, values)
+}
+
+/// Search result item
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchResult {
+    pub id: i64,
+    pub path: String,
+    pub filename: String,
+    pub category: Option<String>,
+    pub tags: Option<String>,
+    pub summary: Option<String>,
+    pub previous_path: Option<String>,
+    pub confidence: Option<f64>,
+    /// Filename with matched terms wrapped in MATCH_START/MATCH_END
+    pub highlighted_filename: Option<String>,
+    /// Best-matching excerpt across all indexed fields
+    pub match_snippet: Option<String>,
+    /// bm25 score (lower is more relevant); None for substring fallback matches
+    pub rank: Option<f64>,
+}
+
+/// Build an FTS5 MATCH expression from free text
+/// Every word becomes a quoted prefix term, so user input can never inject FTS5 syntax
+/// and "inv" matches "invoice". Returns None when the query has no searchable words.
+pub fn build_match_expression(query: &str) -> Option<String> {
+    let terms: Vec<String> = query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|t| !t.is_empty())
+        .map(|t| format!("\"{}\"*", t))
+        .collect();
+
+    if terms.is_empty() {
+        None
+    } else {
+        Some(terms.join(" "))
+    }
+}
+
+/// Ranked full-text search using the FTS5 index, narrowed by filters
+pub fn search_fts(
+    conn: &Connection,
+    text: &str,
+    filters: &[SearchFilter],
+    limit: usize,
+) -> SqlResult<Vec<SearchResult>> {
+    let match_expr = match build_match_expression(text) {
+        Some(expr) => expr,
+        None => return Ok(Vec::new()),
+    };
+
+    let (filter_sql, filter_values) = filters_sql(filters, 5);
+    let sql = format!(
+        "SELECT f.id, f.path, f.filename, m.category, m.tags, m.summary, m.confidence,
+                highlight(files_fts, 0, ?3, ?4),
+                snippet(files_fts, -1, ?3, ?4, '…', 16),
+                bm25(files_fts, {}) AS rank
+         FROM files_fts
+         JOIN files f ON f.id = files_fts.rowid
+         LEFT JOIN ai_metadata m ON f.id = m.file_id
+         WHERE files_fts MATCH ?1{}
+         ORDER BY rank
+         LIMIT ?2",
+        BM25_WEIGHTS, filter_sql
+    );
+
+    let mut values = vec![
+        Value::Text(match_expr),
+        Value::Integer(limit as i64),
+        Value::Text(MATCH_START.to_string()),
+        Value::Text(MATCH_END.to_string()),
+    ];
+    values.extend(filter_values);
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values), |row| {
+        Ok(SearchResult {
+            id: row.get(0)?,
+            path: row.get(1)?,
+            filename: row.get(2)?,
+            category: row.get(3)?,
+            tags: row.get(4)?,
+            summary: row.get(5)?,
+            previous_path: None,
+            confidence: row.get(6)?,
+            highlighted_filename: row.get(7)?,
+            match_snippet: row.get(8)?,
+            rank: row.get(9)?,
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Unranked lookup over files / ai_metadata
+/// With text, matches it as a substring of filename, tags or summary, which catches partial
+/// words in the middle of a token (e.g. "voice" in "invoice") that FTS prefixes miss.
+/// Without text, lists files matching the filters alone, most recently modified first.
+pub fn search_substring(
+    conn: &Connection,
+    text: &str,
+    filters: &[SearchFilter],
+    limit: usize,
+) -> SqlResult<Vec<SearchResult>> {
+    let mut values = vec![Value::Integer(limit as i64)];
+    let text_sql = if text.is_empty() {
+        "1 = 1".to_string()
+    } else {
+        values.push(Value::Text(like_contains(text)));
+        "(f.filename LIKE ?2 ESCAPE '\\' OR m.tags LIKE ?2 ESCAPE '\\' OR m.summary LIKE ?2 ESCAPE '\\')"
+            .to_string()
+    };
+
+    let (filter_sql, filter_values) = filters_sql(filters, values.len() + 1);
+    values.extend(filter_values);
+
+    let sql = format!(
+        "SELECT f.id, f.path, f.filename, m.category, m.tags, m.summary, m.confidence
+         FROM files f
+         LEFT JOIN ai_metadata m ON f.id = m.file_id
+         WHERE {}{}
+         ORDER BY f.modified_at DESC
+         LIMIT ?1",
+        text_sql, filter_sql
+    );
+
+    let mut stmt = conn.prepare(&sql)?;
+    let rows = stmt.query_map(params_from_iter(values), |row| {
+        Ok(SearchResult {
+            id: row.get(0)?,
+            path: row.get(1)?,
+            filename: row.get(2)?,
+            category: row.get(3)?,
+            tags: row.get(4)?,
+            summary: row.get(5)?,
+            previous_path: None,
+            confidence: row.get(6)?,
+            highlighted_filename: None,
+            match_snippet: None,
+            rank: None,
+        })
+    })?;
+
+    rows.collect()
+}
+
+/// Search the index with a query string that may contain qualifiers
+/// Ranked FTS first; substring fallback if nothing matched; filter-only queries list matches
+pub fn search(conn: &Connection, input: &str, limit: usize) -> SqlResult<Vec<SearchResult>> {
+    let query = parse_query(input);
+
+    if query.text.is_empty() {
+        if query.filters.is_empty() {
+            return Ok(Vec::new());
+        }
+        return search_substring(conn, "", &query.filters, limit);
+    }
+
+    let results = search_fts(conn, &query.text, &query.filters, limit)?;
+    if !results.is_empty() {
+        return Ok(results);
+    }
+    search_substring(conn, &query.text, &query.filters, limit)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use rusqlite::params;
+
+    fn setup_test_db() -> (tempfile::TempDir, Connection) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.db");
+        db::init_database(&path).unwrap();
+        let conn = db::open_connection(&path).unwrap();
+        (dir, conn)
+    }
+
+    fn insert_file(conn: &Connection, path: &str, filename: &str) -> i64 {
+        conn.execute(
+            "INSERT INTO files (path, filename, extension, size) VALUES (?1, ?2, 'pdf', 100)",
+            params![path, filename],
+        )
+        .unwrap();
+        conn.last_insert_rowid()
+    }
+
+    #[test]
+    fn test_build_match_expression() {
+        assert_eq!(build_match_expression("tax 2023"), Some("\"tax\"* \"2023\"*".to_string()));
+        assert_eq!(build_match_expression("w-2 \"form\""), Some("\"w\"* \"2\"* \"form\"*".to_string()));
+        assert_eq!(build_match_expression("  -- "), None);
+    }
+
+    #[test]
+    fn test_index_follows_scan_classify_move_delete() {
+        let (_dir, conn) = setup_test_db();
+        let id = insert_file(&conn, "/docs/scan_001.pdf", "scan_001.pdf");
+
+        // Content snippet and classification become searchable
+        conn.execute(
+            "INSERT INTO content_snippets (file_id, snippet) VALUES (?1, 'Electricity bill for March')",
+            [id],
+        )
+        .unwrap();
+        conn.execute(
+            "INSERT INTO ai_metadata (file_id, category, tags, summary) VALUES (?1, 'Home', 'utilities', 'Power bill')",
+            [id],
+        )
+        .unwrap();
+        assert_eq!(search_fts(&conn, "electricity", &[], 10).unwrap().len(), 1);
+        assert_eq!(search_fts(&conn, "utilities", &[], 10).unwrap().len(), 1);
+
+        // Moving the file updates the indexed path
+        conn.execute("UPDATE files SET path = '/organized/Home/scan_001.pdf' WHERE id = ?1", [id])
+            .unwrap();
+        assert_eq!(search_fts(&conn, "organized", &[], 10).unwrap().len(), 1);
+
+        // Deleting the file removes it from the index
+        conn.execute("DELETE FROM files WHERE id = ?1", [id]).unwrap();
+        assert!(search_fts(&conn, "electricity", &[], 10).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_filename_matches_rank_first_with_highlight() {
+        let (_dir, conn) = setup_test_db();
+        let invoice = insert_file(&conn, "/docs/invoice_acme.pdf", "invoice_acme.pdf");
+        let other = insert_file(&conn, "/docs/notes.pdf", "notes.pdf");
+        conn.execute(
+            "INSERT INTO content_snippets (file_id, snippet) VALUES (?1, 'Mentions an invoice once')",
+            [other],
+        )
+        .unwrap();
+
+        let results = search_fts(&conn, "invoice", &[], 10).unwrap();
+        assert_eq!(results.len(), 2);
+        assert_eq!(results[0].id, invoice);
+        assert_eq!(
+            results[0].highlighted_filename.as_deref(),
+            Some("<mark>invoice</mark>_acme.pdf")
+        );
+    }
+
+    #[test]
+    fn test_substring_fallback() {
+        let (_dir, conn) = setup_test_db();
+        insert_file(&conn, "/docs/invoice.pdf", "invoice.pdf");
+
+        let results = search(&conn, "voice", 10).unwrap();
+        assert_eq!(results.len(), 1);
+        assert!(results[0].rank.is_none());
+    }
+
+    #[test]
+    fn test_parse_query_qualifiers() {
+        let query = parse_query(
+            "tax return category:dinero ext:.PDF,docx tag:irs after:2023 size:>5MB confidence:<0.5 path:\"My Docs\"",
+        );
+        assert_eq!(query.text, "tax return");
+        assert_eq!(
+            query.filters,
+            vec![
+                SearchFilter::Category(vec!["Money".to_string()]),
+                SearchFilter::Extension(vec!["pdf".to_string(), "docx".to_string()]),
+                SearchFilter::Tag("irs".to_string()),
+                SearchFilter::After("2023-01-01".to_string()),
+                SearchFilter::Size(Comparison::Greater, 5 * 1024 * 1024),
+                SearchFilter::Confidence(Comparison::Less, 0.5),
+                SearchFilter::Path("My Docs".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_parse_query_keeps_invalid_qualifiers_as_text() {
+        let query = parse_query("size:huge after:someday https://example.com");
+        assert!(query.filters.is_empty());
+        assert_eq!(query.text, "size:huge after:someday https://example.com");
+    }
+
+    #[test]
+    fn test_parse_size() {
+        assert_eq!(parse_size("500"), Some(500));
+        assert_eq!(parse_size("1.5KB"), Some(1536));
+        assert_eq!(parse_size("2gb"), Some(2 * 1024 * 1024 * 1024));
+        assert_eq!(parse_size("5 parsecs"), None);
+    }
+
+    #[test]
+    fn test_filters_narrow_results() {
+        let (_dir, conn) = setup_test_db();
+        let big = insert_file(&conn, "/home/Downloads/tax_2023.pdf", "tax_2023.pdf");
+        let small = insert_file(&conn, "/home/Documents/tax_2022.pdf", "tax_2022.pdf");
+        conn.execute("UPDATE files SET size = 10485760, modified_at = '2023-04-01T00:00:00+00:00' WHERE id = ?1", [big])
+            .unwrap();
+        conn.execute("UPDATE files SET modified_at = '2022-04-01T00:00:00+00:00' WHERE id = ?1", [small])
+            .unwrap();
+        conn.execute(
+            "INSERT INTO ai_metadata (file_id, category, tags, confidence) VALUES (?1, 'Money', 'taxes, irs', 0.4)",
+            [big],
+        )
+        .unwrap();
+
+        let ids = |q: &str| -> Vec<i64> { search(&conn, q, 10).unwrap().iter().map(|r| r.id).collect() };
+
+        assert_eq!(ids("tax").len(), 2);
+        assert_eq!(ids("tax size:>5MB"), vec![big]);
+        assert_eq!(ids("tax after:2023-01-01"), vec![big]);
+        assert_eq!(ids("tax before:2023"), vec![small]);
+        assert_eq!(ids("tax path:Documents"), vec![small]);
+        assert_eq!(ids("category:money confidence:<0.5"), vec![big]);
+        assert_eq!(ids("tag:irs"), vec![big]);
+        assert_eq!(ids("category:review"), vec![small]);
+        assert!(ids("tax ext:docx").is_empty());
+
+        // A PDF saved without its extension is found by type, not by ext
+        conn.execute("UPDATE files SET extension = NULL, detected_type = 'pdf' WHERE id = ?1", [small]).unwrap();
+        assert_eq!(ids("tax ext:pdf"), vec![big]);
+        let mut by_type = ids("tax type:pdf");
+        by_type.sort();
+        assert_eq!(by_type, vec![big, small]);
+    }
+}
@@ -0,0 +1,495 @@
+//! Watcher Module
+//!
+//! Keeps the file index current between scans (per doc 08, "File Watcher").
+//! Filesystem events for the scan roots are collected into batches and
+//! debounced; each batch then upserts, renames or deletes rows in `files`
+//! through the indexer, the same way `scan_directories` does. If events
+//! overflow (the OS dropped some, or too many paths piled up), the batch
+//! falls back to a full rescan of the roots instead.
+
+use crate::ignore_rules::IgnoreRules;
+use crate::indexer::{self, IndexOutcome};
+use crate::path_identity::PathIdentity;
+use crate::scanner::{self, CancelToken, ProgressTracker, ScanConfig};
+use notify::event::{ModifyKind, RenameMode};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rusqlite::{Connection, Result as SqlResult};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{self, Receiver, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+/// Quiet period after the last event before a batch is applied
+pub const DEBOUNCE_DELAY: Duration = Duration::from_secs(2);
+
+/// Longest a batch may wait during a continuous burst of events
+pub const MAX_BATCH_DELAY: Duration = Duration::from_secs(30);
+
+/// Pending paths above which a batch is replaced by a full rescan
+pub const MAX_PENDING_PATHS: usize = 5_000;
+
+/// Watcher status reported to the UI
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct WatcherStatus {
+    pub running: bool,
+    pub roots: Vec<String>,
+    pub events_received: u64,
+    pub files_indexed: u64,
+    pub files_removed: u64,
+    pub files_renamed: u64,
+    pub rescans: u64,
+    pub last_sync_at: Option<String>,
+    pub last_error: Option<String>,
+}
+
+/// Tauri-managed handle to the running watcher (None when stopped)
+#[derive(Default)]
+pub struct WatcherState(pub Mutex<Option<FileWatcher>>);
+
+/// Changes collected since the last flush
+#[derive(Debug, Default)]
+pub struct EventBatch {
+    /// Paths created, modified or removed; the action is decided from disk at flush time
+    pub touched: BTreeSet<PathBuf>,
+    pub renames: Vec<(PathBuf, PathBuf)>,
+    pub rescan: bool,
+    /// Source of a rename reported as two events (Windows, macOS)
+    rename_from: Option<PathBuf>,
+    first_event_at: Option<Instant>,
+    last_event_at: Option<Instant>,
+}
+
+impl EventBatch {
+    pub fn is_empty(&self) -> bool {
+        self.touched.is_empty() && self.renames.is_empty() && !self.rescan
+    }
+
+    /// Add a watcher event (or watcher error) to the batch
+    pub fn record(&mut self, event: notify::Result<Event>) {
+        let now = Instant::now();
+        self.first_event_at.get_or_insert(now);
+        self.last_event_at = Some(now);
+
+        let event = match event {
+            Ok(event) => event,
+            Err(_) => {
+                self.rescan = true;
+                return;
+            }
+        };
+
+        if event.need_rescan() {
+            self.rescan = true;
+        }
+
+        match event.kind {
+            EventKind::Access(_) => {}
+            EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+                self.renames.push((event.paths[0].clone(), event.paths[1].clone()));
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::From)) => {
+                // Treated as a removal unless the matching "to" event follows
+                for path in &event.paths {
+                    self.touched.insert(path.clone());
+                    self.rename_from = Some(path.clone());
+                }
+            }
+            EventKind::Modify(ModifyKind::Name(RenameMode::To)) => {
+                for path in event.paths {
+                    match self.rename_from.take() {
+                        Some(from) => {
+                            self.touched.remove(&from);
+                            self.renames.push((from, path));
+                        }
+                        None => {
+                            self.touched.insert(path);
+                        }
+                    }
+                }
+            }
+            _ => self.touched.extend(event.paths),
+        }
+
+        if self.touched.len() + self.renames.len() > MAX_PENDING_PATHS {
+            self.rescan = true;
+        }
+        if self.rescan {
+            // A rescan covers everything, so stop tracking individual paths
+            self.touched.clear();
+            self.renames.clear();
+        }
+    }
+
+    /// When the batch should be applied (None while empty)
+    pub fn flush_deadline(&self) -> Option<Instant> {
+        let first = self.first_event_at?;
+        let last = self.last_event_at?;
+        Some((last + DEBOUNCE_DELAY).min(first + MAX_BATCH_DELAY))
+    }
+}
+
+/// Counts from applying one batch
+#[derive(Debug, Default, PartialEq)]
+pub struct BatchResult {
+    pub indexed: usize,
+    pub removed: usize,
+    pub renamed: usize,
+    pub rescanned: bool,
+}
+
+/// Index every file under `dir`, recording the identity keys of the paths seen
+/// `dir` is a scan root or a folder below one; ignore rules and `max_depth`
+/// are applied relative to its scan root.
+fn index_tree(
+    conn: &Connection,
+    config: &ScanConfig,
+    identity: &PathIdentity,
+    dir: &Path,
+    seen_keys: &mut HashSet<String>,
+) -> SqlResult<usize> {
+    let known = indexer::known_files(conn, &dir.to_string_lossy())?;
+    let files = scanner::scan_subtree(config, &known, dir);
+    seen_keys.extend(files.iter().map(|file| identity.path_key(&file.path)));
+    let counts = indexer::index_files(conn, &files, identity, &CancelToken::default(), &ProgressTracker::new(&|_| {}))?;
+    Ok(counts.changed())
+}
+
+/// Whether a walk from the scan roots would skip this directory entirely
+fn is_pruned_dir(dir: &Path, config: &ScanConfig, rules: &IgnoreRules) -> bool {
+    let is_root = config.directories.iter().any(|root| root == dir);
+    !is_root && (rules.is_ignored(dir, true) || !config.within_max_depth(dir))
+}
+
+/// Whether a walk from the scan roots would leave this file out
+/// Unlike during a walk, the folders above a changed file haven't been checked yet.
+fn is_excluded_file(path: &Path, config: &ScanConfig, rules: &IgnoreRules) -> bool {
+    scanner::is_excluded(path, config, rules) || rules.is_ignored(path, false) || !config.within_max_depth(path)
+}
+
+/// Write a batch of changes to the index
+pub fn apply_batch(conn: &Connection, config: &ScanConfig, batch: &EventBatch) -> SqlResult<BatchResult> {
+    let mut result = BatchResult::default();
+    let mut seen_keys = HashSet::new();
+    let rules = config.ignore_rules();
+    let identity = config.path_identity();
+
+    if batch.rescan {
+        for dir in &config.directories {
+            result.indexed += index_tree(conn, config, &identity, dir, &mut seen_keys)?;
+        }
+        let roots: Vec<String> = config
+            .directories
+            .iter()
+            .map(|d| d.to_string_lossy().to_string())
+            .collect();
+        result.removed = indexer::remove_stale_files(conn, &roots, &identity, &seen_keys)?;
+        result.rescanned = true;
+        indexer::extract_pending_snippets(conn, &seen_keys, &identity, &config.parser_registry())?;
+        return Ok(result);
+    }
+
+    let mut touched = batch.touched.clone();
+    for (from, to) in &batch.renames {
+        let from_str = from.to_string_lossy();
+        let excluded = if to.is_dir() {
+            is_pruned_dir(to, config, &rules)
+        } else {
+            to.is_file() && is_excluded_file(to, config, &rules)
+        };
+        if excluded {
+            result.removed += indexer::remove_path(conn, &from_str, &identity)?;
+            continue;
+        }
+
+        let moved = indexer::rename_path(conn, &from_str, &to.to_string_lossy(), &identity)?;
+        if moved == 0 {
+            // Moved in from outside the index: treat as new
+            touched.insert(to.clone());
+        } else {
+            result.renamed += moved;
+        }
+    }
+
+    for path in &touched {
+        if path.is_dir() {
+            if !is_pruned_dir(path, config, &rules) {
+                result.indexed += index_tree(conn, config, &identity, path, &mut seen_keys)?;
+            }
+        } else if path.is_file() && !is_excluded_file(path, config, &rules) {
+            let known = indexer::known_files(conn, &path.to_string_lossy())?;
+            if let Some(file) = scanner::scan_file(path, config.compute_hashes, known.get(path)) {
+                seen_keys.insert(identity.path_key(&file.path));
+                if indexer::index_file(conn, &file, &identity)? != IndexOutcome::Unchanged {
+                    result.indexed += 1;
+                }
+            }
+        } else {
+            result.removed += indexer::remove_path(conn, &path.to_string_lossy(), &identity)?;
+        }
+    }
+
+    indexer::extract_pending_snippets(conn, &seen_keys, &identity, &config.parser_registry())?;
+    Ok(result)
+}
+
+/// A running filesystem watcher; dropping the notify watcher ends the worker thread
+pub struct FileWatcher {
+    watcher: Option<RecommendedWatcher>,
+    worker: Option<JoinHandle<()>>,
+    status: Arc<Mutex<WatcherStatus>>,
+}
+
+impl FileWatcher {
+    /// Start watching the scan roots in `config.directories`
+    pub fn start(db_path: PathBuf, config: ScanConfig) -> Result<Self, String> {
+        let (tx, rx) = mpsc::channel();
+        let mut watcher = notify::recommended_watcher(move |event| {
+            let _ = tx.send(event);
+        })
+        .map_err(|e| format!("Could not start file watcher: {}", e))?;
+
+        for dir in &config.directories {
+            watcher
+                .watch(dir, RecursiveMode::Recursive)
+                .map_err(|e| format!("Could not watch {}: {}", dir.display(), e))?;
+        }
+
+        let status = Arc::new(Mutex::new(WatcherStatus {
+            running: true,
+            roots: config
+                .directories
+                .iter()
+                .map(|d| d.to_string_lossy().to_string())
+                .collect(),
+            ..Default::default()
+        }));
+
+        let worker_status = Arc::clone(&status);
+        let worker = std::thread::spawn(move || run_worker(rx, db_path, config, worker_status));
+
+        Ok(Self {
+            watcher: Some(watcher),
+            worker: Some(worker),
+            status,
+        })
+    }
+
+    pub fn status(&self) -> WatcherStatus {
+        self.status.lock().map(|s| s.clone()).unwrap_or_default()
+    }
+
+    /// Stop watching; pending events are flushed before the worker exits
+    pub fn stop(mut self) -> WatcherStatus {
+        self.watcher.take();
+        if let Some(worker) = self.worker.take() {
+            let _ = worker.join();
+        }
+        let mut status = self.status();
+        status.running = false;
+        status
+    }
+}
+
+/// Worker loop: collect events, flush debounced batches, exit when the watcher is dropped
+fn run_worker(
+    rx: Receiver<notify::Result<Event>>,
+    db_path: PathBuf,
+    config: ScanConfig,
+    status: Arc<Mutex<WatcherStatus>>,
+) {
+    let mut batch = EventBatch::default();
+
+    loop {
+        let received = match batch.flush_deadline() {
+            Some(deadline) => rx.recv_timeout(deadline.saturating_duration_since(Instant::now())),
+            None => rx.recv().map_err(|_| RecvTimeoutError::Disconnected),
+        };
+
+        match received {
+            Ok(event) => {
+                batch.record(event);
+                if let Ok(mut s) = status.lock() {
+                    s.events_received += 1;
+                }
+            }
+            Err(RecvTimeoutError::Timeout) => {
+                flush(&db_path, &config, std::mem::take(&mut batch), &status);
+            }
+            Err(RecvTimeoutError::Disconnected) => {
+                flush(&db_path, &config, batch, &status);
+                break;
+            }
+        }
+    }
+}
+
+/// Apply a batch and record the outcome in the shared status
+fn flush(db_path: &PathBuf, config: &ScanConfig, batch: EventBatch, status: &Mutex<WatcherStatus>) {
+    if batch.is_empty() {
+        return;
+    }
+
+    let result = crate::db::open_connection(db_path).and_then(|conn| apply_batch(&conn, config, &batch));
+
+    if let Ok(mut s) = status.lock() {
+        match result {
+            Ok(r) => {
+                s.files_indexed += r.indexed as u64;
+                s.files_removed += r.removed as u64;
+                s.files_renamed += r.renamed as u64;
+                if r.rescanned {
+                    s.rescans += 1;
+                }
+                s.last_sync_at = Some(chrono::Utc::now().to_rfc3339());
+                s.last_error = None;
+            }
+            Err(e) => s.last_error = Some(e.to_string()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db;
+    use notify::event::{CreateKind, Flag};
+
+    fn setup_test_db() -> (tempfile::TempDir, Connection) {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("test.db");
+        db::init_database(&path).unwrap();
+        let conn = db::open_connection(&path).unwrap();
+        (dir, conn)
+    }
+
+    fn watch_config(root: &Path) -> ScanConfig {
+        ScanConfig {
+            directories: vec![root.to_path_buf()],
+            compute_hashes: true,
+            ..ScanConfig::default()
+        }
+    }
+
+    fn indexed_paths(conn: &Connection) -> Vec<String> {
+        let mut stmt = conn.prepare("SELECT path FROM files ORDER BY path").unwrap();
+        stmt.query_map([], |row| row.get(0)).unwrap().map(|r| r.unwrap()).collect()
+    }
+
+    #[test]
+    fn test_batch_pairs_split_renames() {
+        let mut batch = EventBatch::default();
+        batch.record(Ok(Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::From))).add_path("/w/a.txt".into())));
+        batch.record(Ok(Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::To))).add_path("/w/b.txt".into())));
+        batch.record(Ok(Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::From))).add_path("/w/gone.txt".into())));
+
+        assert_eq!(batch.renames, vec![(PathBuf::from("/w/a.txt"), PathBuf::from("/w/b.txt"))]);
+        assert!(batch.touched.contains(Path::new("/w/gone.txt")));
+        assert!(!batch.touched.contains(Path::new("/w/a.txt")));
+        assert!(batch.flush_deadline().is_some());
+    }
+
+    #[test]
+    fn test_batch_overflow_falls_back_to_rescan() {
+        let mut batch = EventBatch::default();
+        batch.record(Ok(Event::new(EventKind::Create(CreateKind::File)).add_path("/w/a.txt".into())));
+        batch.record(Ok(Event::new(EventKind::Other).set_flag(Flag::Rescan)));
+        assert!(batch.rescan);
+        assert!(batch.touched.is_empty());
+
+        let mut batch = EventBatch::default();
+        for i in 0..=MAX_PENDING_PATHS {
+            batch.record(Ok(Event::new(EventKind::Create(CreateKind::File)).add_path(format!("/w/{}.txt", i).into())));
+        }
+        assert!(batch.rescan);
+        assert!(batch.touched.is_empty());
+    }
+
+    #[test]
+    fn test_apply_batch_upserts_renames_and_deletes() {
+        let (dir, conn) = setup_test_db();
+        let root = dir.path().join("watched");
+        std::fs::create_dir_all(&root).unwrap();
+        let config = watch_config(&root);
+
+        let notes = root.join("notes.txt");
+        std::fs::write(&notes, "meeting notes").unwrap();
+        let mut batch = EventBatch::default();
+        batch.touched.insert(notes.clone());
+        assert_eq!(apply_batch(&conn, &config, &batch).unwrap().indexed, 1);
+
+        let renamed = root.join("minutes.txt");
+        std::fs::rename(&notes, &renamed).unwrap();
+        let mut batch = EventBatch::default();
+        batch.renames.push((notes.clone(), renamed.clone()));
+        assert_eq!(apply_batch(&conn, &config, &batch).unwrap().renamed, 1);
+        assert_eq!(indexed_paths(&conn), vec![renamed.to_string_lossy().to_string()]);
+
+        std::fs::remove_file(&renamed).unwrap();
+        let mut batch = EventBatch::default();
+        batch.touched.insert(renamed.clone());
+        assert_eq!(apply_batch(&conn, &config, &batch).unwrap().removed, 1);
+        assert!(indexed_paths(&conn).is_empty());
+    }
+
+    #[test]
+    fn test_apply_batch_rescan_syncs_roots() {
+        let (dir, conn) = setup_test_db();
+        let root = dir.path().join("watched");
+        std::fs::create_dir_all(root.join("sub")).unwrap();
+        std::fs::write(root.join("a.txt"), "a").unwrap();
+        std::fs::write(root.join("sub").join("b.txt"), "b").unwrap();
+
+        let batch = EventBatch {
+            rescan: true,
+            ..Default::default()
+        };
+        let result = apply_batch(&conn, &watch_config(&root), &batch).unwrap();
+        assert!(result.rescanned);
+        assert_eq!(result.indexed, 2);
+        assert_eq!(indexed_paths(&conn).len(), 2);
+
+        // Files deleted while unwatched are dropped by the next rescan
+        std::fs::remove_file(root.join("sub").join("b.txt")).unwrap();
+        let result = apply_batch(&conn, &watch_config(&root), &batch).unwrap();
+        assert_eq!(result.removed, 1);
+        assert_eq!(indexed_paths(&conn), vec![root.join("a.txt").to_string_lossy().to_string()]);
+    }
+
+    #[test]
+    fn test_apply_batch_skips_ignored_directories() {
+        let (dir, conn) = setup_test_db();
+        let root = dir.path().join("watched");
+        let modules = root.join("node_modules");
+        let drafts = root.join("project").join("drafts");
+        std::fs::create_dir_all(&modules).unwrap();
+        std::fs::create_dir_all(&drafts).unwrap();
+        std::fs::write(modules.join("x.js"), "module.exports = {}").unwrap();
+        std::fs::write(drafts.join("v1.txt"), "draft").unwrap();
+        std::fs::write(root.join(crate::ignore_rules::IGNORE_FILE_NAME), "drafts/\n").unwrap();
+
+        // A folder created (or moved in) under the root is checked against the root's rules
+        let mut batch = EventBatch::default();
+        batch.touched.insert(modules.clone());
+        batch.touched.insert(modules.join("x.js"));
+        batch.touched.insert(drafts.clone());
+        assert_eq!(apply_batch(&conn, &watch_config(&root), &batch).unwrap().indexed, 0);
+        assert!(indexed_paths(&conn).is_empty());
+
+        // max_depth counts from the scan root, not from the changed folder
+        let deep = root.join("a").join("b");
+        std::fs::create_dir_all(&deep).unwrap();
+        std::fs::write(deep.join("deep.txt"), "deep").unwrap();
+        std::fs::write(root.join("a").join("shallow.txt"), "shallow").unwrap();
+        let config = ScanConfig {
+            max_depth: Some(2),
+            ..watch_config(&root)
+        };
+        let mut batch = EventBatch::default();
+        batch.touched.insert(root.join("a"));
+        assert_eq!(apply_batch(&conn, &config, &batch).unwrap().indexed, 1);
+        assert_eq!(indexed_paths(&conn), vec![root.join("a").join("shallow.txt").to_string_lossy().to_string()]);
+    }
+}
@@ -13,6 +13,7 @@ import { Card, CardContent } from '@/components/ui/card';
 import { Progress } from '@/components/ui/progress';
 import { Stepper, ORGANIZATION_STEPS } from '@/components/Stepper';
 import { invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import {
   Loader2,
   Pause,
@@ -35,6 +36,18 @@ interface ClassificationProgress {
   estimated_credits: number;
 }
 
+// Type for scan progress events from backend ("scan-progress")
+interface BackendScanProgress {
+  phase: 'discovering' | 'indexing' | 'extracting' | 'done';
+  files_seen: number;
+  bytes_hashed: number;
+  files_processed: number;
+  current_directory: string | null;
+}
+
+// Give up if the backend reports no progress for this long
+const SCAN_STALL_TIMEOUT_MS = 60000;
+
 // Type for scanned files result
 interface ScannedFile {
   path: string;
@@ -48,6 +61,7 @@ interface ScanResult {
   unchanged_files: number;
   deleted_files: number;
   total_files: number;
+  snippets_extracted: number;
   files: ScannedFile[];
 }
 
@@ -93,6 +107,9 @@ export function ScanningScreen() {
   const [simulatedProgress, setSimulatedProgress] = useState(0);
   const [discoveryComplete, setDiscoveryComplete] = useState(false);
 
+  // Live progress reported by the backend while scanning
+  const [scanActivity, setScanActivity] = useState<BackendScanProgress | null>(null);
+
   // Track when no files are found (for friendly empty state)
   const [noFilesFound, setNoFilesFound] = useState(false);
 
@@ -240,19 +257,37 @@ export function ScanningScreen() {
         // Get folder paths to scan
         const paths = state.selectedFolders.map(f => f.path);
 
+        // Follow backend progress; each event also resets the stall timeout
+        let lastProgressAt = Date.now();
+        const unlisten = await listen<BackendScanProgress>('scan-progress', (event) => {
+          lastProgressAt = Date.now();
+          setScanActivity(event.payload);
+        });
+
         // Add timeout to prevent hanging forever
+        let stallCheck: ReturnType<typeof setInterval> | undefined;
         const timeoutPromise = new Promise<never>((_, reject) => {
-          setTimeout(() => reject(new Error('Scan timed out after 60 seconds. Make sure you are running with "npm run tauri dev".')), 60000);
+          stallCheck = setInterval(() => {
+            if (Date.now() - lastProgressAt > SCAN_STALL_TIMEOUT_MS) {
+              reject(new Error('Scan stopped responding for 60 seconds. Make sure you are running with "npm run tauri dev".'));
+            }
+          }, 5000);
         });
 
         // Call Tauri backend to scan with extension filter (incremental scan)
-        const scanResult = await Promise.race([
-          invoke<ScanResult>('scan_directories', {
-            directories: paths,
-            extensions: state.selectedExtensions.length > 0 ? state.selectedExtensions : null,
-          }),
-          timeoutPromise,
-        ]);
+        let scanResult: ScanResult;
+        try {
+          scanResult = await Promise.race([
+            invoke<ScanResult>('scan_directories', {
+              directories: paths,
+              extensions: state.selectedExtensions.length > 0 ? state.selectedExtensions : null,
+            }),
+            timeoutPromise,
+          ]);
+        } finally {
+          clearInterval(stallCheck);
+          unlisten();
+        }
 
         const fileCount = scanResult.total_files;
 
@@ -278,6 +313,8 @@ export function ScanningScreen() {
         await runAIClassification(fileCount);
 
       } catch (error) {
+        // Cancelling rejects the scan; that's not an error to show
+        if (isCancelledRef.current) return;
         console.error('[Scanning] Error:', error);
         const errorMsg = error instanceof Error ? error.message : String(error);
         dispatch({ type: 'SET_ERROR', error: errorMsg });
@@ -290,6 +327,7 @@ export function ScanningScreen() {
   // Handle cancel
   const handleCancel = () => {
     isCancelledRef.current = true;
+    invoke('cancel_scan').catch(error => console.error('[Scanning] Cancel error:', error));
     cancelScan();
   };
 
@@ -431,13 +469,18 @@ export function ScanningScreen() {
           </h1>
           <p className="text-muted-foreground">
             {isDiscoveryPhase
-              ? t('scanning.filesFound', { count: progress?.filesFound || 0 })
+              ? t('scanning.filesFound', { count: progress?.filesFound || scanActivity?.files_seen || 0 })
               : t('scanning.analyzing', {
                   current: progress?.filesAnalyzed || 0,
                   total: progress?.totalFiles || 0
                 })
             }
           </p>
+          {isDiscoveryPhase && scanActivity?.current_directory && (
+            <p className="text-xs text-muted-foreground truncate" title={scanActivity.current_directory}>
+              {scanActivity.current_directory}
+            </p>
+          )}
         </div>
 
         {/* Progress bar */}
@@ -25,6 +25,38 @@ interface SearchResult {
   tags?: string[];
   summary?: string;
   modified_at: string;
+  highlighted_filename?: string;
+  match_snippet?: string;
+}
+
+const MATCH_START = '<mark>';
+const MATCH_END = '</mark>';
+
+/** Render text whose matched terms are wrapped in <mark> markers, without parsing it as HTML */
+function Highlighted({ text }: { text: string }) {
+  const parts: React.ReactNode[] = [];
+  let rest = text;
+  let key = 0;
+  while (rest.length > 0) {
+    const start = rest.indexOf(MATCH_START);
+    if (start === -1) {
+      parts.push(rest);
+      break;
+    }
+    const end = rest.indexOf(MATCH_END, start + MATCH_START.length);
+    if (end === -1) {
+      parts.push(rest.replace(MATCH_START, ''));
+      break;
+    }
+    if (start > 0) parts.push(rest.slice(0, start));
+    parts.push(
+      <mark key={key++} className="bg-yellow-200 text-foreground rounded-sm px-0.5">
+        {rest.slice(start + MATCH_START.length, end)}
+      </mark>
+    );
+    rest = rest.slice(end + MATCH_END.length);
+  }
+  return <>{parts}</>;
 }
 
 export function SearchScreen() {
@@ -165,7 +197,9 @@ export function SearchScreen() {
                         <FileText className="h-5 w-5 text-muted-foreground" />
                       </div>
                       <div className="flex-1 min-w-0">
-                        <h3 className="font-medium truncate">{result.filename}</h3>
+                        <h3 className="font-medium truncate">
+                          <Highlighted text={result.highlighted_filename ?? result.filename} />
+                        </h3>
                         <div className="flex items-center gap-1 text-sm text-muted-foreground mt-1">
                           <Folder className="h-3 w-3" />
                           <span className="truncate selectable">{result.current_path}</span>
@@ -175,6 +209,11 @@ export function SearchScreen() {
                             {t('search.previousLocation')}: {result.previous_path}
                           </p>
                         )}
+                        {result.match_snippet && (
+                          <p className="text-sm text-muted-foreground mt-2 line-clamp-2">
+                            <Highlighted text={result.match_snippet} />
+                          </p>
+                        )}
                         {result.summary && (
                           <p className="text-sm text-muted-foreground mt-2 line-clamp-2">
                             {result.summary}
@@ -18,9 +18,18 @@ interface SettingsScreenProps {
   onClose: () => void;
 }
 
+interface ExternalExtractor {
+  name: string;
+  extensions: string[];
+  command: string;
+  args?: string[];
+}
+
 interface AppSettings {
   anthropic_api_key?: string;
   anthropic_model?: string;
+  scans_used?: number;
+  external_extractors?: ExternalExtractor[];
 }
 
 export function SettingsScreen({ onClose }: SettingsScreenProps) {
@@ -33,12 +42,15 @@ export function SettingsScreen({ onClose }: SettingsScreenProps) {
   const [testError, setTestError] = useState<string | null>(null);
   const [hasChanges, setHasChanges] = useState(false);
   const [originalKey, setOriginalKey] = useState('');
+  // Fields this screen doesn't edit are saved back unchanged
+  const [loadedSettings, setLoadedSettings] = useState<AppSettings>({});
 
   // Load existing settings on mount
   useEffect(() => {
     const loadSettings = async () => {
       try {
         const settings = await invoke<AppSettings>('get_settings');
+        setLoadedSettings(settings);
         if (settings.anthropic_api_key) {
           setApiKey(settings.anthropic_api_key);
           setOriginalKey(settings.anthropic_api_key);
@@ -63,6 +75,7 @@ export function SettingsScreen({ onClose }: SettingsScreenProps) {
     try {
       await invoke('save_settings', {
         settings: {
+          ...loadedSettings,
           anthropic_api_key: apiKey || null,
           anthropic_model: null,
         },